@@ -0,0 +1,6 @@
+#!/usr/bin/env bash
+#MISE quiet=true
+
+set -euo pipefail
+
+cargo run --quiet -- cache reindex "$@"
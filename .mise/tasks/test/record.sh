@@ -0,0 +1,6 @@
+#!/usr/bin/env bash
+#MISE quiet=true
+
+set -euo pipefail
+
+CASSETTE_MODE=record cargo test --quiet integration_tests:: "$@"
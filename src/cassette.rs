@@ -0,0 +1,429 @@
+//! Record-and-replay of the HTTP interactions for the integration tests
+//!
+//! A [`CassetteServer`] stands in for an API host. In replay mode (the default), it serves the interactions from a cassette file under `fixtures/cassettes`, and [`CassetteServer::finish`] fails if a request had no matching interaction or if an interaction was never requested. In record mode (`CASSETTE_MODE=record`), it forwards the requests to the real API and saves the interactions when the test calls [`CassetteServer::finish`].
+//!
+//! The committed cassettes have been assembled from the responses in `fixtures/` (run the tests with `CASSETTE_MODE=record` to replace them with real recordings). The tests that only need the recorded pages use [`start_mock_clob_server`](crate::start_mock_clob_server) instead.
+
+use crate::{MockHttpRequest, MockHttpResponse, MockHttpServer, MockHttpServerStartError, ParseEnvVarError, parse_env_var};
+use core::str::FromStr;
+use errgonomic::{handle, handle_bool};
+use reqwest::Client;
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{File, create_dir_all};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use strum::{Display, EnumString, ParseError as StrumParseError};
+use thiserror::Error;
+use url::form_urlencoded;
+
+pub const CASSETTE_MODE_ENV: &str = "CASSETTE_MODE";
+
+pub const CASSETTES_DIR: &str = "fixtures/cassettes";
+
+#[derive(Display, EnumString, Default, Eq, PartialEq, Hash, Copy, Clone, Debug)]
+#[strum(serialize_all = "snake_case")]
+pub enum CassetteMode {
+    #[default]
+    Replay,
+    Record,
+}
+
+impl CassetteMode {
+    pub fn from_env() -> Result<Self, ParseEnvVarError<StrumParseError>> {
+        parse_env_var(CASSETTE_MODE_ENV, |value| Self::from_str(value.trim())).map(Option::unwrap_or_default)
+    }
+}
+
+/// A single request and the response to it
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct CassetteInteraction {
+    pub method: String,
+    /// The path and the query
+    pub target: String,
+    pub request_body: String,
+    pub status: u16,
+    /// The raw body (not parsed, so that the replayed response is byte-for-byte identical to the recorded one)
+    pub response_body: String,
+}
+
+impl CassetteInteraction {
+    /// Returns true if the request has the same method, target and body
+    ///
+    /// The query params are compared regardless of their order and percent-encoding, and the bodies are compared as JSON values if both of them are valid JSON.
+    pub fn is_match(&self, request: &MockHttpRequest) -> bool {
+        self.method == request.method && normalize_target(&self.target) == normalize_target(&request.target) && is_same_body(&self.request_body, &request.body)
+    }
+
+    pub fn response(&self) -> MockHttpResponse {
+        MockHttpResponse::new(self.status, self.response_body.clone()).with_header(CONTENT_TYPE.as_str(), "application/json")
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Default, Clone, Debug)]
+pub struct Cassette {
+    pub interactions: Vec<CassetteInteraction>,
+}
+
+impl Cassette {
+    pub fn path(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join(CASSETTES_DIR)
+            .join(format!("{name}.json"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CassetteLoadError> {
+        use CassetteLoadError::*;
+        let file = handle!(File::open(path), OpenFailed, path: path.to_path_buf());
+        let cassette = handle!(serde_json::from_reader(BufReader::new(file)), DeserializeFailed, path: path.to_path_buf());
+        Ok(cassette)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), CassetteSaveError> {
+        use CassetteSaveError::*;
+        if let Some(dir) = path.parent() {
+            handle!(create_dir_all(dir), CreateDirAllFailed, dir: dir.to_path_buf());
+        }
+        let file = handle!(File::create(path), CreateFailed, path: path.to_path_buf());
+        let mut writer = BufWriter::new(file);
+        handle!(serde_json::to_writer_pretty(&mut writer, self), SerializeFailed, path: path.to_path_buf());
+        handle!(writer.write_all(b"\n"), WriteAllFailed, path: path.to_path_buf());
+        handle!(writer.flush(), FlushFailed, path: path.to_path_buf());
+        Ok(())
+    }
+}
+
+/// The state of a cassette that is being replayed
+#[derive(Default, Clone, Debug)]
+struct CassetteReplay {
+    cassette: Cassette,
+    used: Vec<bool>,
+    /// The requests without a matching interaction (formatted as `{method} {target}`)
+    unmatched: Vec<String>,
+}
+
+impl CassetteReplay {
+    fn new(cassette: Cassette) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        Self {
+            cassette,
+            used,
+            unmatched: vec![],
+        }
+    }
+
+    /// Returns the response of the first unused matching interaction, and marks it as used
+    ///
+    /// If all matching interactions are used, the last one is repeated (e.g. when a command polls the same target). If no interaction matches, the request is recorded as unmatched, and the response is 404.
+    fn respond(&mut self, request: &MockHttpRequest) -> MockHttpResponse {
+        let matching = self
+            .cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_index, interaction)| interaction.is_match(request))
+            .map(|(index, _interaction)| index)
+            .collect::<Vec<usize>>();
+        let index_opt = matching
+            .iter()
+            .find(|index| !self.used.get(**index).copied().unwrap_or_default())
+            .or(matching.last())
+            .copied();
+        match index_opt.and_then(|index| Some((index, self.cassette.interactions.get(index)?))) {
+            Some((index, interaction)) => {
+                if let Some(is_used) = self.used.get_mut(index) {
+                    *is_used = true;
+                }
+                interaction.response()
+            }
+            None => {
+                let request_line = format!("{} {}", request.method, request.target);
+                let response = MockHttpResponse::new(404, format!("the cassette has no interaction for '{request_line}'"));
+                self.unmatched.push(request_line);
+                response
+            }
+        }
+    }
+
+    /// Returns the interactions that were never requested (formatted as `{method} {target}`)
+    fn unused_interactions(&self) -> Vec<String> {
+        self.cassette
+            .interactions
+            .iter()
+            .zip(&self.used)
+            .filter(|(_interaction, is_used)| !**is_used)
+            .map(|(interaction, _is_used)| format!("{} {}", interaction.method, interaction.target))
+            .collect()
+    }
+}
+
+/// Returns the path and the sorted decoded query params, so that the targets that differ only in the order or in the percent-encoding of the query params are equal
+fn normalize_target(target: &str) -> (&str, Vec<(String, String)>) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut params = form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect::<Vec<(String, String)>>();
+    params.sort();
+    (path, params)
+}
+
+/// Compares the bodies as JSON values if both of them are valid JSON (so that the formatting doesn't matter), and as strings otherwise
+fn is_same_body(recorded: &str, actual: &str) -> bool {
+    match (serde_json::from_str::<Value>(recorded), serde_json::from_str::<Value>(actual)) {
+        (Ok(recorded), Ok(actual)) => recorded == actual,
+        _ => recorded == actual,
+    }
+}
+
+/// A local server that replays or records a cassette (see the module docs)
+pub struct CassetteServer {
+    pub mode: CassetteMode,
+    pub path: PathBuf,
+    pub server: MockHttpServer,
+    replay: Arc<Mutex<CassetteReplay>>,
+    recorded: Arc<Mutex<Vec<CassetteInteraction>>>,
+}
+
+impl CassetteServer {
+    /// Starts the server for the cassette `fixtures/cassettes/{name}.json` (the `upstream` host is used only in record mode)
+    pub async fn start(name: &str, upstream: &str) -> Result<Self, CassetteServerStartError> {
+        use CassetteServerStartError::*;
+        let mode = handle!(CassetteMode::from_env(), ParseModeFailed);
+        let path = Cassette::path(name);
+        let replay = Arc::new(Mutex::new(CassetteReplay::default()));
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let server = match mode {
+            CassetteMode::Replay => {
+                let cassette = handle!(Cassette::load(&path), LoadFailed);
+                *replay.lock().unwrap_or_else(PoisonError::into_inner) = CassetteReplay::new(cassette);
+                let replay = replay.clone();
+                handle!(
+                    MockHttpServer::start_with_handler(move |request| {
+                        replay
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .respond(request)
+                    })
+                    .await,
+                    StartServerFailed
+                )
+            }
+            CassetteMode::Record => {
+                let client = Client::new();
+                let upstream = upstream.trim_end_matches('/').to_string();
+                let recorded = recorded.clone();
+                handle!(
+                    MockHttpServer::start_with_async_handler(move |request| {
+                        let client = client.clone();
+                        let upstream = upstream.clone();
+                        let recorded = recorded.clone();
+                        async move {
+                            match Self::forward(&client, &upstream, request).await {
+                                Ok(interaction) => {
+                                    let response = interaction.response();
+                                    recorded
+                                        .lock()
+                                        .unwrap_or_else(PoisonError::into_inner)
+                                        .push(interaction);
+                                    response
+                                }
+                                Err(error) => MockHttpResponse::new(502, format!("{error:?}")),
+                            }
+                        }
+                    })
+                    .await,
+                    StartServerFailed
+                )
+            }
+        };
+        Ok(Self {
+            mode,
+            path,
+            server,
+            replay,
+            recorded,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.server.url
+    }
+
+    /// Checks that every request matched an interaction and every interaction was requested (in replay mode), or saves the recorded interactions to the cassette file (in record mode)
+    pub fn finish(self) -> Result<(), CassetteServerFinishError> {
+        use CassetteServerFinishError::*;
+        match self.mode {
+            CassetteMode::Replay => {
+                let replay = self
+                    .replay
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .clone();
+                let interactions = replay.unused_interactions();
+                let requests = replay.unmatched;
+                handle_bool!(!requests.is_empty(), UnmatchedRequests, path: self.path.clone(), requests);
+                handle_bool!(!interactions.is_empty(), UnusedInteractions, path: self.path, interactions);
+            }
+            CassetteMode::Record => {
+                let interactions = self
+                    .recorded
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .clone();
+                let cassette = Cassette {
+                    interactions,
+                };
+                handle!(cassette.save(&self.path), SaveFailed);
+            }
+        }
+        Ok(())
+    }
+
+    async fn forward(client: &Client, upstream: &str, request: MockHttpRequest) -> Result<CassetteInteraction, CassetteServerForwardError> {
+        use CassetteServerForwardError::*;
+        let MockHttpRequest {
+            method,
+            target,
+            body,
+        } = request;
+        let url = format!("{upstream}{target}");
+        let builder = match method.as_str() {
+            "POST" => client
+                .post(&url)
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.clone()),
+            _ => client.get(&url),
+        };
+        let response = handle!(builder.send().await, SendFailed, url);
+        let status = response.status().as_u16();
+        let response_body = handle!(response.text().await, TextFailed, url);
+        Ok(CassetteInteraction {
+            method,
+            target,
+            request_body: body,
+            status,
+            response_body,
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CassetteLoadError {
+    #[error("failed to open cassette '{path}' (run the test with '{CASSETTE_MODE_ENV}=record' to record it)")]
+    OpenFailed { source: io::Error, path: PathBuf },
+    #[error("failed to deserialize cassette '{path}'")]
+    DeserializeFailed { source: serde_json::Error, path: PathBuf },
+}
+
+#[derive(Error, Debug)]
+pub enum CassetteSaveError {
+    #[error("failed to create directory '{dir}'")]
+    CreateDirAllFailed { source: io::Error, dir: PathBuf },
+    #[error("failed to create cassette '{path}'")]
+    CreateFailed { source: io::Error, path: PathBuf },
+    #[error("failed to serialize cassette '{path}'")]
+    SerializeFailed { source: serde_json::Error, path: PathBuf },
+    #[error("failed to write cassette '{path}'")]
+    WriteAllFailed { source: io::Error, path: PathBuf },
+    #[error("failed to flush cassette '{path}'")]
+    FlushFailed { source: io::Error, path: PathBuf },
+}
+
+#[derive(Error, Debug)]
+pub enum CassetteServerStartError {
+    #[error("failed to parse env var '{CASSETTE_MODE_ENV}'")]
+    ParseModeFailed { source: ParseEnvVarError<StrumParseError> },
+    #[error("failed to load cassette")]
+    LoadFailed { source: CassetteLoadError },
+    #[error("failed to start cassette server")]
+    StartServerFailed { source: MockHttpServerStartError },
+}
+
+#[derive(Error, Debug)]
+pub enum CassetteServerFinishError {
+    #[error("cassette '{path}' has no interactions for {len} requests: {requests:?}", len = requests.len())]
+    UnmatchedRequests { path: PathBuf, requests: Vec<String> },
+    #[error("cassette '{path}' has {len} interactions that were not requested: {interactions:?}", len = interactions.len())]
+    UnusedInteractions { path: PathBuf, interactions: Vec<String> },
+    #[error("failed to save cassette")]
+    SaveFailed { source: CassetteSaveError },
+}
+
+#[derive(Error, Debug)]
+pub enum CassetteServerForwardError {
+    #[error("failed to send request to '{url}'")]
+    SendFailed { source: reqwest::Error, url: String },
+    #[error("failed to read response from '{url}'")]
+    TextFailed { source: reqwest::Error, url: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interaction(method: &str, target: &str, request_body: &str) -> CassetteInteraction {
+        CassetteInteraction {
+            method: method.to_string(),
+            target: target.to_string(),
+            request_body: request_body.to_string(),
+            status: 200,
+            response_body: "[]".to_string(),
+        }
+    }
+
+    fn request(method: &str, target: &str, body: &str) -> MockHttpRequest {
+        MockHttpRequest {
+            method: method.to_string(),
+            target: target.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn must_match_exact_requests_only() {
+        let cassette = Cassette {
+            interactions: vec![
+                interaction("GET", "/events?limit=500&offset=0", ""),
+                interaction("POST", "/books", r#"[{"token_id":"1"}]"#),
+            ],
+        };
+        let mut replay = CassetteReplay::new(cassette);
+        // the order of the query params doesn't matter
+        assert_eq!(
+            replay
+                .respond(&request("GET", "/events?offset=0&limit=500", ""))
+                .status,
+            200
+        );
+        assert_eq!(
+            replay
+                .respond(&request("GET", "/events?limit=500&offset=500", ""))
+                .status,
+            404
+        );
+        assert_eq!(
+            replay
+                .respond(&request("POST", "/books", r#"[{"token_id":"2"}]"#))
+                .status,
+            404
+        );
+        assert_eq!(replay.unused_interactions(), vec!["POST /books".to_string()]);
+        // the formatting of the JSON body doesn't matter
+        assert_eq!(
+            replay
+                .respond(&request("POST", "/books", r#"[ { "token_id": "1" } ]"#))
+                .status,
+            200
+        );
+        assert!(replay.unused_interactions().is_empty());
+        assert_eq!(
+            replay.unmatched,
+            vec![
+                "GET /events?limit=500&offset=500".to_string(),
+                "POST /books".to_string()
+            ]
+        );
+    }
+}
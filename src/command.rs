@@ -50,10 +50,42 @@ mod cache_command;
 pub use cache_command::*;
 mod cache_check_command;
 pub use cache_check_command::*;
+
+mod cache_clob_markets_command;
+
+pub use cache_clob_markets_command::*;
 mod cache_download_command;
 
 pub use cache_download_command::*;
 
+mod cache_mine_properties_command;
+
+pub use cache_mine_properties_command::*;
+
+mod cache_migrate_command;
+
+pub use cache_migrate_command::*;
+
+mod cache_reindex_command;
+
+pub use cache_reindex_command::*;
+
+mod cache_keyspace_list_command;
+
+pub use cache_keyspace_list_command::*;
+
+mod cache_keyspace_get_command;
+
+pub use cache_keyspace_get_command::*;
+
+mod cache_keyspace_count_command;
+
+pub use cache_keyspace_count_command::*;
+
+mod cache_keyspace_export_command;
+
+pub use cache_keyspace_export_command::*;
+
 mod cache_gamma_events_command;
 
 pub use cache_gamma_events_command::*;
@@ -77,3 +109,11 @@ pub use list_related_markets_command::*;
 mod transcode_command;
 
 pub use transcode_command::*;
+
+mod http_middleware_args;
+
+pub use http_middleware_args::*;
+
+mod api_host_args;
+
+pub use api_host_args::*;
@@ -0,0 +1,58 @@
+use crate::{ClobClient, DEFAULT_CLOB_HOST, DEFAULT_GAMMA_HOST, DEFAULT_OPINION_HOST, HttpMiddleware};
+use errgonomic::handle;
+use polymarket_client_sdk::clob::{Client as PolymarketClobClient, Config as PolymarketClobConfig};
+use polymarket_client_sdk::error::Error as PolymarketError;
+use polymarket_client_sdk::gamma::Client as GammaClient;
+use thiserror::Error;
+
+/// The base URLs of the APIs (the tests point them to a local mock server)
+#[derive(clap::Args, Clone, Debug)]
+pub struct ApiHostArgs {
+    #[arg(long, default_value = DEFAULT_CLOB_HOST)]
+    pub clob_host: String,
+
+    #[arg(long, default_value = DEFAULT_GAMMA_HOST)]
+    pub gamma_host: String,
+
+    #[arg(long, default_value = DEFAULT_OPINION_HOST)]
+    pub opinion_host: String,
+}
+
+impl ApiHostArgs {
+    pub fn clob_client(&self, middleware: HttpMiddleware) -> Result<ClobClient, ApiHostArgsClobClientError> {
+        use ApiHostArgsClobClientError::*;
+        let inner = handle!(PolymarketClobClient::new(&self.clob_host, PolymarketClobConfig::default()), ClientNewFailed, host: self.clob_host.clone());
+        Ok(ClobClient {
+            inner,
+            middleware,
+        })
+    }
+
+    pub fn gamma_client(&self) -> Result<GammaClient, ApiHostArgsGammaClientError> {
+        use ApiHostArgsGammaClientError::*;
+        let client = handle!(GammaClient::new(&self.gamma_host), ClientNewFailed, host: self.gamma_host.clone());
+        Ok(client)
+    }
+}
+
+impl Default for ApiHostArgs {
+    fn default() -> Self {
+        Self {
+            clob_host: DEFAULT_CLOB_HOST.to_string(),
+            gamma_host: DEFAULT_GAMMA_HOST.to_string(),
+            opinion_host: DEFAULT_OPINION_HOST.to_string(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ApiHostArgsClobClientError {
+    #[error("failed to create CLOB client for host '{host}'")]
+    ClientNewFailed { source: PolymarketError, host: String },
+}
+
+#[derive(Error, Debug)]
+pub enum ApiHostArgsGammaClientError {
+    #[error("failed to create Gamma client for host '{host}'")]
+    ClientNewFailed { source: PolymarketError, host: String },
+}
@@ -1,21 +1,58 @@
-use crate::{CLOB_MARKET_RESPONSES_KEYSPACE, ClobMarketResponsePrecise, DEFAULT_DB_DIR, MARKET_RESPONSE_PROPERTIES, OpenKeyspaceError, Property, PropertyName, PropertyStats, open_keyspace};
-use errgonomic::{ErrVec, handle, handle_iter};
-use fjall::{Error as FjallError, Guard, Readable, SingleWriterTxDatabase, Slice, Snapshot, UserKey};
+use crate::{CACHE_CHECK_CHUNK_SIZE, CLOB_MARKET_PROPERTIES, CLOB_MARKET_RESPONSES_KEYSPACE, CLOB_MARKETS_KEYSPACE, CLOB_ORDER_BOOK_SUMMARY_RESPONSE_KEYSPACE, CacheEntity, ClobMarket, ClobMarketResponsePrecise, DEFAULT_DB_DIR, Expr, ExprProperty, ExprPropertyTryNewError, GAMMA_EVENT_PROPERTIES, GAMMA_EVENTS_KEYSPACE, GammaEvent, MARKET_RESPONSE_PROPERTIES, ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES, OrderBookSummaryResponsePrecise, Property, PropertyContext, PropertyContextOpenError, PropertyContextReadError, PropertyDistribution, PropertyDistributionView, PropertyDistributionViewName, PropertyFactory, PropertyName, PropertyStats, ReportFormat, ReportFormatWriteError, Repository, RepositoryReadEntryError, progress_report_line, property_name_matches};
+use core::num::NonZeroUsize;
+use errgonomic::{ErrVec, handle, handle_bool};
+use fjall::{Error as FjallError, SingleWriterTxDatabase, Slice};
+use itertools::Itertools;
 use polymarket_client_sdk::clob::types::response::MarketResponse;
-use rkyv::from_bytes;
+use rkyv::api::high::{HighSerializer, HighValidator};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::de::Pool;
 use rkyv::rancor::Error as RkyvError;
-use rustc_hash::FxHashMap;
-use std::io::{self, Write, stdout};
+use rkyv::rancor::Strategy;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use rkyv::{Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use std::io::stdout;
+use std::panic::resume_unwind;
 use std::path::PathBuf;
 use std::process::ExitCode;
+use std::thread::{available_parallelism, scope};
 use thiserror::Error;
 
-type ViolationStatsMap = FxHashMap<PropertyName, PropertyStats<3, String>>;
+type Distribution = PropertyDistribution<String>;
+
+type StatefulProperty<T> = (Box<dyn Property<T>>, Distribution);
+
+type StatelessProperty<T> = (PropertyFactory<T>, Distribution);
 
 #[derive(clap::Parser, Clone, Debug)]
 pub struct CacheCheckCommand {
     #[arg(long, default_value = DEFAULT_DB_DIR)]
     pub dir: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = ReportFormat::Jsonl)]
+    pub format: ReportFormat,
+
+    #[arg(long, value_enum, default_value_t = PropertyDistributionViewName::Simple)]
+    pub view: PropertyDistributionViewName,
+
+    /// The maximum number of example keys per property and outcome
+    #[arg(long, default_value_t = 3)]
+    pub example_limit: usize,
+
+    /// Check only the properties with these names (can be repeated)
+    #[arg(long)]
+    pub only: Vec<PropertyName>,
+
+    /// Don't check the properties with these names (can be repeated)
+    #[arg(long)]
+    pub skip: Vec<PropertyName>,
+
+    /// Check a property that is derived from the registered properties (e.g. `active_xor_closed & !tokens_len_is_two`, can be repeated)
+    ///
+    /// The variables of the expression must not be excluded by `--only` or `--skip` (the expression is rejected otherwise)
+    #[arg(long)]
+    pub expr: Vec<Expr<PropertyName>>,
 }
 
 impl CacheCheckCommand {
@@ -23,89 +60,333 @@ impl CacheCheckCommand {
         use CacheCheckCommandRunError::*;
         let Self {
             dir,
+            format,
+            view,
+            example_limit,
+            only,
+            skip,
+            expr: exprs,
         } = self;
+        let unknown_names = Self::unknown_property_names(only.iter().chain(skip.iter()));
+        handle_bool!(!unknown_names.is_empty(), UnknownPropertyNames, unknown_names);
+        let unresolved_exprs = Self::unresolved_exprs(&exprs);
+        handle_bool!(!unresolved_exprs.is_empty(), UnresolvedExprs, unresolved_exprs);
+        let selection = PropertySelection {
+            only,
+            skip,
+            exprs,
+        };
+        let excluded_exprs = selection.excluded_exprs();
+        handle_bool!(!excluded_exprs.is_empty(), ExcludedExprs, excluded_exprs);
         let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
-        let clob_market_responses_keyspace = handle!(open_keyspace(&db, CLOB_MARKET_RESPONSES_KEYSPACE), OpenMarketKeyspaceFailed);
-        let snapshot = db.read_tx();
-        let mut properties = Self::named_properties();
-        let mut violations = Self::init_violations(&properties);
-        let iter = snapshot.iter(&clob_market_responses_keyspace);
-        let _processed = handle_iter!(iter.map(|guard| Self::process_entry(&mut violations, &mut properties, &snapshot, guard)), ProcessMarketEntryFailed);
-        handle!(Self::write_violations(&violations), WriteViolationsFailed);
-        Ok(ExitCode::SUCCESS)
+        let context = handle!(PropertyContext::open(&db), OpenContextFailed);
+        let mut distributions = Vec::new();
+        let market_response_distributions = handle!(Self::check_keyspace(&context, &context.market_response_repository, &MARKET_RESPONSE_PROPERTIES, &selection, example_limit, <MarketResponse as From<ClobMarketResponsePrecise>>::from), CheckKeyspaceFailed, keyspace: CLOB_MARKET_RESPONSES_KEYSPACE);
+        distributions.extend(market_response_distributions);
+        let market_distributions = handle!(Self::check_keyspace(&context, &context.market_repository, &CLOB_MARKET_PROPERTIES, &selection, example_limit, |market: ClobMarket| market), CheckKeyspaceFailed, keyspace: CLOB_MARKETS_KEYSPACE);
+        distributions.extend(market_distributions);
+        let orderbook_distributions = handle!(Self::check_keyspace(&context, &context.orderbook_repository, &ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES, &selection, example_limit, |orderbook: OrderBookSummaryResponsePrecise| orderbook), CheckKeyspaceFailed, keyspace: CLOB_ORDER_BOOK_SUMMARY_RESPONSE_KEYSPACE);
+        distributions.extend(orderbook_distributions);
+        let event_distributions = handle!(Self::check_keyspace(&context, &context.event_repository, &GAMMA_EVENT_PROPERTIES, &selection, example_limit, |event: GammaEvent| event), CheckKeyspaceFailed, keyspace: GAMMA_EVENTS_KEYSPACE);
+        distributions.extend(event_distributions);
+        let views = distributions
+            .iter()
+            .map(|distribution| PropertyDistributionView::from((view, distribution)))
+            .collect::<Vec<_>>();
+        let mut stdout = stdout().lock();
+        handle!(format.write(&mut stdout, &views), WriteReportFailed);
+        // a property that couldn't be evaluated for some value may hide an invariant violation
+        if distributions
+            .iter()
+            .any(|distribution| distribution.is_invariant_violated() || distribution.has_errors())
+        {
+            Ok(ExitCode::FAILURE)
+        } else {
+            Ok(ExitCode::SUCCESS)
+        }
     }
 
-    fn named_properties() -> Vec<(PropertyName, Box<dyn Property<MarketResponse>>)> {
-        MARKET_RESPONSE_PROPERTIES
-            .into_iter()
-            .map(|factory| {
-                let property = factory();
-                let name = property.name();
-                (name, property)
+    /// Returns the names that don't match any registered property (a typo in `--only` would otherwise silently check nothing)
+    fn unknown_property_names<'a>(names: impl Iterator<Item = &'a PropertyName>) -> Vec<PropertyName> {
+        let known_names = Self::registered_names(&MARKET_RESPONSE_PROPERTIES)
+            .chain(Self::registered_names(&CLOB_MARKET_PROPERTIES))
+            .chain(Self::registered_names(&ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES))
+            .chain(Self::registered_names(&GAMMA_EVENT_PROPERTIES))
+            .collect::<Vec<_>>();
+        names
+            .filter(|name| {
+                !known_names
+                    .iter()
+                    .any(|known_name| property_name_matches(known_name, name))
             })
+            .cloned()
             .collect()
     }
 
-    fn init_violations(properties: &[(PropertyName, Box<dyn Property<MarketResponse>>)]) -> ViolationStatsMap {
-        properties
+    /// Returns the expressions whose variables don't match the properties of a single keyspace (with the unmatched variables of every keyspace)
+    fn unresolved_exprs(exprs: &[Expr<PropertyName>]) -> Vec<String> {
+        exprs
             .iter()
-            .map(|(name, _)| (name.clone(), PropertyStats::default()))
+            .filter_map(|expr| {
+                let results = [
+                    ExprProperty::try_new(expr, &MARKET_RESPONSE_PROPERTIES).err(),
+                    ExprProperty::try_new(expr, &CLOB_MARKET_PROPERTIES).err(),
+                    ExprProperty::try_new(expr, &ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES).err(),
+                    ExprProperty::try_new(expr, &GAMMA_EVENT_PROPERTIES).err(),
+                ];
+                let errors = results.into_iter().collect::<Option<Vec<_>>>()?;
+                let vars = errors
+                    .into_iter()
+                    .map(
+                        |ExprPropertyTryNewError::VarNotFound {
+                             var,
+                         }| var,
+                    )
+                    .unique()
+                    .join(", ");
+                Some(format!("{expr} (unmatched variables: {vars})"))
+            })
             .collect()
     }
 
-    fn process_entry(violations: &mut ViolationStatsMap, properties: &mut [(PropertyName, Box<dyn Property<MarketResponse>>)], snapshot: &Snapshot, guard: Guard) -> Result<(), CacheCheckCommandProcessMarketEntryError> {
-        use CacheCheckCommandProcessMarketEntryError::*;
-        let (key_slice, value_slice) = handle!(guard.into_inner(), ReadEntryFailed);
-        let value = handle!(from_bytes::<ClobMarketResponsePrecise, RkyvError>(value_slice.as_ref()), DeserializeFailed, value: value_slice);
-        let market_response = MarketResponse::from(value);
-        Self::record_violations(violations, properties, snapshot, key_slice, &market_response);
-        Ok(())
+    fn registered_names<T>(factories: &[PropertyFactory<T>]) -> impl Iterator<Item = PropertyName> + '_ {
+        factories.iter().map(|factory| factory().name())
     }
 
-    fn record_violations<T>(violations: &mut ViolationStatsMap, properties: &mut [(PropertyName, Box<dyn Property<T>>)], snapshot: &Snapshot, key: UserKey, value: &T) {
-        properties.iter_mut().for_each(|(name, property)| {
-            if !property.holds(value, snapshot) {
-                let stats = violations.entry(name.clone()).or_default();
-                // TODO: Fix error handling
-                let key_string = String::from_utf8(key.to_vec()).expect("every key should be a valid string");
-                stats.witness(key_string);
+    /// Checks the properties against every value of the keyspace (the stored value of type `U` is converted to the property target `T`)
+    ///
+    /// The entries are read in chunks: the stateful properties are evaluated on the current thread while the stateless properties are evaluated by the workers
+    fn check_keyspace<T: Sync + 'static, U>(context: &PropertyContext, repository: &Repository<U>, factories: &[PropertyFactory<T>], selection: &PropertySelection, example_limit: usize, convert: impl Fn(U) -> T) -> Result<Vec<Distribution>, CacheCheckCommandCheckKeyspaceError>
+    where
+        U: CacheEntity + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+        U::Archived: for<'a> CheckBytes<HighValidator<'a, RkyvError>> + RkyvDeserialize<U, Strategy<Pool, RkyvError>>,
+    {
+        use CacheCheckCommandCheckKeyspaceError::*;
+        let keyspace_name = repository.keyspace_name;
+        let (mut stateful, mut stateless) = Self::init_properties(keyspace_name, factories, selection, example_limit);
+        let parallelism = available_parallelism().unwrap_or(NonZeroUsize::MIN);
+        let action = format!("Checking {keyspace_name}");
+        let mut offset: usize = 0;
+        let mut errors = Vec::new();
+        for (page_offset, chunk) in repository
+            .iter(&context.snapshot)
+            .chunks(CACHE_CHECK_CHUNK_SIZE)
+            .into_iter()
+            .enumerate()
+        {
+            eprintln!("{}", progress_report_line(&action, offset, None, None, page_offset, None));
+            let (entries, chunk_errors): (Vec<_>, Vec<_>) = chunk
+                .map(|entry| Self::read_entry(entry, &convert))
+                .partition_result();
+            errors.extend(chunk_errors);
+            Self::check_chunk(&mut stateful, &mut stateless, context, &entries, parallelism);
+            offset = offset.saturating_add(entries.len());
+        }
+        if !errors.is_empty() {
+            return Err(ReadEntryFailed {
+                source: errors.into(),
+            });
+        }
+        let distributions = stateful
+            .into_iter()
+            .map(|(_property, distribution)| distribution)
+            .chain(
+                stateless
+                    .into_iter()
+                    .map(|(_factory, distribution)| distribution),
+            )
+            .collect();
+        Ok(distributions)
+    }
+
+    fn init_properties<T: 'static>(keyspace_name: &'static str, factories: &[PropertyFactory<T>], selection: &PropertySelection, example_limit: usize) -> (Vec<StatefulProperty<T>>, Vec<StatelessProperty<T>>) {
+        let mut stateful = Vec::new();
+        let mut stateless = Vec::new();
+        for factory in factories {
+            let property = factory();
+            let name = property.name();
+            if !selection.includes(&name) {
+                continue;
+            }
+            let distribution = Distribution::new(keyspace_name.to_string(), name, property.is_invariant(), example_limit, PropertyStats::default(), PropertyStats::default(), PropertyStats::default());
+            if property.is_stateful() {
+                stateful.push((property, distribution));
+            } else {
+                stateless.push((*factory, distribution));
+            }
+        }
+        for expr in &selection.exprs {
+            if let Ok(property) = ExprProperty::try_new(expr, factories) {
+                let distribution = Distribution::new(keyspace_name.to_string(), property.name(), property.is_invariant(), example_limit, PropertyStats::default(), PropertyStats::default(), PropertyStats::default());
+                stateful.push((Box::new(property), distribution));
             }
+        }
+        (stateful, stateless)
+    }
+
+    fn read_entry<T, U>(entry: Result<(Slice, U), RepositoryReadEntryError>, convert: &impl Fn(U) -> T) -> Result<(String, T), RepositoryReadEntryError> {
+        let (key_slice, value) = entry?;
+        // the keys of the checked keyspaces are strings, but a lossy conversion is used to avoid failing the whole check on an invalid key
+        let key = String::from_utf8_lossy(&key_slice).into_owned();
+        Ok((key, convert(value)))
+    }
+
+    fn check_chunk<T: Sync>(stateful: &mut [StatefulProperty<T>], stateless: &mut [StatelessProperty<T>], context: &PropertyContext, entries: &[(String, T)], parallelism: NonZeroUsize) {
+        let factories = stateless
+            .iter()
+            .map(|(factory, _distribution)| *factory)
+            .collect::<Vec<_>>();
+        let factories = factories.as_slice();
+        let worker_len = entries.len().div_ceil(parallelism.get()).max(1);
+        let outcomes = scope(|scope| {
+            let workers = entries
+                .chunks(worker_len)
+                .map(|slice| scope.spawn(move || Self::evaluate_stateless(factories, context, slice)))
+                .collect::<Vec<_>>();
+            entries.iter().for_each(|(key, value)| {
+                stateful.iter_mut().for_each(|(property, distribution)| {
+                    let holds = property.holds(value, context);
+                    Self::record_outcome(distribution, key, holds);
+                })
+            });
+            workers
+                .into_iter()
+                .flat_map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|payload| resume_unwind(payload))
+                })
+                .collect::<Vec<_>>()
         });
+        // the outcomes are recorded in key order, so the examples are the same as in a sequential run
+        entries
+            .iter()
+            .zip(outcomes)
+            .for_each(|((key, _value), entry_outcomes)| {
+                stateless
+                    .iter_mut()
+                    .zip(entry_outcomes)
+                    .for_each(|((_factory, distribution), holds)| Self::record_outcome(distribution, key, holds))
+            });
     }
 
-    fn write_violations(violations: &ViolationStatsMap) -> Result<(), CacheCheckCommandWriteViolationsError> {
-        use CacheCheckCommandWriteViolationsError::*;
-        let mut stdout = stdout().lock();
-        handle!(serde_json::to_writer_pretty(&mut stdout, violations), SerializeFailed);
-        handle!(stdout.write_all(b"\n"), WriteFailed);
-        Ok(())
+    /// Returns the outcomes of every stateless property for every value of the slice
+    fn evaluate_stateless<T>(factories: &[PropertyFactory<T>], context: &PropertyContext, slice: &[(String, T)]) -> Vec<Vec<Result<bool, PropertyContextReadError>>> {
+        let mut properties = factories
+            .iter()
+            .map(|factory| factory())
+            .collect::<Vec<_>>();
+        slice
+            .iter()
+            .map(|(_key, value)| {
+                properties
+                    .iter_mut()
+                    .map(|property| property.holds(value, context))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn record_outcome(distribution: &mut Distribution, key: &str, holds: Result<bool, PropertyContextReadError>) {
+        let key = key.to_string();
+        match holds {
+            Ok(true) => distribution.witness_success(key),
+            Ok(false) => distribution.witness_failure(key),
+            Err(_) => distribution.witness_error(key),
+        }
+    }
+}
+
+/// The properties that are selected by `--only`, `--skip` and `--expr`
+#[derive(Clone, Debug)]
+struct PropertySelection {
+    only: Vec<PropertyName>,
+    skip: Vec<PropertyName>,
+    exprs: Vec<Expr<PropertyName>>,
+}
+
+impl PropertySelection {
+    fn includes(&self, name: &PropertyName) -> bool {
+        let matches = |input: &PropertyName| property_name_matches(name, input);
+        (self.only.is_empty() || self.only.iter().any(matches)) && !self.skip.iter().any(matches)
+    }
+
+    /// Returns the expressions that reference the properties which are not included (with the names of these properties)
+    fn excluded_exprs(&self) -> Vec<String> {
+        self.exprs
+            .iter()
+            .filter_map(|expr| {
+                let names = self
+                    .excluded_names(expr, &MARKET_RESPONSE_PROPERTIES)
+                    .chain(self.excluded_names(expr, &CLOB_MARKET_PROPERTIES))
+                    .chain(self.excluded_names(expr, &ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES))
+                    .chain(self.excluded_names(expr, &GAMMA_EVENT_PROPERTIES))
+                    .unique()
+                    .collect::<Vec<_>>();
+                (!names.is_empty()).then(|| format!("{expr} (excluded properties: {})", names.join(", ")))
+            })
+            .collect()
+    }
+
+    fn excluded_names<T>(&self, expr: &Expr<PropertyName>, factories: &[PropertyFactory<T>]) -> impl Iterator<Item = PropertyName> {
+        ExprProperty::try_new(expr, factories)
+            .map(|property| {
+                property
+                    .property_names()
+                    .filter(|name| !self.includes(name))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+            .into_iter()
     }
 }
 
 #[derive(Error, Debug)]
 pub enum CacheCheckCommandRunError {
+    #[error("unknown property names: {unknown_names:?}")]
+    UnknownPropertyNames { unknown_names: Vec<PropertyName> },
+    #[error("expressions that don't match the properties of a single keyspace: {unresolved_exprs:?}")]
+    UnresolvedExprs { unresolved_exprs: Vec<String> },
+    #[error("expressions that reference the properties excluded by --only or --skip: {excluded_exprs:?}")]
+    ExcludedExprs { excluded_exprs: Vec<String> },
     #[error("failed to open database at '{dir}'")]
     OpenDatabaseFailed { source: FjallError, dir: PathBuf },
-    #[error("failed to open market keyspace")]
-    OpenMarketKeyspaceFailed { source: OpenKeyspaceError },
-    #[error("failed to process {len} cache entries", len = source.len())]
-    ProcessMarketEntryFailed { source: ErrVec<CacheCheckCommandProcessMarketEntryError> },
-    #[error("failed to write violations output")]
-    WriteViolationsFailed { source: CacheCheckCommandWriteViolationsError },
+    #[error("failed to open property context")]
+    OpenContextFailed { source: PropertyContextOpenError },
+    #[error("failed to check keyspace '{keyspace}'")]
+    CheckKeyspaceFailed { source: CacheCheckCommandCheckKeyspaceError, keyspace: &'static str },
+    #[error("failed to write report")]
+    WriteReportFailed { source: ReportFormatWriteError },
 }
 
 #[derive(Error, Debug)]
-pub enum CacheCheckCommandProcessMarketEntryError {
-    #[error("failed to read cache entry")]
-    ReadEntryFailed { source: FjallError },
-    #[error("failed to deserialize market response")]
-    DeserializeFailed { source: RkyvError, value: Slice },
+pub enum CacheCheckCommandCheckKeyspaceError {
+    #[error("failed to read {len} cache entries", len = source.len())]
+    ReadEntryFailed { source: ErrVec<RepositoryReadEntryError> },
 }
 
-#[derive(Error, Debug)]
-pub enum CacheCheckCommandWriteViolationsError {
-    #[error("failed to serialize violations output")]
-    SerializeFailed { source: serde_json::Error },
-    #[error("failed to write violations output")]
-    WriteFailed { source: io::Error },
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_match_property_names_in_snake_case() {
+        let names = [
+            "active_xor_closed".to_string(),
+            "NoSuchProperty".to_string(),
+        ];
+        assert_eq!(CacheCheckCommand::unknown_property_names(names.iter()), vec!["NoSuchProperty".to_string()]);
+        let selection = PropertySelection {
+            only: vec!["active_xor_closed".to_string()],
+            skip: vec![],
+            exprs: vec![],
+        };
+        assert!(selection.includes(&"ActiveXorClosed".to_string()));
+        let selection = PropertySelection {
+            only: vec![],
+            skip: vec!["active_xor_closed".to_string()],
+            exprs: vec![],
+        };
+        assert!(!selection.includes(&"ActiveXorClosed".to_string()));
+    }
 }
@@ -0,0 +1,37 @@
+use CacheClobMarketsSubcommand::*;
+use errgonomic::map_err;
+use std::process::ExitCode;
+use thiserror::Error;
+
+#[derive(clap::Parser, Clone, Debug)]
+pub struct CacheClobMarketsCommand {
+    #[command(subcommand)]
+    subcommand: CacheClobMarketsSubcommand,
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum CacheClobMarketsSubcommand {
+    Query(CacheClobMarketsQueryCommand),
+}
+
+impl CacheClobMarketsCommand {
+    pub async fn run(self) -> Result<ExitCode, CacheClobMarketsCommandRunError> {
+        use CacheClobMarketsCommandRunError::*;
+        let Self {
+            subcommand,
+        } = self;
+        match subcommand {
+            Query(command) => map_err!(command.run().await, CacheClobMarketsQueryCommandRunFailed),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CacheClobMarketsCommandRunError {
+    #[error("failed to run cache clob markets query command")]
+    CacheClobMarketsQueryCommandRunFailed { source: CacheClobMarketsQueryCommandRunError },
+}
+
+mod cache_clob_markets_query_command;
+
+pub use cache_clob_markets_query_command::*;
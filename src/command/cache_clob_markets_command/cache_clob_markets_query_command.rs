@@ -0,0 +1,80 @@
+use crate::{ClobMarketResponsePrecise, DEFAULT_DB_DIR, Expr, MarketFilter, Repository, RepositoryOpenError, RepositoryReadEntryError};
+use errgonomic::handle;
+use fjall::{Error as FjallError, SingleWriterTxDatabase};
+use std::io::{self, Write, stdout};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Writes the cached markets that match the filter as JSON lines
+///
+/// The filter is evaluated over the market responses, because they contain the tags and the rewards (see [`MarketFilter`] for the syntax).
+/// Example: `cache clob-markets query 'tag:Politics & !closed & end_date>=2025-01-01 & (neg_risk | rewards_daily_rate>0)'`
+#[derive(clap::Parser, Clone, Debug)]
+pub struct CacheClobMarketsQueryCommand {
+    #[arg(long, default_value = DEFAULT_DB_DIR)]
+    pub dir: PathBuf,
+
+    /// The number of matching markets to skip
+    #[arg(long)]
+    pub offset: Option<usize>,
+
+    /// The maximum number of matching markets to write
+    #[arg(long)]
+    pub limit: Option<NonZeroUsize>,
+
+    pub filter: Expr<MarketFilter>,
+}
+
+impl CacheClobMarketsQueryCommand {
+    pub async fn run(self) -> Result<ExitCode, CacheClobMarketsQueryCommandRunError> {
+        use CacheClobMarketsQueryCommandRunError::*;
+        let Self {
+            dir,
+            offset,
+            limit,
+            filter,
+        } = self;
+        let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
+        let repository = handle!(Repository::<ClobMarketResponsePrecise>::open(&db), RepositoryOpenFailed);
+        let snapshot = db.read_tx();
+        let offset = offset.unwrap_or(0);
+        let limit = limit.map(NonZeroUsize::get).unwrap_or(usize::MAX);
+        let mut stdout = stdout().lock();
+        // the read errors are handled before the offset is applied, so that only the matching markets are skipped
+        let mut skipped_count = 0usize;
+        let mut written_count = 0usize;
+        for entry in repository.iter(&snapshot) {
+            if written_count >= limit {
+                break;
+            }
+            let (_key, market) = handle!(entry, ReadMarketFailed);
+            if !filter.eval(&mut |market_filter: &MarketFilter| market_filter.matches(&market)) {
+                continue;
+            }
+            if skipped_count < offset {
+                skipped_count = skipped_count.saturating_add(1);
+                continue;
+            }
+            handle!(serde_json::to_writer(&mut stdout, &market), SerializeFailed, market: Box::new(market));
+            handle!(stdout.write_all(b"\n"), WriteAllFailed);
+            written_count = written_count.saturating_add(1);
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CacheClobMarketsQueryCommandRunError {
+    #[error("failed to open database at '{dir}'")]
+    OpenDatabaseFailed { source: FjallError, dir: PathBuf },
+    #[error("failed to open repository")]
+    RepositoryOpenFailed { source: RepositoryOpenError },
+    #[error("failed to read market")]
+    ReadMarketFailed { source: RepositoryReadEntryError },
+    #[error("failed to serialize market '{slug}'", slug = market.market_slug)]
+    SerializeFailed { source: serde_json::Error, market: Box<ClobMarketResponsePrecise> },
+    #[error("failed to write output")]
+    WriteAllFailed { source: io::Error },
+}
@@ -1,4 +1,4 @@
-use crate::{CacheCheckCommand, CacheCheckCommandRunError, CacheDownloadCommand, CacheDownloadCommandRunError, CacheGammaEventsCommand, CacheGammaEventsCommandRunError, CacheMarketResponsesCommand, CacheMarketResponsesCommandRunError, CacheOrderBookSummaryResponsesCommand, CacheOrderBookSummaryResponsesCommandRunError};
+use crate::{CacheCheckCommand, CacheCheckCommandRunError, CacheClobMarketsCommand, CacheClobMarketsCommandRunError, CacheDownloadCommand, CacheDownloadCommandRunError, CacheGammaEventsCommand, CacheGammaEventsCommandRunError, CacheMarketResponsesCommand, CacheMarketResponsesCommandRunError, CacheMigrateCommand, CacheMigrateCommandRunError, CacheMinePropertiesCommand, CacheMinePropertiesCommandRunError, CacheOrderBookSummaryResponsesCommand, CacheOrderBookSummaryResponsesCommandRunError, CacheReindexCommand, CacheReindexCommandRunError};
 use CacheSubcommand::*;
 use errgonomic::map_err;
 use std::process::ExitCode;
@@ -15,10 +15,14 @@ pub struct CacheCommand {
 #[derive(clap::Subcommand, Clone, Debug)]
 pub enum CacheSubcommand {
     Check(CacheCheckCommand),
+    ClobMarkets(CacheClobMarketsCommand),
     Download(CacheDownloadCommand),
     GammaEvents(CacheGammaEventsCommand),
     MarketResponses(CacheMarketResponsesCommand),
+    Migrate(CacheMigrateCommand),
+    MineProperties(CacheMinePropertiesCommand),
     OrderBookSummaryResponses(CacheOrderBookSummaryResponsesCommand),
+    Reindex(CacheReindexCommand),
 }
 
 impl CacheCommand {
@@ -29,10 +33,14 @@ impl CacheCommand {
         } = self;
         match subcommand {
             Check(command) => map_err!(command.run().await, CacheCheckCommandRunFailed),
+            ClobMarkets(command) => map_err!(command.run().await, CacheClobMarketsCommandRunFailed),
             Download(command) => map_err!(command.run().await, CacheDownloadCommandRunFailed),
             GammaEvents(command) => map_err!(command.run().await, CacheGammaEventsCommandRunFailed),
             MarketResponses(command) => map_err!(command.run().await, CacheMarketResponsesCommandRunFailed),
+            Migrate(command) => map_err!(command.run().await, CacheMigrateCommandRunFailed),
+            MineProperties(command) => map_err!(command.run().await, CacheMinePropertiesCommandRunFailed),
             OrderBookSummaryResponses(command) => map_err!(command.run().await, CacheOrderBookSummaryResponsesCommandRunFailed),
+            Reindex(command) => map_err!(command.run().await, CacheReindexCommandRunFailed),
         }
     }
 }
@@ -41,12 +49,20 @@ impl CacheCommand {
 pub enum CacheCommandRunError {
     #[error("failed to run cache check command")]
     CacheCheckCommandRunFailed { source: CacheCheckCommandRunError },
+    #[error("failed to run cache clob markets command")]
+    CacheClobMarketsCommandRunFailed { source: CacheClobMarketsCommandRunError },
     #[error("failed to run cache download command")]
     CacheDownloadCommandRunFailed { source: CacheDownloadCommandRunError },
     #[error("failed to run cache gamma events command")]
     CacheGammaEventsCommandRunFailed { source: CacheGammaEventsCommandRunError },
     #[error("failed to run cache market responses command")]
     CacheMarketResponsesCommandRunFailed { source: CacheMarketResponsesCommandRunError },
+    #[error("failed to run cache migrate command")]
+    CacheMigrateCommandRunFailed { source: CacheMigrateCommandRunError },
+    #[error("failed to run cache mine properties command")]
+    CacheMinePropertiesCommandRunFailed { source: CacheMinePropertiesCommandRunError },
     #[error("failed to run cache order book summary responses command")]
     CacheOrderBookSummaryResponsesCommandRunFailed { source: CacheOrderBookSummaryResponsesCommandRunError },
+    #[error("failed to run cache reindex command")]
+    CacheReindexCommandRunFailed { source: CacheReindexCommandRunError },
 }
@@ -1,20 +1,17 @@
-use crate::{CLOB_MARKET_RESPONSES_KEYSPACE, CLOB_MARKETS_KEYSPACE, CLOB_ORDER_BOOK_SUMMARY_RESPONSE_KEYSPACE, ClobMarket, ClobMarketFallible, ClobMarketResponsePrecise, ClobMarketResponsePreciseFallible, ConvertGammaEventRawToGammaEventError, ConvertOrderBookSummaryResponseToOrderbookError, DEFAULT_DB_DIR, GAMMA_EVENTS_KEYSPACE, GAMMA_EVENTS_PAGE_SIZE, GAMMA_QUERY_ASCENDING, GammaEvent, NEXT_CURSOR_STOP, NextCursor, OpenKeyspaceError, OrderBookSummaryResponsePrecise, ShouldDownloadOrderbooks, TokenId, format_debug_diff, gamma_event_raw_is_fresh, open_keyspace, progress_report_line};
+use crate::{ApiHostArgs, ApiHostArgsClobClientError, ApiHostArgsGammaClientError, CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE, CacheEntity, ClobClient, ClobClientOrderBookError, ClobMarket, ClobMarketFallible, ClobMarketResponsePrecise, ClobMarketResponsePreciseFallible, ConvertGammaEventRawToGammaEventError, DEFAULT_DB_DIR, DOWNLOAD_CHECKPOINTS_KEYSPACE, DownloadCheckpoint, DownloadEndpoint, GAMMA_EVENTS_PAGE_SIZE, GAMMA_QUERY_ASCENDING, GammaEvent, HttpEndpoint, HttpMiddleware, HttpMiddlewareArgs, HttpMiddlewareCallError, MarketIndexes, MarketIndexesOpenError, NEXT_CURSOR_STOP, NextCursor, OpenKeyspaceError, OrderBookSnapshotKey, OrderBookSummaryResponsePrecise, Repository, RepositoryCountError, RepositoryGetError, RepositoryInsertError, RepositoryOpenError, ShouldDownloadOrderbooks, TokenId, format_debug_diff, gamma_event_raw_is_fresh, open_keyspace, progress_report_line};
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD;
 use core::fmt::Debug;
 use core::num::TryFromIntError;
 use errgonomic::{DisplayAsDebug, ErrVec, handle, handle_bool, handle_iter, map_err};
-use fjall::{Error as FjallError, PersistMode, SingleWriterTxDatabase, SingleWriterTxKeyspace, SingleWriterWriteTx, UserKey};
-use futures::future::join_all;
+use fjall::{Error as FjallError, PersistMode, Readable, SingleWriterTxDatabase, SingleWriterTxKeyspace, SingleWriterWriteTx, Slice, UserKey};
 use itertools::Itertools;
-use polymarket_client_sdk::clob::Client as ClobClient;
-use polymarket_client_sdk::clob::types::request::OrderBookSummaryRequest;
-use polymarket_client_sdk::clob::types::response::{MarketResponse, OrderBookSummaryResponse};
+use polymarket_client_sdk::clob::types::response::MarketResponse;
 use polymarket_client_sdk::error::Error as PolymarketError;
 use polymarket_client_sdk::gamma::Client as GammaClient;
 use polymarket_client_sdk::gamma::types::request::EventsRequest;
 use polymarket_client_sdk::gamma::types::response::Event;
-use rkyv::{rancor::Error as RkyvError, to_bytes};
+use rkyv::{from_bytes, rancor::Error as RkyvError, to_bytes};
 use rustc_hash::FxHashSet;
 use std::error::Error as StdError;
 use std::hash::Hash;
@@ -22,8 +19,7 @@ use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::process::ExitCode;
 use thiserror::Error;
-
-const ORDERBOOKS_CHUNK_SIZE: usize = 500;
+use time::OffsetDateTime;
 
 #[derive(clap::Parser, Clone, Debug)]
 pub struct CacheDownloadCommand {
@@ -31,12 +27,18 @@ pub struct CacheDownloadCommand {
     #[arg(long)]
     pub page_limit: Option<NonZeroUsize>,
 
-    /// A starting offset that overrides the cached keyspace length
+    /// A starting offset that overrides the stored download checkpoints (`--offset 0` clears the cache)
     #[arg(long)]
     pub offset: Option<usize>,
 
     #[arg(long, default_value = DEFAULT_DB_DIR)]
     pub dir: PathBuf,
+
+    #[command(flatten)]
+    pub hosts: ApiHostArgs,
+
+    #[command(flatten)]
+    pub http: HttpMiddlewareArgs,
 }
 
 impl CacheDownloadCommand {
@@ -46,28 +48,45 @@ impl CacheDownloadCommand {
             page_limit,
             offset,
             dir,
+            hosts,
+            http,
         } = self;
         let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
-        let market_response_keyspace = handle!(open_keyspace(&db, CLOB_MARKET_RESPONSES_KEYSPACE), KeyspaceOpenFailed);
-        let market_keyspace = handle!(open_keyspace(&db, CLOB_MARKETS_KEYSPACE), KeyspaceOpenFailed);
-        let orderbook_keyspace = handle!(open_keyspace(&db, CLOB_ORDER_BOOK_SUMMARY_RESPONSE_KEYSPACE), KeyspaceOpenFailed);
-        let event_keyspace = handle!(open_keyspace(&db, GAMMA_EVENTS_KEYSPACE), KeyspaceOpenFailed);
+        let market_response_repository = handle!(Repository::<ClobMarketResponsePrecise>::open(&db), RepositoryOpenFailed);
+        let market_repository = handle!(Repository::<ClobMarket>::open(&db), RepositoryOpenFailed);
+        let orderbook_repository = handle!(Repository::<OrderBookSummaryResponsePrecise>::open(&db), RepositoryOpenFailed);
+        let event_repository = handle!(Repository::<GammaEvent>::open(&db), RepositoryOpenFailed);
+        let orderbook_snapshot_repository = handle!(Repository::<OrderBookSummaryResponsePrecise>::open_named(&db, CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE), RepositoryOpenFailed);
+        let checkpoint_keyspace = handle!(open_keyspace(&db, DOWNLOAD_CHECKPOINTS_KEYSPACE), KeyspaceOpenFailed);
+        let market_indexes = handle!(MarketIndexes::open(&db), MarketIndexesOpenFailed);
         if offset == Some(0) {
-            market_response_keyspace.as_ref().clear().unwrap();
-            market_keyspace.as_ref().clear().unwrap();
-            orderbook_keyspace.as_ref().clear().unwrap();
-            event_keyspace.as_ref().clear().unwrap();
+            let keyspaces = [
+                &market_response_repository.keyspace,
+                &market_repository.keyspace,
+                &orderbook_repository.keyspace,
+                &orderbook_snapshot_repository.keyspace,
+                &event_repository.keyspace,
+                &checkpoint_keyspace,
+            ];
+            handle_iter!(
+                keyspaces
+                    .into_iter()
+                    .chain(market_indexes.keyspaces())
+                    .map(|keyspace| keyspace.as_ref().clear()),
+                ClearKeyspacesFailed
+            );
         }
-        let clob_client = ClobClient::default();
-        let gamma_client = GammaClient::default();
+        let middleware = http.middleware();
+        let clob_client = handle!(hosts.clob_client(middleware.clone()), ClobClientFailed);
+        let gamma_client = handle!(hosts.gamma_client(), GammaClientFailed);
         let page_limit = page_limit.map(NonZeroUsize::get);
         let markets_download = async {
             use CacheDownloadCommandRunError::*;
-            map_err!(Self::download_market_responses(&db, &market_response_keyspace, &market_keyspace, &orderbook_keyspace, &clob_client, page_limit, offset).await, DownloadMarketResponsesFailed)
+            map_err!(Self::download_market_responses(&db, &market_response_repository, &market_indexes, &market_repository, &orderbook_repository, &orderbook_snapshot_repository, &checkpoint_keyspace, &clob_client, page_limit, offset).await, DownloadMarketResponsesFailed)
         };
         let events_download = async {
             use CacheDownloadCommandRunError::*;
-            map_err!(Self::download_gamma_events(&db, &event_keyspace, &gamma_client, page_limit, offset).await, DownloadGammaEventsFailed)
+            map_err!(Self::download_gamma_events(&db, &event_repository, &checkpoint_keyspace, &gamma_client, &middleware, page_limit, offset).await, DownloadGammaEventsFailed)
         };
         let result = tokio::try_join!(markets_download, events_download);
         match result {
@@ -77,19 +96,26 @@ impl CacheDownloadCommand {
         Ok(ExitCode::SUCCESS)
     }
 
-    async fn download_market_responses(db: &SingleWriterTxDatabase, market_response_keyspace: &SingleWriterTxKeyspace, market_keyspace: &SingleWriterTxKeyspace, orderbook_keyspace: &SingleWriterTxKeyspace, client: &ClobClient, page_limit: Option<usize>, offset: Option<usize>) -> Result<(), CacheDownloadCommandDownloadMarketResponsesError> {
+    #[allow(clippy::too_many_arguments)]
+    async fn download_market_responses(db: &SingleWriterTxDatabase, market_response_repository: &Repository<ClobMarketResponsePrecise>, market_indexes: &MarketIndexes, market_repository: &Repository<ClobMarket>, orderbook_repository: &Repository<OrderBookSummaryResponsePrecise>, orderbook_snapshot_repository: &Repository<OrderBookSummaryResponsePrecise>, checkpoint_keyspace: &SingleWriterTxKeyspace, client: &ClobClient, page_limit: Option<usize>, offset: Option<usize>) -> Result<(), CacheDownloadCommandDownloadMarketResponsesError> {
         use CacheDownloadCommandDownloadMarketResponsesError::*;
-        let mut offset = match offset {
-            Some(offset) => offset,
-            None => handle!(market_response_keyspace.as_ref().len(), MarketKeyspaceLenFailed),
-        };
-        let mut next_cursor: NextCursor = STANDARD.encode(offset.to_string());
+        let endpoint = DownloadEndpoint::ClobMarkets;
+        let checkpoint_opt = handle!(Self::read_checkpoint(db, checkpoint_keyspace, endpoint), ReadCheckpointFailed);
+        let stored_count = handle!(market_response_repository.count(&db.read_tx()), CountStoredFailed);
+        let (mut offset, mut next_cursor, mut page_count) = handle!(Self::market_responses_start(offset, checkpoint_opt, stored_count), MarketResponsesStartFailed);
         let mut market_slugs = FxHashSet::default();
         let mut page_offset: usize = 0;
 
         loop {
             eprintln!("{}", progress_report_line("Downloading markets", offset, None, None, page_offset, page_limit));
-            let page = handle!(client.markets(Some(next_cursor.clone())).await, FetchMarketsFailed, next_cursor);
+            let page = handle!(
+                client
+                    .middleware
+                    .call(HttpEndpoint::Clob, || client.inner.markets(Some(next_cursor.clone())))
+                    .await,
+                FetchMarketsFailed,
+                next_cursor
+            );
             let markets = page.data;
             let next_cursor_new = page.next_cursor;
             if markets.is_empty() {
@@ -101,10 +127,13 @@ impl CacheDownloadCommand {
             let token_ids = markets
                 .iter()
                 .filter(|m| m.should_download_orderbooks())
-                .flat_map(|market_response| market_response.tokens.iter().map(|t| t.token_id));
-            let orderbooks = handle!(Self::fetch_orderbooks_for_tokens(client, token_ids).await, FetchOrderbooksForTokensFailed);
-            handle!(Self::write_market_response_page_to_database(db, market_response_keyspace, market_keyspace, orderbook_keyspace, markets, orderbooks), WritePageToDatabaseFailed);
+                .flat_map(|market_response| market_response.tokens.iter().map(|t| t.token_id))
+                .collect_vec();
+            let orderbooks = handle!(Self::fetch_orderbooks(client, &token_ids).await, FetchOrderbooksFailed);
             offset = offset.saturating_add(market_count);
+            page_count = page_count.saturating_add(1);
+            let checkpoint = handle!(Self::checkpoint(Some(next_cursor_new.clone()), offset, page_count), CheckpointFailed);
+            handle!(Self::write_market_response_page_to_database(db, market_response_repository, market_indexes, market_repository, orderbook_repository, orderbook_snapshot_repository, checkpoint_keyspace, markets, orderbooks, checkpoint), WritePageToDatabaseFailed);
             page_offset = page_offset.saturating_add(1);
             next_cursor = next_cursor_new;
             if next_cursor == NEXT_CURSOR_STOP || Self::limit_reached(page_offset, page_limit) {
@@ -114,12 +143,12 @@ impl CacheDownloadCommand {
         Ok(())
     }
 
-    async fn download_gamma_events(db: &SingleWriterTxDatabase, event_keyspace: &SingleWriterTxKeyspace, client: &GammaClient, page_limit: Option<usize>, offset: Option<usize>) -> Result<(), CacheDownloadCommandDownloadGammaEventsError> {
+    async fn download_gamma_events(db: &SingleWriterTxDatabase, event_repository: &Repository<GammaEvent>, checkpoint_keyspace: &SingleWriterTxKeyspace, client: &GammaClient, middleware: &HttpMiddleware, page_limit: Option<usize>, offset: Option<usize>) -> Result<(), CacheDownloadCommandDownloadGammaEventsError> {
         use CacheDownloadCommandDownloadGammaEventsError::*;
-        let mut offset = match offset {
-            Some(offset) => offset,
-            None => handle!(event_keyspace.as_ref().len(), EventKeyspaceLenFailed),
-        };
+        let endpoint = DownloadEndpoint::GammaEvents;
+        let checkpoint_opt = handle!(Self::read_checkpoint(db, checkpoint_keyspace, endpoint), ReadCheckpointFailed);
+        let stored_count = handle!(event_repository.count(&db.read_tx()), CountStoredFailed);
+        let (mut offset, mut page_count) = handle!(Self::gamma_events_start(offset, checkpoint_opt, stored_count), GammaEventsStartFailed);
         let mut event_slugs = FxHashSet::default();
         let mut page_offset: usize = 0;
         let page_size = GAMMA_EVENTS_PAGE_SIZE;
@@ -132,13 +161,21 @@ impl CacheDownloadCommand {
                 .limit(GAMMA_EVENTS_PAGE_SIZE as i32)
                 .offset(offset as i32)
                 .build();
-            let events = handle!(client.events(&request).await, FetchEventsFailed, request);
+            let events = handle!(
+                middleware
+                    .call(HttpEndpoint::Gamma, || client.events(&request))
+                    .await,
+                FetchEventsFailed,
+                request
+            );
             if events.is_empty() {
                 break;
             }
             let event_count = events.len();
-            handle!(Self::write_events_to_database(db, event_keyspace, &mut event_slugs, events), WriteEventsToDatabaseFailed);
             offset = offset.saturating_add(event_count);
+            page_count = page_count.saturating_add(1);
+            let checkpoint = handle!(Self::checkpoint(None, offset, page_count), CheckpointFailed);
+            handle!(Self::write_events_to_database(db, event_repository, checkpoint_keyspace, &mut event_slugs, events, checkpoint), WriteEventsToDatabaseFailed);
             page_offset = page_offset.saturating_add(1);
             if event_count < page_size || Self::limit_reached(page_offset, page_limit) {
                 break;
@@ -147,32 +184,81 @@ impl CacheDownloadCommand {
         Ok(())
     }
 
-    async fn fetch_orderbooks_for_tokens(client: &ClobClient, token_ids: impl Iterator<Item = TokenId>) -> Result<Vec<OrderBookSummaryResponse>, CacheDownloadCommandFetchOrderbooksForTokensError> {
-        use CacheDownloadCommandFetchOrderbooksForTokensError::*;
-        let chunks = token_ids.chunks(ORDERBOOKS_CHUNK_SIZE);
-        let futures = chunks
-            .into_iter()
-            .map(|chunk| Self::fetch_orderbooks_chunk(client, chunk));
-        let results = join_all(futures).await;
-        let orderbooks = handle_iter!(results.into_iter(), FetchOrderbooksChunkFailed);
-        Ok(orderbooks.into_iter().flatten().collect())
+    /// Returns `(offset, next_cursor, page_count)` for the first page of the markets download
+    ///
+    /// If the previous download has reached the last page, the cursor is derived from the offset, so that the markets that were listed after the previous download are picked up.
+    /// If the database has no checkpoint (e.g. it was filled before the checkpoints were introduced), the download starts after the `stored_count` markets. The API offset of the last stored market is never less than `stored_count`, so no market is skipped (some markets may be downloaded again).
+    fn market_responses_start(offset: Option<usize>, checkpoint_opt: Option<DownloadCheckpoint>, stored_count: usize) -> Result<(usize, NextCursor, usize), CacheDownloadCommandStartError> {
+        match (offset, checkpoint_opt) {
+            (Some(offset), _) => Ok((offset, Self::next_cursor_from_offset(offset), 0)),
+            (None, Some(checkpoint)) => {
+                let (offset, page_count) = Self::checkpoint_position(&checkpoint)?;
+                let is_final = checkpoint.is_final();
+                let next_cursor = match checkpoint.next_cursor {
+                    Some(next_cursor) if !is_final => next_cursor,
+                    _ => Self::next_cursor_from_offset(offset),
+                };
+                Ok((offset, next_cursor, page_count))
+            }
+            (None, None) => Ok((stored_count, Self::next_cursor_from_offset(stored_count), 0)),
+        }
+    }
+
+    /// Returns `(offset, page_count)` for the first page of the events download
+    ///
+    /// Without a checkpoint, the download starts after the `stored_count` events (see [`Self::market_responses_start`]).
+    fn gamma_events_start(offset: Option<usize>, checkpoint_opt: Option<DownloadCheckpoint>, stored_count: usize) -> Result<(usize, usize), CacheDownloadCommandStartError> {
+        match (offset, checkpoint_opt) {
+            (Some(offset), _) => Ok((offset, 0)),
+            (None, Some(checkpoint)) => Self::checkpoint_position(&checkpoint),
+            (None, None) => Ok((stored_count, 0)),
+        }
+    }
+
+    fn checkpoint_position(checkpoint: &DownloadCheckpoint) -> Result<(usize, usize), CacheDownloadCommandStartError> {
+        use CacheDownloadCommandStartError::*;
+        let offset = handle!(usize::try_from(checkpoint.offset), OffsetTryFromFailed, offset: checkpoint.offset);
+        let page_count = handle!(usize::try_from(checkpoint.page_count), PageCountTryFromFailed, page_count: checkpoint.page_count);
+        Ok((offset, page_count))
+    }
+
+    fn next_cursor_from_offset(offset: usize) -> NextCursor {
+        STANDARD.encode(offset.to_string())
+    }
+
+    fn checkpoint(next_cursor: Option<NextCursor>, offset: usize, page_count: usize) -> Result<DownloadCheckpoint, CacheDownloadCommandCheckpointError> {
+        use CacheDownloadCommandCheckpointError::*;
+        let offset = handle!(u64::try_from(offset), OffsetTryFromFailed, offset);
+        let page_count = handle!(u64::try_from(page_count), PageCountTryFromFailed, page_count);
+        Ok(DownloadCheckpoint::new(next_cursor, offset, page_count, OffsetDateTime::now_utc()))
     }
 
-    async fn fetch_orderbooks_chunk(client: &ClobClient, token_ids: impl Iterator<Item = TokenId>) -> Result<Vec<OrderBookSummaryResponse>, CacheDownloadCommandFetchOrderbooksChunkError> {
-        use CacheDownloadCommandFetchOrderbooksChunkError::*;
-        let requests = token_ids
-            .map(|token_id| {
-                OrderBookSummaryRequest::builder()
-                    .token_id(token_id)
-                    .build()
-            })
+    fn read_checkpoint(db: &SingleWriterTxDatabase, checkpoint_keyspace: &SingleWriterTxKeyspace, endpoint: DownloadEndpoint) -> Result<Option<DownloadCheckpoint>, CacheDownloadCommandReadCheckpointError> {
+        use CacheDownloadCommandReadCheckpointError::*;
+        let snapshot = db.read_tx();
+        let value_opt = handle!(snapshot.get(checkpoint_keyspace, endpoint.key()), GetFailed, endpoint);
+        match value_opt {
+            Some(value) => {
+                let checkpoint = handle!(from_bytes::<DownloadCheckpoint, RkyvError>(value.as_ref()), DeserializeFailed, endpoint, value);
+                Ok(Some(checkpoint))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// The page is not written if any order book fails to download (the tokens without an order book are skipped)
+    async fn fetch_orderbooks(client: &ClobClient, token_ids: &[TokenId]) -> Result<Vec<OrderBookSummaryResponsePrecise>, ErrVec<ClobClientOrderBookError>> {
+        let batch = client.order_books(token_ids).await;
+        let failures = batch
+            .failures
+            .into_iter()
+            .filter(|failure| !failure.is_order_book_not_found())
             .collect::<Vec<_>>();
-        let orderbooks = handle!(client.order_books(&requests).await, OrderBooksFailed, requests: requests.into_boxed_slice());
-        Ok(orderbooks)
+        if failures.is_empty() { Ok(batch.order_books) } else { Err(failures.into()) }
     }
 
     #[allow(clippy::too_many_arguments)]
-    fn write_market_response_page_to_database(db: &SingleWriterTxDatabase, market_response_keyspace: &SingleWriterTxKeyspace, market_keyspace: &SingleWriterTxKeyspace, orderbook_keyspace: &SingleWriterTxKeyspace, markets: Vec<MarketResponse>, orderbooks: Vec<OrderBookSummaryResponse>) -> Result<(), CacheDownloadCommandWritePageToDatabaseError> {
+    fn write_market_response_page_to_database(db: &SingleWriterTxDatabase, market_response_repository: &Repository<ClobMarketResponsePrecise>, market_indexes: &MarketIndexes, market_repository: &Repository<ClobMarket>, orderbook_repository: &Repository<OrderBookSummaryResponsePrecise>, orderbook_snapshot_repository: &Repository<OrderBookSummaryResponsePrecise>, checkpoint_keyspace: &SingleWriterTxKeyspace, markets: Vec<MarketResponse>, orderbooks: Vec<OrderBookSummaryResponsePrecise>, checkpoint: DownloadCheckpoint) -> Result<(), CacheDownloadCommandWritePageToDatabaseError> {
         use CacheDownloadCommandWritePageToDatabaseError::*;
         let market_entries = handle_iter!(
             markets.into_iter().map(|market_response| {
@@ -198,16 +284,51 @@ impl CacheDownloadCommand {
                 }
                 (responses, markets)
             });
+        // the previous versions are read through the transaction (before the page is inserted) to remove their stale index entries
         let mut tx = db.write_tx();
-        let _market_response_inserts = handle_iter!(Self::insert_iter(&mut tx, market_response_keyspace, market_responses, |market_response| market_response.market_slug.as_str().into(), Self::market_response_bytes), InsertMarketResponseEntriesFailed);
-        let _market_inserts = handle_iter!(Self::insert_iter(&mut tx, market_keyspace, markets, |market| market.slug.as_str().into(), Self::market_bytes), InsertMarketEntriesFailed);
-        let _orderbook_inserts = handle_iter!(Self::insert_iter(&mut tx, orderbook_keyspace, orderbooks, |orderbook| orderbook.asset_id.to_string().into(), Self::orderbook_bytes), InsertOrderbookEntriesFailed);
+        let previous_market_responses = handle_iter!(
+            market_responses
+                .iter()
+                .map(|market_response| market_response_repository.get(&tx, &market_response.market_slug)),
+            ReadPreviousMarketResponsesFailed
+        );
+        market_responses
+            .iter()
+            .zip(&previous_market_responses)
+            .for_each(|(market_response, previous)| market_indexes.update(&mut tx, previous.as_ref(), market_response));
+        let _market_response_inserts = handle_iter!(
+            market_responses
+                .iter()
+                .map(|market_response| market_response_repository.insert(&mut tx, market_response)),
+            InsertMarketResponseEntriesFailed
+        );
+        let _market_inserts = handle_iter!(
+            markets
+                .iter()
+                .map(|market| market_repository.insert(&mut tx, market)),
+            InsertMarketEntriesFailed
+        );
+        // The latest order book is overwritten, while the snapshot is appended to the history
+        let _orderbook_inserts = handle_iter!(
+            orderbooks
+                .iter()
+                .map(|orderbook| orderbook_repository.insert(&mut tx, orderbook)),
+            InsertOrderbookEntriesFailed
+        );
+        let _orderbook_snapshot_inserts = handle_iter!(
+            orderbooks.iter().map(|orderbook| {
+                let snapshot_key = OrderBookSnapshotKey::new(orderbook.token_id, orderbook.updated_at);
+                orderbook_snapshot_repository.insert_with_key(&mut tx, snapshot_key.to_bytes().as_slice(), orderbook)
+            }),
+            InsertOrderbookSnapshotEntriesFailed
+        );
+        handle!(Self::insert(&mut tx, checkpoint_keyspace, DownloadEndpoint::ClobMarkets.key().into(), checkpoint, &mut Self::checkpoint_bytes), InsertCheckpointFailed);
         handle!(tx.commit(), CommitTransactionFailed);
         handle!(db.persist(PersistMode::Buffer), PersistDatabaseFailed);
         Ok(())
     }
 
-    fn write_events_to_database(db: &SingleWriterTxDatabase, event_keyspace: &SingleWriterTxKeyspace, event_slugs: &mut FxHashSet<String>, events: Vec<Event>) -> Result<(), CacheDownloadCommandWriteEventsToDatabaseError> {
+    fn write_events_to_database(db: &SingleWriterTxDatabase, event_repository: &Repository<GammaEvent>, checkpoint_keyspace: &SingleWriterTxKeyspace, event_slugs: &mut FxHashSet<String>, events: Vec<Event>, checkpoint: DownloadCheckpoint) -> Result<(), CacheDownloadCommandWriteEventsToDatabaseError> {
         use CacheDownloadCommandWriteEventsToDatabaseError::*;
         let event_entries = handle_iter!(
             events
@@ -216,15 +337,20 @@ impl CacheDownloadCommand {
                 .map(|event| {
                     use CacheDownloadCommandEventEntryFromResponseError::*;
                     let event = handle!(GammaEvent::try_from(event), TryFromFailed);
-                    let event_slug = event.slug.clone();
-                    Ok((event_slug, event))
+                    Ok(event)
                 }),
             EventEntryFromResponseFailed
         );
-        let duplicates = Self::get_duplicates(&event_entries, |(event_slug, _)| event_slug.clone(), event_slugs).collect_vec();
+        let duplicates = Self::get_duplicates(&event_entries, CacheEntity::key, event_slugs).collect_vec();
         handle_bool!(!duplicates.is_empty(), DuplicatesFound, duplicates);
         let mut tx = db.write_tx();
-        let _event_inserts = handle_iter!(Self::insert_iter(&mut tx, event_keyspace, event_entries, |(event_slug, _)| event_slug.as_str().into(), |(_event_slug, event)| Self::event_bytes(event)), InsertEventEntriesFailed);
+        let _event_inserts = handle_iter!(
+            event_entries
+                .iter()
+                .map(|event| event_repository.insert(&mut tx, event)),
+            InsertEventEntriesFailed
+        );
+        handle!(Self::insert(&mut tx, checkpoint_keyspace, DownloadEndpoint::GammaEvents.key().into(), checkpoint, &mut Self::checkpoint_bytes), InsertCheckpointFailed);
         handle!(tx.commit(), CommitTransactionFailed);
         handle!(db.persist(PersistMode::Buffer), PersistDatabaseFailed);
         Ok(())
@@ -260,16 +386,6 @@ impl CacheDownloadCommand {
         Ok(())
     }
 
-    fn insert_iter<T, E>(tx: &mut SingleWriterWriteTx, keyspace: &SingleWriterTxKeyspace, values: impl IntoIterator<Item = T>, mut get_key: impl FnMut(&T) -> UserKey, mut serialize: impl FnMut(T) -> Result<Vec<u8>, E>) -> impl Iterator<Item = Result<(), CacheDownloadCommandInsertError<E>>>
-    where
-        E: StdError + Send + Sync + 'static,
-    {
-        values.into_iter().map(move |value| {
-            let key = get_key(&value);
-            Self::insert(tx, keyspace, key, value, &mut serialize)
-        })
-    }
-
     fn get_duplicates<'a, T: 'a, I: Eq + Hash>(values: impl IntoIterator<Item = &'a T>, mut map: impl FnMut(&'a T) -> I, seen: &mut FxHashSet<I>) -> impl Iterator<Item = I> {
         values.into_iter().filter_map(move |x| {
             let input = map(x);
@@ -282,28 +398,9 @@ impl CacheDownloadCommand {
         })
     }
 
-    fn market_response_bytes(market: ClobMarketResponsePrecise) -> Result<Vec<u8>, CacheDownloadCommandMarketResponseBytesError> {
-        use CacheDownloadCommandMarketResponseBytesError::*;
-        let bytes = handle!(to_bytes::<RkyvError>(&market), SerializeFailed, market);
-        Ok(bytes.into_vec())
-    }
-
-    fn market_bytes(market: ClobMarket) -> Result<Vec<u8>, CacheDownloadCommandMarketBytesError> {
-        use CacheDownloadCommandMarketBytesError::*;
-        let bytes = handle!(to_bytes::<RkyvError>(&market), SerializeFailed, market);
-        Ok(bytes.into_vec())
-    }
-
-    fn orderbook_bytes(orderbook: OrderBookSummaryResponse) -> Result<Vec<u8>, CacheDownloadCommandOrderbookBytesError> {
-        use CacheDownloadCommandOrderbookBytesError::*;
-        let (_orderbook, orderbook_precise) = handle!(Self::round_trip_entry::<OrderBookSummaryResponse, OrderBookSummaryResponsePrecise, ConvertOrderBookSummaryResponseToOrderbookError>(orderbook), RoundTripEntryFailed);
-        let bytes = handle!(to_bytes::<RkyvError>(&orderbook_precise), SerializeFailed, orderbook: Box::new(orderbook_precise));
-        Ok(bytes.into_vec())
-    }
-
-    fn event_bytes(event: GammaEvent) -> Result<Vec<u8>, CacheDownloadCommandEventBytesError> {
-        use CacheDownloadCommandEventBytesError::*;
-        let bytes = handle!(to_bytes::<RkyvError>(&event), SerializeFailed, event);
+    fn checkpoint_bytes(checkpoint: DownloadCheckpoint) -> Result<Vec<u8>, CacheDownloadCommandCheckpointBytesError> {
+        use CacheDownloadCommandCheckpointBytesError::*;
+        let bytes = handle!(to_bytes::<RkyvError>(&checkpoint), SerializeFailed, checkpoint);
         Ok(bytes.into_vec())
     }
 
@@ -321,6 +418,16 @@ pub enum CacheDownloadCommandRunError {
     OpenDatabaseFailed { source: FjallError, dir: PathBuf },
     #[error("failed to open keyspace")]
     KeyspaceOpenFailed { source: OpenKeyspaceError },
+    #[error("failed to open repository")]
+    RepositoryOpenFailed { source: RepositoryOpenError },
+    #[error("failed to open market indexes")]
+    MarketIndexesOpenFailed { source: MarketIndexesOpenError },
+    #[error("failed to clear {len} keyspaces", len = source.len())]
+    ClearKeyspacesFailed { source: ErrVec<FjallError> },
+    #[error("failed to create CLOB client")]
+    ClobClientFailed { source: ApiHostArgsClobClientError },
+    #[error("failed to create Gamma client")]
+    GammaClientFailed { source: ApiHostArgsGammaClientError },
     #[error("failed to download market responses")]
     DownloadMarketResponsesFailed { source: CacheDownloadCommandDownloadMarketResponsesError },
     #[error("failed to download gamma events")]
@@ -329,60 +436,88 @@ pub enum CacheDownloadCommandRunError {
 
 #[derive(Error, Debug)]
 pub enum CacheDownloadCommandDownloadMarketResponsesError {
-    #[error("failed to read market keyspace length")]
-    MarketKeyspaceLenFailed { source: FjallError },
+    #[error("failed to read markets download checkpoint")]
+    ReadCheckpointFailed { source: CacheDownloadCommandReadCheckpointError },
+    #[error("failed to count stored markets")]
+    CountStoredFailed { source: RepositoryCountError },
+    #[error("failed to determine markets download start position")]
+    MarketResponsesStartFailed { source: CacheDownloadCommandStartError },
     #[error("failed to fetch markets page with cursor '{next_cursor}'")]
-    FetchMarketsFailed { source: PolymarketError, next_cursor: NextCursor },
+    FetchMarketsFailed { source: HttpMiddlewareCallError<PolymarketError>, next_cursor: NextCursor },
     #[error("found {len} duplicates", len = duplicates.len())]
     DuplicatesFound { duplicates: Vec<String> },
-    #[error("failed to fetch order books")]
-    FetchOrderbooksForTokensFailed { source: CacheDownloadCommandFetchOrderbooksForTokensError },
+    #[error("failed to fetch order books for {len} tokens", len = source.len())]
+    FetchOrderbooksFailed { source: ErrVec<ClobClientOrderBookError> },
+    #[error("failed to create markets download checkpoint")]
+    CheckpointFailed { source: CacheDownloadCommandCheckpointError },
     #[error("failed to persist page to database")]
     WritePageToDatabaseFailed { source: CacheDownloadCommandWritePageToDatabaseError },
 }
 
 #[derive(Error, Debug)]
 pub enum CacheDownloadCommandDownloadGammaEventsError {
-    #[error("failed to read event keyspace length")]
-    EventKeyspaceLenFailed { source: FjallError },
+    #[error("failed to read events download checkpoint")]
+    ReadCheckpointFailed { source: CacheDownloadCommandReadCheckpointError },
+    #[error("failed to count stored events")]
+    CountStoredFailed { source: RepositoryCountError },
+    #[error("failed to determine events download start position")]
+    GammaEventsStartFailed { source: CacheDownloadCommandStartError },
     #[error("failed to fetch gamma events page")]
-    FetchEventsFailed { source: PolymarketError, request: Box<EventsRequest> },
-    #[error("failed to convert event count '{count}' to offset")]
-    EventCountConversionFailed { source: TryFromIntError, count: usize },
+    FetchEventsFailed { source: HttpMiddlewareCallError<PolymarketError>, request: Box<EventsRequest> },
+    #[error("failed to create events download checkpoint")]
+    CheckpointFailed { source: CacheDownloadCommandCheckpointError },
     #[error("failed to persist events to database")]
     WriteEventsToDatabaseFailed { source: CacheDownloadCommandWriteEventsToDatabaseError },
 }
 
 #[derive(Error, Debug)]
-pub enum CacheDownloadCommandEventEntryFromResponseError {
-    #[error("failed to convert gamma event response")]
-    TryFromFailed { source: Box<ConvertGammaEventRawToGammaEventError> },
-    #[error("event response has duplicate event slug '{event_slug}'")]
-    EventSlugDuplicateInvalid { event_slug: String },
+pub enum CacheDownloadCommandStartError {
+    #[error("failed to convert checkpoint offset '{offset}' to usize")]
+    OffsetTryFromFailed { source: TryFromIntError, offset: u64 },
+    #[error("failed to convert checkpoint page count '{page_count}' to usize")]
+    PageCountTryFromFailed { source: TryFromIntError, page_count: u64 },
 }
 
 #[derive(Error, Debug)]
-pub enum CacheDownloadCommandFetchOrderbooksForTokensError {
-    #[error("failed to fetch order books for {len} chunks", len = source.len())]
-    FetchOrderbooksChunkFailed { source: ErrVec<CacheDownloadCommandFetchOrderbooksChunkError> },
+pub enum CacheDownloadCommandCheckpointError {
+    #[error("failed to convert offset '{offset}' to u64")]
+    OffsetTryFromFailed { source: TryFromIntError, offset: usize },
+    #[error("failed to convert page count '{page_count}' to u64")]
+    PageCountTryFromFailed { source: TryFromIntError, page_count: usize },
 }
 
 #[derive(Error, Debug)]
-pub enum CacheDownloadCommandFetchOrderbooksChunkError {
-    #[error("failed to fetch order books for chunk")]
-    OrderBooksFailed { source: PolymarketError, requests: Box<[OrderBookSummaryRequest]> },
+pub enum CacheDownloadCommandReadCheckpointError {
+    #[error("failed to read checkpoint for endpoint '{endpoint}'")]
+    GetFailed { source: FjallError, endpoint: DownloadEndpoint },
+    #[error("failed to deserialize checkpoint for endpoint '{endpoint}'")]
+    DeserializeFailed { source: RkyvError, endpoint: DownloadEndpoint, value: Slice },
+}
+
+#[derive(Error, Debug)]
+pub enum CacheDownloadCommandEventEntryFromResponseError {
+    #[error("failed to convert gamma event response")]
+    TryFromFailed { source: Box<ConvertGammaEventRawToGammaEventError> },
+    #[error("event response has duplicate event slug '{event_slug}'")]
+    EventSlugDuplicateInvalid { event_slug: String },
 }
 
 #[derive(Error, Debug)]
 pub enum CacheDownloadCommandWritePageToDatabaseError {
     #[error("failed to parse {len} market responses", len = source.len())]
     MarketEntriesFromResponseFailed { source: ErrVec<CacheDownloadCommandMarketEntriesFromResponseError> },
+    #[error("failed to read {len} previous market responses", len = source.len())]
+    ReadPreviousMarketResponsesFailed { source: ErrVec<RepositoryGetError> },
     #[error("failed to insert market response entries")]
-    InsertMarketResponseEntriesFailed { source: ErrVec<CacheDownloadCommandInsertError<CacheDownloadCommandMarketResponseBytesError>> },
+    InsertMarketResponseEntriesFailed { source: ErrVec<RepositoryInsertError> },
     #[error("failed to insert market entries")]
-    InsertMarketEntriesFailed { source: ErrVec<CacheDownloadCommandInsertError<CacheDownloadCommandMarketBytesError>> },
+    InsertMarketEntriesFailed { source: ErrVec<RepositoryInsertError> },
     #[error("failed to insert order book entries")]
-    InsertOrderbookEntriesFailed { source: ErrVec<CacheDownloadCommandInsertError<CacheDownloadCommandOrderbookBytesError>> },
+    InsertOrderbookEntriesFailed { source: ErrVec<RepositoryInsertError> },
+    #[error("failed to insert order book snapshot entries")]
+    InsertOrderbookSnapshotEntriesFailed { source: ErrVec<RepositoryInsertError> },
+    #[error("failed to insert download checkpoint")]
+    InsertCheckpointFailed { source: CacheDownloadCommandInsertError<CacheDownloadCommandCheckpointBytesError> },
     #[error("failed to commit database transaction")]
     CommitTransactionFailed { source: FjallError },
     #[error("failed to persist database changes")]
@@ -396,7 +531,9 @@ pub enum CacheDownloadCommandWriteEventsToDatabaseError {
     #[error("found {len} duplicates", len = duplicates.len())]
     DuplicatesFound { duplicates: Vec<String> },
     #[error("failed to insert event entries")]
-    InsertEventEntriesFailed { source: ErrVec<CacheDownloadCommandInsertError<CacheDownloadCommandEventBytesError>> },
+    InsertEventEntriesFailed { source: ErrVec<RepositoryInsertError> },
+    #[error("failed to insert download checkpoint")]
+    InsertCheckpointFailed { source: CacheDownloadCommandInsertError<CacheDownloadCommandCheckpointBytesError> },
     #[error("failed to commit database transaction")]
     CommitTransactionFailed { source: FjallError },
     #[error("failed to persist database changes")]
@@ -433,27 +570,7 @@ where
 }
 
 #[derive(Error, Debug)]
-pub enum CacheDownloadCommandMarketResponseBytesError {
-    #[error("failed to serialize market response")]
-    SerializeFailed { source: RkyvError, market: Box<ClobMarketResponsePrecise> },
-}
-
-#[derive(Error, Debug)]
-pub enum CacheDownloadCommandMarketBytesError {
-    #[error("failed to serialize market")]
-    SerializeFailed { source: RkyvError, market: Box<ClobMarket> },
-}
-
-#[derive(Error, Debug)]
-pub enum CacheDownloadCommandOrderbookBytesError {
-    #[error("failed to round-trip order book summary response")]
-    RoundTripEntryFailed { source: CacheDownloadCommandRoundTripEntryError<OrderBookSummaryResponse, ConvertOrderBookSummaryResponseToOrderbookError> },
-    #[error("failed to serialize order book summary")]
-    SerializeFailed { source: RkyvError, orderbook: Box<OrderBookSummaryResponsePrecise> },
-}
-
-#[derive(Error, Debug)]
-pub enum CacheDownloadCommandEventBytesError {
-    #[error("failed to serialize event response")]
-    SerializeFailed { source: RkyvError, event: Box<GammaEvent> },
+pub enum CacheDownloadCommandCheckpointBytesError {
+    #[error("failed to serialize download checkpoint")]
+    SerializeFailed { source: RkyvError, checkpoint: DownloadCheckpoint },
 }
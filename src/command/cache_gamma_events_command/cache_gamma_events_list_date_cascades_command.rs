@@ -1,16 +1,12 @@
-use crate::{DEFAULT_DB_DIR, GAMMA_EVENTS_KEYSPACE, GammaEvent, OpenKeyspaceError, OutputKind, OutputKindWriteError, open_keyspace};
+use crate::{DEFAULT_DB_DIR, GammaEvent, KEY_VALUE_SEPARATOR, OutputKind, OutputKindWriteError, Repository, RepositoryOpenError, RepositoryReadEntryError};
 use errgonomic::handle;
-use fjall::{Error as FjallError, Guard, Readable, SingleWriterTxDatabase, Slice};
-use rkyv::from_bytes;
-use rkyv::rancor::Error as RkyvError;
+use fjall::{Error as FjallError, SingleWriterTxDatabase, Slice};
 use std::io::{self, Write, stdout};
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::process::ExitCode;
 use thiserror::Error;
 
-const KEY_VALUE_SEPARATOR: &str = ": ";
-
 #[derive(clap::Parser, Clone, Debug)]
 pub struct CacheGammaEventsListDateCascadesCommand {
     #[arg(long, default_value = DEFAULT_DB_DIR)]
@@ -36,21 +32,21 @@ impl CacheGammaEventsListDateCascadesCommand {
             kind,
         } = self;
         let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
-        let keyspace = handle!(open_keyspace(&db, GAMMA_EVENTS_KEYSPACE), OpenKeyspaceFailed);
+        let repository = handle!(Repository::<GammaEvent>::open(&db), OpenRepositoryFailed);
         let snapshot = db.read_tx();
         let offset = offset.unwrap_or(0);
         let limit = limit.map(NonZeroUsize::get).unwrap_or(usize::MAX);
-        let iter = snapshot.iter(&keyspace);
+        let iter = repository.iter(&snapshot);
         let mut stdout = stdout().lock();
         handle!(Self::write_date_cascade_events(iter, &mut stdout, kind, offset, limit), WriteDateCascadesFailed);
         Ok(ExitCode::SUCCESS)
     }
 
-    fn write_date_cascade_events(iter: impl IntoIterator<Item = Guard>, writer: &mut impl Write, kind: OutputKind, offset: usize, limit: usize) -> Result<(), CacheGammaEventsListDateCascadesCommandWriteDateCascadesError> {
+    fn write_date_cascade_events(iter: impl IntoIterator<Item = Result<(Slice, GammaEvent), RepositoryReadEntryError>>, writer: &mut impl Write, kind: OutputKind, offset: usize, limit: usize) -> Result<(), CacheGammaEventsListDateCascadesCommandWriteDateCascadesError> {
         use CacheGammaEventsListDateCascadesCommandWriteDateCascadesError::*;
         let mut entries = iter
             .into_iter()
-            .map(Self::date_cascade_entry_from_guard)
+            .map(Self::date_cascade_entry)
             .filter_map(|result| match result {
                 Ok(Some(entry)) => Some(Ok(entry)),
                 Ok(None) => None,
@@ -69,10 +65,9 @@ impl CacheGammaEventsListDateCascadesCommand {
         })
     }
 
-    fn date_cascade_entry_from_guard(guard: Guard) -> Result<Option<(Slice, Vec<u8>)>, CacheGammaEventsListDateCascadesCommandWriteDateCascadesError> {
+    fn date_cascade_entry(entry: Result<(Slice, GammaEvent), RepositoryReadEntryError>) -> Result<Option<(Slice, Vec<u8>)>, CacheGammaEventsListDateCascadesCommandWriteDateCascadesError> {
         use CacheGammaEventsListDateCascadesCommandWriteDateCascadesError::*;
-        let (key_slice, value_slice) = handle!(guard.into_inner(), ReadEntryFailed);
-        let event = handle!(from_bytes::<GammaEvent, RkyvError>(value_slice.as_ref()), DeserializeFailed, value: value_slice);
+        let (key_slice, event) = handle!(entry, ReadEntryFailed);
         if event.is_date_cascade.unwrap_or_default() {
             let output_bytes = handle!(serde_json::to_vec(&event), SerializeOutputFailed, event: Box::new(event));
             Ok(Some((key_slice, output_bytes)))
@@ -86,8 +81,8 @@ impl CacheGammaEventsListDateCascadesCommand {
 pub enum CacheGammaEventsListDateCascadesCommandRunError {
     #[error("failed to open database at '{dir}'")]
     OpenDatabaseFailed { source: FjallError, dir: PathBuf },
-    #[error("failed to open gamma events keyspace")]
-    OpenKeyspaceFailed { source: OpenKeyspaceError },
+    #[error("failed to open gamma events repository")]
+    OpenRepositoryFailed { source: RepositoryOpenError },
     #[error("failed to write date cascade events output")]
     WriteDateCascadesFailed { source: CacheGammaEventsListDateCascadesCommandWriteDateCascadesError },
 }
@@ -95,9 +90,7 @@ pub enum CacheGammaEventsListDateCascadesCommandRunError {
 #[derive(Error, Debug)]
 pub enum CacheGammaEventsListDateCascadesCommandWriteDateCascadesError {
     #[error("failed to read cache entry")]
-    ReadEntryFailed { source: FjallError },
-    #[error("failed to deserialize event entry")]
-    DeserializeFailed { source: RkyvError, value: Slice },
+    ReadEntryFailed { source: RepositoryReadEntryError },
     #[error("failed to serialize event output")]
     SerializeOutputFailed { source: serde_json::Error, event: Box<GammaEvent> },
     #[error("failed to write output")]
@@ -1,11 +1,10 @@
-use crate::{ConvertGammaEventRawToGammaEventError, DEFAULT_DB_DIR, GAMMA_EVENTS_KEYSPACE, GAMMA_EVENTS_PAGE_SIZE, GammaEvent, GammaEventGetTimeSpreadArbitrageOpportunitiesError, OpenKeyspaceError, open_keyspace};
+use crate::{ApiHostArgs, ApiHostArgsGammaClientError, ConvertGammaEventRawToGammaEventError, DEFAULT_DB_DIR, GAMMA_EVENTS_PAGE_SIZE, GammaEvent, GammaEventGetTimeSpreadArbitrageOpportunitiesError, HttpEndpoint, HttpMiddleware, HttpMiddlewareArgs, HttpMiddlewareCallError, Repository, RepositoryInsertError, RepositoryOpenError, RepositoryReadEntryError};
 use errgonomic::{ErrVec, handle, handle_iter};
-use fjall::{Error as FjallError, Guard, PersistMode, Readable, SingleWriterTxDatabase, SingleWriterTxKeyspace, Slice};
+use fjall::{Error as FjallError, PersistMode, SingleWriterTxDatabase};
 use itertools::Itertools;
 use polymarket_client_sdk::error::Error as PolymarketError;
 use polymarket_client_sdk::gamma::Client as GammaClient;
 use polymarket_client_sdk::gamma::types::request::EventsRequest;
-use rkyv::{from_bytes, rancor::Error as RkyvError, to_bytes};
 use std::io::{Write, stdout};
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
@@ -19,6 +18,12 @@ pub struct CacheGammaEventsMonitorTimeSpreadOpportunitiesCommand {
 
     #[arg(long)]
     pub max_iterations: Option<NonZeroUsize>,
+
+    #[command(flatten)]
+    pub hosts: ApiHostArgs,
+
+    #[command(flatten)]
+    pub http: HttpMiddlewareArgs,
 }
 
 impl CacheGammaEventsMonitorTimeSpreadOpportunitiesCommand {
@@ -27,19 +32,22 @@ impl CacheGammaEventsMonitorTimeSpreadOpportunitiesCommand {
         let Self {
             dir,
             max_iterations,
+            hosts,
+            http,
         } = self;
+        let middleware = http.middleware();
         let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
-        let keyspace = handle!(open_keyspace(&db, GAMMA_EVENTS_KEYSPACE), OpenKeyspaceFailed);
-        let event_ids = handle!(Self::collect_date_cascade_event_ids(&db, &keyspace), CollectDateCascadeEventIdsFailed);
-        let client = GammaClient::default();
+        let repository = handle!(Repository::<GammaEvent>::open(&db), OpenRepositoryFailed);
+        let event_ids = handle!(Self::collect_date_cascade_event_ids(&db, &repository), CollectDateCascadeEventIdsFailed);
+        let client = handle!(hosts.gamma_client(), GammaClientFailed);
         let max_iterations = max_iterations.map(NonZeroUsize::get);
         let mut iterations = 0usize;
         loop {
-            let events = handle!(Self::refresh_date_cascades(&db, &keyspace, &client, &event_ids).await, RefreshDateCascadesFailed);
+            let events = handle!(Self::refresh_date_cascades(&db, &repository, &client, &middleware, &event_ids).await, RefreshDateCascadesFailed);
             let opportunities = handle_iter!(
                 events
                     .iter()
-                    .map(|event| event.get_time_spread_arbitrage_opportunities()),
+                    .map(|event| event.get_time_spread_arbitrage_opportunities(&hosts.gamma_host)),
                 GetTimeSpreadArbitrageOpportunitiesFailed
             );
             let mut stdout = stdout().lock();
@@ -57,40 +65,31 @@ impl CacheGammaEventsMonitorTimeSpreadOpportunitiesCommand {
         Ok(ExitCode::SUCCESS)
     }
 
-    fn collect_date_cascade_event_ids(db: &SingleWriterTxDatabase, keyspace: &SingleWriterTxKeyspace) -> Result<Vec<u64>, CacheGammaEventsMonitorDateCascadesCommandCollectDateCascadeEventIdsError> {
+    fn collect_date_cascade_event_ids(db: &SingleWriterTxDatabase, repository: &Repository<GammaEvent>) -> Result<Vec<u64>, CacheGammaEventsMonitorDateCascadesCommandCollectDateCascadeEventIdsError> {
         use CacheGammaEventsMonitorDateCascadesCommandCollectDateCascadeEventIdsError::*;
         let snapshot = db.read_tx();
-        let iter = snapshot.iter(keyspace);
-        let results = iter
-            .into_iter()
-            .map(Self::date_cascade_event_id_from_guard)
+        let results = repository
+            .iter(&snapshot)
             .filter_map(|result| match result {
-                Ok(Some(event_id)) => Some(Ok(event_id)),
-                Ok(None) => None,
+                Ok((_key, event)) if event.is_date_cascade.unwrap_or_default() => Some(Ok(event.id)),
+                Ok(_) => None,
                 Err(error) => Some(Err(error)),
             });
-        let event_ids = handle_iter!(results, DateCascadeEventIdFromGuardFailed);
+        let event_ids = handle_iter!(results, ReadEntryFailed);
         Ok(event_ids)
     }
 
-    fn date_cascade_event_id_from_guard(guard: Guard) -> Result<Option<u64>, CacheGammaEventsMonitorDateCascadesCommandDateCascadeEventIdFromGuardError> {
-        use CacheGammaEventsMonitorDateCascadesCommandDateCascadeEventIdFromGuardError::*;
-        let (_key, value) = handle!(guard.into_inner(), ReadEntryFailed);
-        let event = handle!(from_bytes::<GammaEvent, RkyvError>(value.as_ref()), DeserializeFailed, value);
-        if event.is_date_cascade.unwrap_or_default() { Ok(Some(event.id)) } else { Ok(None) }
-    }
-
-    async fn refresh_date_cascades(db: &SingleWriterTxDatabase, keyspace: &SingleWriterTxKeyspace, client: &GammaClient, event_ids: &[u64]) -> Result<Vec<GammaEvent>, CacheGammaEventsMonitorDateCascadesCommandRefreshDateCascadesError> {
+    async fn refresh_date_cascades(db: &SingleWriterTxDatabase, repository: &Repository<GammaEvent>, client: &GammaClient, middleware: &HttpMiddleware, event_ids: &[u64]) -> Result<Vec<GammaEvent>, CacheGammaEventsMonitorDateCascadesCommandRefreshDateCascadesError> {
         use CacheGammaEventsMonitorDateCascadesCommandRefreshDateCascadesError::*;
         let mut events_all = Vec::new();
         for chunk in event_ids.chunks(GAMMA_EVENTS_PAGE_SIZE) {
-            let mut events_chunk = handle!(Self::refresh_date_cascade_chunk(db, keyspace, client, chunk).await, RefreshDateCascadesChunkFailed);
+            let mut events_chunk = handle!(Self::refresh_date_cascade_chunk(db, repository, client, middleware, chunk).await, RefreshDateCascadesChunkFailed);
             events_all.append(&mut events_chunk);
         }
         Ok(events_all)
     }
 
-    async fn refresh_date_cascade_chunk(db: &SingleWriterTxDatabase, keyspace: &SingleWriterTxKeyspace, client: &GammaClient, event_ids: &[u64]) -> Result<Vec<GammaEvent>, CacheGammaEventsMonitorDateCascadesCommandRefreshDateCascadesChunkError> {
+    async fn refresh_date_cascade_chunk(db: &SingleWriterTxDatabase, repository: &Repository<GammaEvent>, client: &GammaClient, middleware: &HttpMiddleware, event_ids: &[u64]) -> Result<Vec<GammaEvent>, CacheGammaEventsMonitorDateCascadesCommandRefreshDateCascadesChunkError> {
         use CacheGammaEventsMonitorDateCascadesCommandRefreshDateCascadesChunkError::*;
         let request = EventsRequest::builder()
             .id(event_ids.iter().map(ToString::to_string).collect())
@@ -98,38 +97,30 @@ impl CacheGammaEventsMonitorTimeSpreadOpportunitiesCommand {
             .limit(GAMMA_EVENTS_PAGE_SIZE as i32)
             .ascending(true)
             .build();
-        let events_raw = handle!(client.events(&request).await, EventsFailed, request: Box::new(request));
+        let events_raw = handle!(middleware.call(HttpEndpoint::Gamma, || client.events(&request)).await, EventsFailed, request: Box::new(request));
         let events = handle_iter!(events_raw.into_iter().map(GammaEvent::try_from), TryFromFailed);
-        handle!(Self::write_events_to_database(db, keyspace, &events), WriteEventsToDatabaseFailed);
+        handle!(Self::write_events_to_database(db, repository, &events), WriteEventsToDatabaseFailed);
         Ok(events)
     }
 
-    fn write_events_to_database(db: &SingleWriterTxDatabase, keyspace: &SingleWriterTxKeyspace, events: &[GammaEvent]) -> Result<(), CacheGammaEventsMonitorDateCascadesCommandWriteEventsToDatabaseError> {
+    fn write_events_to_database(db: &SingleWriterTxDatabase, repository: &Repository<GammaEvent>, events: &[GammaEvent]) -> Result<(), CacheGammaEventsMonitorDateCascadesCommandWriteEventsToDatabaseError> {
         use CacheGammaEventsMonitorDateCascadesCommandWriteEventsToDatabaseError::*;
-        let serialized_events = handle_iter!(events.iter().map(Self::serialize_event_entry), SerializeEventEntryFailed);
         let mut tx = db.write_tx();
-        serialized_events.into_iter().for_each(|(event_id, bytes)| {
-            tx.insert(keyspace, event_id, bytes);
-        });
+        let _inserts = handle_iter!(events.iter().map(|event| repository.insert(&mut tx, event)), InsertFailed);
         handle!(tx.commit(), CommitTransactionFailed);
         handle!(db.persist(PersistMode::Buffer), PersistDatabaseFailed);
         Ok(())
     }
-
-    fn serialize_event_entry(event: &GammaEvent) -> Result<(String, Vec<u8>), CacheGammaEventsMonitorDateCascadesCommandSerializeEventEntryError> {
-        use CacheGammaEventsMonitorDateCascadesCommandSerializeEventEntryError::*;
-        let event_id = event.id.to_string();
-        let bytes = handle!(to_bytes::<RkyvError>(event), SerializeFailed, event_id);
-        Ok((event_id, bytes.into_vec()))
-    }
 }
 
 #[derive(Error, Debug)]
 pub enum CacheGammaEventsMonitorDateCascadesCommandRunError {
     #[error("failed to open database at '{dir}'")]
     OpenDatabaseFailed { source: FjallError, dir: PathBuf },
-    #[error("failed to open gamma events keyspace")]
-    OpenKeyspaceFailed { source: OpenKeyspaceError },
+    #[error("failed to open gamma events repository")]
+    OpenRepositoryFailed { source: RepositoryOpenError },
+    #[error("failed to create Gamma client")]
+    GammaClientFailed { source: ApiHostArgsGammaClientError },
     #[error("failed to collect date cascade event ids")]
     CollectDateCascadeEventIdsFailed { source: CacheGammaEventsMonitorDateCascadesCommandCollectDateCascadeEventIdsError },
     #[error("failed to refresh date cascade events")]
@@ -140,16 +131,8 @@ pub enum CacheGammaEventsMonitorDateCascadesCommandRunError {
 
 #[derive(Error, Debug)]
 pub enum CacheGammaEventsMonitorDateCascadesCommandCollectDateCascadeEventIdsError {
-    #[error("failed to process {len} date cascade events", len = source.len())]
-    DateCascadeEventIdFromGuardFailed { source: ErrVec<CacheGammaEventsMonitorDateCascadesCommandDateCascadeEventIdFromGuardError> },
-}
-
-#[derive(Error, Debug)]
-pub enum CacheGammaEventsMonitorDateCascadesCommandDateCascadeEventIdFromGuardError {
-    #[error("failed to read cache entry")]
-    ReadEntryFailed { source: FjallError },
-    #[error("failed to deserialize event entry")]
-    DeserializeFailed { source: RkyvError, value: Slice },
+    #[error("failed to read {len} event entries", len = source.len())]
+    ReadEntryFailed { source: ErrVec<RepositoryReadEntryError> },
 }
 
 #[derive(Error, Debug)]
@@ -161,7 +144,7 @@ pub enum CacheGammaEventsMonitorDateCascadesCommandRefreshDateCascadesError {
 #[derive(Error, Debug)]
 pub enum CacheGammaEventsMonitorDateCascadesCommandRefreshDateCascadesChunkError {
     #[error("failed to fetch gamma events")]
-    EventsFailed { source: PolymarketError, request: Box<EventsRequest> },
+    EventsFailed { source: HttpMiddlewareCallError<PolymarketError>, request: Box<EventsRequest> },
     #[error("failed to convert {len} gamma event responses", len = source.len())]
     TryFromFailed { source: ErrVec<ConvertGammaEventRawToGammaEventError> },
     #[error("failed to persist events to database")]
@@ -170,16 +153,10 @@ pub enum CacheGammaEventsMonitorDateCascadesCommandRefreshDateCascadesChunkError
 
 #[derive(Error, Debug)]
 pub enum CacheGammaEventsMonitorDateCascadesCommandWriteEventsToDatabaseError {
-    #[error("failed to serialize {len} event responses", len = source.len())]
-    SerializeEventEntryFailed { source: ErrVec<CacheGammaEventsMonitorDateCascadesCommandSerializeEventEntryError> },
+    #[error("failed to insert {len} events", len = source.len())]
+    InsertFailed { source: ErrVec<RepositoryInsertError> },
     #[error("failed to commit database transaction")]
     CommitTransactionFailed { source: FjallError },
     #[error("failed to persist database changes")]
     PersistDatabaseFailed { source: FjallError },
 }
-
-#[derive(Error, Debug)]
-pub enum CacheGammaEventsMonitorDateCascadesCommandSerializeEventEntryError {
-    #[error("failed to serialize event response for event '{event_id}'")]
-    SerializeFailed { source: RkyvError, event_id: String },
-}
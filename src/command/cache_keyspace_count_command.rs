@@ -0,0 +1,49 @@
+use crate::{CacheEntity, DEFAULT_DB_DIR, Repository, RepositoryCountError, RepositoryOpenError};
+use errgonomic::handle;
+use fjall::{Error as FjallError, SingleWriterTxDatabase};
+use rkyv::api::high::{HighSerializer, HighValidator};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::de::Pool;
+use rkyv::rancor::Error as RkyvError;
+use rkyv::rancor::Strategy;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use rkyv::{Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Prints the number of entries in a keyspace
+#[derive(clap::Parser, Clone, Debug)]
+pub struct CacheKeyspaceCountCommand {
+    #[arg(long, default_value = DEFAULT_DB_DIR)]
+    pub dir: PathBuf,
+}
+
+impl CacheKeyspaceCountCommand {
+    pub async fn run<T>(self) -> Result<ExitCode, CacheKeyspaceCountCommandRunError>
+    where
+        T: CacheEntity + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, RkyvError>> + RkyvDeserialize<T, Strategy<Pool, RkyvError>>,
+    {
+        use CacheKeyspaceCountCommandRunError::*;
+        let Self {
+            dir,
+        } = self;
+        let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
+        let repository = handle!(Repository::<T>::open(&db), RepositoryOpenFailed);
+        let count = handle!(repository.count(&db.read_tx()), CountFailed);
+        println!("{count}");
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CacheKeyspaceCountCommandRunError {
+    #[error("failed to open database at '{dir}'")]
+    OpenDatabaseFailed { source: FjallError, dir: PathBuf },
+    #[error("failed to open repository")]
+    RepositoryOpenFailed { source: RepositoryOpenError },
+    #[error("failed to count entries")]
+    CountFailed { source: RepositoryCountError },
+}
@@ -0,0 +1,101 @@
+use crate::{CacheEntity, DEFAULT_DB_DIR, EncodeCachedValueError, PrefixKind, Repository, RepositoryOpenError, RepositoryReadEntryError, TranscodeFormat, encode_cached_value};
+use errgonomic::handle;
+use fjall::{Error as FjallError, SingleWriterTxDatabase, Slice};
+use rkyv::api::high::{HighSerializer, HighValidator};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::de::Pool;
+use rkyv::rancor::Error as RkyvError;
+use rkyv::rancor::Strategy;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use rkyv::{Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write, stdout};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Writes every value of a keyspace with a length prefix (the output can be piped into `transcode`)
+#[derive(clap::Parser, Clone, Debug)]
+pub struct CacheKeyspaceExportCommand {
+    #[arg(long, default_value = DEFAULT_DB_DIR)]
+    pub dir: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = TranscodeFormat::Rkyv)]
+    pub format: TranscodeFormat,
+
+    #[arg(long, value_enum, default_value_t = PrefixKind::LenU64Le)]
+    pub prefix: PrefixKind,
+
+    /// The output file (stdout if not set)
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+impl CacheKeyspaceExportCommand {
+    pub async fn run<T>(self) -> Result<ExitCode, CacheKeyspaceExportCommandRunError>
+    where
+        T: CacheEntity + Serialize + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, RkyvError>> + RkyvDeserialize<T, Strategy<Pool, RkyvError>>,
+    {
+        use CacheKeyspaceExportCommandRunError::*;
+        let Self {
+            dir,
+            format,
+            prefix,
+            output,
+        } = self;
+        let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
+        let repository = handle!(Repository::<T>::open(&db), RepositoryOpenFailed);
+        let writer: Box<dyn Write> = match output {
+            Some(output) => Box::new(handle!(File::create(&output), CreateFileFailed, output)),
+            None => Box::new(stdout().lock()),
+        };
+        let mut writer = BufWriter::new(writer);
+        let snapshot = db.read_tx();
+        for entry in repository.iter(&snapshot) {
+            handle!(Self::write_value(entry, &mut writer, format, prefix), WriteValueFailed);
+        }
+        handle!(writer.flush(), FlushFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+
+    fn write_value<T>(entry: Result<(Slice, T), RepositoryReadEntryError>, writer: &mut impl Write, format: TranscodeFormat, prefix: PrefixKind) -> Result<(), CacheKeyspaceExportCommandWriteValueError>
+    where
+        T: Serialize + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+    {
+        use CacheKeyspaceExportCommandWriteValueError::*;
+        let (key_slice, value) = handle!(entry, ReadEntryFailed);
+        let value_bytes = handle!(encode_cached_value(format, &value), EncodeCachedValueFailed, key: key_slice);
+        handle!(prefix.write(&value_bytes, writer), WritePrefixFailed);
+        handle!(writer.write_all(&value_bytes), WriteAllFailed);
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CacheKeyspaceExportCommandRunError {
+    #[error("failed to open database at '{dir}'")]
+    OpenDatabaseFailed { source: FjallError, dir: PathBuf },
+    #[error("failed to open repository")]
+    RepositoryOpenFailed { source: RepositoryOpenError },
+    #[error("failed to create output file '{output}'")]
+    CreateFileFailed { source: io::Error, output: PathBuf },
+    #[error("failed to write value")]
+    WriteValueFailed { source: CacheKeyspaceExportCommandWriteValueError },
+    #[error("failed to flush output")]
+    FlushFailed { source: io::Error },
+}
+
+#[derive(Error, Debug)]
+pub enum CacheKeyspaceExportCommandWriteValueError {
+    #[error("failed to read cache entry")]
+    ReadEntryFailed { source: RepositoryReadEntryError },
+    #[error("failed to encode cached value")]
+    EncodeCachedValueFailed { source: EncodeCachedValueError, key: Slice },
+    #[error("failed to write length prefix")]
+    WritePrefixFailed { source: io::Error },
+    #[error("failed to write value")]
+    WriteAllFailed { source: io::Error },
+}
@@ -0,0 +1,85 @@
+use crate::{CacheEntity, DEFAULT_DB_DIR, EncodeCachedValueLineError, Repository, RepositoryGetError, RepositoryOpenError, TranscodeFormat, encode_cached_value_line};
+use errgonomic::{handle, handle_opt};
+use fjall::{Error as FjallError, SingleWriterTxDatabase};
+use rkyv::api::high::{HighSerializer, HighValidator};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::de::Pool;
+use rkyv::rancor::Error as RkyvError;
+use rkyv::rancor::Strategy;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use rkyv::{Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use serde::Serialize;
+use std::io::{self, Write, stdout};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Prints the value of a single key
+#[derive(clap::Parser, Clone, Debug)]
+pub struct CacheKeyspaceGetCommand {
+    #[arg(long, default_value = DEFAULT_DB_DIR)]
+    pub dir: PathBuf,
+
+    /// The rkyv value is base64-encoded
+    #[arg(long, value_enum, default_value_t = TranscodeFormat::SerdeJson)]
+    pub format: TranscodeFormat,
+
+    pub key: String,
+}
+
+impl CacheKeyspaceGetCommand {
+    pub async fn run<T>(self) -> Result<ExitCode, CacheKeyspaceGetCommandRunError>
+    where
+        T: CacheEntity + Serialize + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, RkyvError>> + RkyvDeserialize<T, Strategy<Pool, RkyvError>>,
+    {
+        use CacheKeyspaceGetCommandRunError::*;
+        let mut stdout = stdout().lock();
+        handle!(self.write::<T>(&mut stdout), WriteFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+
+    pub fn write<T>(self, writer: &mut impl Write) -> Result<(), CacheKeyspaceGetCommandWriteError>
+    where
+        T: CacheEntity + Serialize + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, RkyvError>> + RkyvDeserialize<T, Strategy<Pool, RkyvError>>,
+    {
+        use CacheKeyspaceGetCommandWriteError::*;
+        let Self {
+            dir,
+            format,
+            key,
+        } = self;
+        let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
+        let repository = handle!(Repository::<T>::open(&db), RepositoryOpenFailed);
+        let value_opt = handle!(repository.get(&db.read_tx(), &key), GetFailed, key);
+        let value = handle_opt!(value_opt, KeyNotFound, key);
+        let value_bytes = handle!(encode_cached_value_line(format, &value), EncodeCachedValueLineFailed, key);
+        handle!(writer.write_all(&value_bytes), WriteAllFailed);
+        handle!(writer.write_all(b"\n"), WriteAllFailed);
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CacheKeyspaceGetCommandRunError {
+    #[error("failed to write value")]
+    WriteFailed { source: CacheKeyspaceGetCommandWriteError },
+}
+
+#[derive(Error, Debug)]
+pub enum CacheKeyspaceGetCommandWriteError {
+    #[error("failed to open database at '{dir}'")]
+    OpenDatabaseFailed { source: FjallError, dir: PathBuf },
+    #[error("failed to open repository")]
+    RepositoryOpenFailed { source: RepositoryOpenError },
+    #[error("failed to get value for key '{key}'")]
+    GetFailed { source: RepositoryGetError, key: String },
+    #[error("key '{key}' not found")]
+    KeyNotFound { key: String },
+    #[error("failed to encode value for key '{key}'")]
+    EncodeCachedValueLineFailed { source: EncodeCachedValueLineError, key: String },
+    #[error("failed to write output")]
+    WriteAllFailed { source: io::Error },
+}
@@ -0,0 +1,114 @@
+use crate::{CacheEntity, DEFAULT_DB_DIR, EncodeCachedValueLineError, KEY_VALUE_SEPARATOR, OutputKind, OutputKindWriteError, Repository, RepositoryOpenError, RepositoryReadEntryError, TranscodeFormat, encode_cached_value_line};
+use errgonomic::handle;
+use fjall::{Error as FjallError, SingleWriterTxDatabase, Slice};
+use rkyv::api::high::{HighSerializer, HighValidator};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::de::Pool;
+use rkyv::rancor::Error as RkyvError;
+use rkyv::rancor::Strategy;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use rkyv::{Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use serde::Serialize;
+use std::io::{self, Write, stdout};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Lists the entries of a keyspace (one entry per line)
+#[derive(clap::Parser, Clone, Debug)]
+pub struct CacheKeyspaceListCommand {
+    #[arg(long, default_value = DEFAULT_DB_DIR)]
+    pub dir: PathBuf,
+
+    #[arg(long)]
+    pub offset: Option<usize>,
+
+    #[arg(long)]
+    pub limit: Option<NonZeroUsize>,
+
+    #[arg(long, value_enum, default_value_t = OutputKind::KeyValue)]
+    pub kind: OutputKind,
+
+    /// The rkyv values are base64-encoded (one entry per line)
+    #[arg(long, value_enum, default_value_t = TranscodeFormat::SerdeJson)]
+    pub format: TranscodeFormat,
+}
+
+impl CacheKeyspaceListCommand {
+    pub async fn run<T>(self) -> Result<ExitCode, CacheKeyspaceListCommandRunError>
+    where
+        T: CacheEntity + Serialize + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, RkyvError>> + RkyvDeserialize<T, Strategy<Pool, RkyvError>>,
+    {
+        use CacheKeyspaceListCommandRunError::*;
+        let mut stdout = stdout().lock();
+        handle!(self.write::<T>(&mut stdout), WriteFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+
+    pub fn write<T>(self, writer: &mut impl Write) -> Result<(), CacheKeyspaceListCommandWriteError>
+    where
+        T: CacheEntity + Serialize + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, RkyvError>> + RkyvDeserialize<T, Strategy<Pool, RkyvError>>,
+    {
+        use CacheKeyspaceListCommandWriteError::*;
+        let Self {
+            dir,
+            offset,
+            limit,
+            kind,
+            format,
+        } = self;
+        let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
+        let repository = handle!(Repository::<T>::open(&db), RepositoryOpenFailed);
+        let snapshot = db.read_tx();
+        let offset = offset.unwrap_or(0);
+        let limit = limit.map(NonZeroUsize::get).unwrap_or(usize::MAX);
+        for entry in repository.iter(&snapshot).skip(offset).take(limit) {
+            handle!(Self::write_entry(entry, writer, kind, format), WriteEntryFailed);
+        }
+        Ok(())
+    }
+
+    fn write_entry<T>(entry: Result<(Slice, T), RepositoryReadEntryError>, writer: &mut impl Write, kind: OutputKind, format: TranscodeFormat) -> Result<(), CacheKeyspaceListCommandWriteEntryError>
+    where
+        T: Serialize + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+    {
+        use CacheKeyspaceListCommandWriteEntryError::*;
+        let (key_slice, value) = handle!(entry, ReadEntryFailed);
+        let value_bytes = handle!(encode_cached_value_line(format, &value), EncodeCachedValueLineFailed, key: key_slice);
+        handle!(kind.write(writer, key_slice.as_ref(), &value_bytes, KEY_VALUE_SEPARATOR), WriteFailed);
+        handle!(writer.write_all(b"\n"), WriteAllFailed);
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CacheKeyspaceListCommandRunError {
+    #[error("failed to write entries")]
+    WriteFailed { source: CacheKeyspaceListCommandWriteError },
+}
+
+#[derive(Error, Debug)]
+pub enum CacheKeyspaceListCommandWriteError {
+    #[error("failed to open database at '{dir}'")]
+    OpenDatabaseFailed { source: FjallError, dir: PathBuf },
+    #[error("failed to open repository")]
+    RepositoryOpenFailed { source: RepositoryOpenError },
+    #[error("failed to write entry")]
+    WriteEntryFailed { source: CacheKeyspaceListCommandWriteEntryError },
+}
+
+#[derive(Error, Debug)]
+pub enum CacheKeyspaceListCommandWriteEntryError {
+    #[error("failed to read cache entry")]
+    ReadEntryFailed { source: RepositoryReadEntryError },
+    #[error("failed to encode cached value")]
+    EncodeCachedValueLineFailed { source: EncodeCachedValueLineError, key: Slice },
+    #[error("failed to write output")]
+    WriteFailed { source: OutputKindWriteError },
+    #[error("failed to write output newline")]
+    WriteAllFailed { source: io::Error },
+}
@@ -1,6 +1,8 @@
+use crate::{CacheKeyspaceCountCommand, CacheKeyspaceCountCommandRunError, CacheKeyspaceExportCommand, CacheKeyspaceExportCommandRunError, CacheKeyspaceGetCommand, CacheKeyspaceGetCommandRunError, CacheKeyspaceListCommand, CacheKeyspaceListCommandRunError, ClobMarketResponsePrecise};
+use CacheMarketResponsesSubcommand::*;
+use errgonomic::map_err;
 use std::process::ExitCode;
 use thiserror::Error;
-// use CacheMarketResponsesSubcommand::*;
 
 #[derive(clap::Parser, Clone, Debug)]
 pub struct CacheMarketResponsesCommand {
@@ -9,17 +11,36 @@ pub struct CacheMarketResponsesCommand {
 }
 
 #[derive(clap::Subcommand, Clone, Debug)]
-pub enum CacheMarketResponsesSubcommand {}
+pub enum CacheMarketResponsesSubcommand {
+    List(CacheKeyspaceListCommand),
+    Get(CacheKeyspaceGetCommand),
+    Count(CacheKeyspaceCountCommand),
+    Export(CacheKeyspaceExportCommand),
+}
 
 impl CacheMarketResponsesCommand {
     pub async fn run(self) -> Result<ExitCode, CacheMarketResponsesCommandRunError> {
-        // use CacheMarketResponsesCommandRunError::*;
-        // let Self {
-        //     subcommand: _,
-        // } = self;
-        Ok(ExitCode::SUCCESS)
+        use CacheMarketResponsesCommandRunError::*;
+        let Self {
+            subcommand,
+        } = self;
+        match subcommand {
+            List(command) => map_err!(command.run::<ClobMarketResponsePrecise>().await, ListFailed),
+            Get(command) => map_err!(command.run::<ClobMarketResponsePrecise>().await, GetFailed),
+            Count(command) => map_err!(command.run::<ClobMarketResponsePrecise>().await, CountFailed),
+            Export(command) => map_err!(command.run::<ClobMarketResponsePrecise>().await, ExportFailed),
+        }
     }
 }
 
 #[derive(Error, Debug)]
-pub enum CacheMarketResponsesCommandRunError {}
+pub enum CacheMarketResponsesCommandRunError {
+    #[error("failed to list market responses")]
+    ListFailed { source: CacheKeyspaceListCommandRunError },
+    #[error("failed to get market responses")]
+    GetFailed { source: CacheKeyspaceGetCommandRunError },
+    #[error("failed to count market responses")]
+    CountFailed { source: CacheKeyspaceCountCommandRunError },
+    #[error("failed to export market responses")]
+    ExportFailed { source: CacheKeyspaceExportCommandRunError },
+}
@@ -0,0 +1,174 @@
+use crate::{CacheEntity, ClobMarket, ClobMarketResponsePrecise, DEFAULT_DB_DIR, GammaEvent, KeyspaceMigrationReport, OrderBookSummaryResponsePrecise, Repository, RepositoryGetError, RepositoryInsertError, RepositoryOpenError, RepositoryReadEntryError};
+use errgonomic::{ErrVec, handle, handle_iter};
+use fjall::{Error as FjallError, PersistMode, SingleWriterTxDatabase, Slice};
+use rkyv::api::high::{HighSerializer, HighValidator};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::de::Pool;
+use rkyv::rancor::{Error as RkyvError, Strategy};
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use rkyv::{Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use rustc_hash::FxHashSet;
+use std::io::{self, Write, stdout};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Rewrites the entries that are stored under a key other than [`CacheEntity::key`] (e.g. the gamma events that were keyed by id instead of slug)
+///
+/// If an entry with the derived key already exists, it is kept, and the entry with the stale key is removed.
+/// Prints a report for every keyspace.
+#[derive(clap::Parser, Clone, Debug)]
+pub struct CacheMigrateCommand {
+    #[arg(long, default_value = DEFAULT_DB_DIR)]
+    pub dir: PathBuf,
+
+    /// Print the reports without writing to the database
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl CacheMigrateCommand {
+    pub async fn run(self) -> Result<ExitCode, CacheMigrateCommandRunError> {
+        use CacheMigrateCommandRunError::*;
+        let Self {
+            dir,
+            dry_run,
+        } = self;
+        let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
+        let reports = [
+            handle!(Self::migrate::<ClobMarketResponsePrecise>(&db, dry_run), MigrateFailed, keyspace: ClobMarketResponsePrecise::KEYSPACE),
+            handle!(Self::migrate::<ClobMarket>(&db, dry_run), MigrateFailed, keyspace: ClobMarket::KEYSPACE),
+            handle!(Self::migrate::<OrderBookSummaryResponsePrecise>(&db, dry_run), MigrateFailed, keyspace: OrderBookSummaryResponsePrecise::KEYSPACE),
+            handle!(Self::migrate::<GammaEvent>(&db, dry_run), MigrateFailed, keyspace: GammaEvent::KEYSPACE),
+        ];
+        if !dry_run {
+            handle!(db.persist(PersistMode::Buffer), PersistDatabaseFailed);
+        }
+        let mut stdout = stdout().lock();
+        for report in reports {
+            handle!(serde_json::to_writer(&mut stdout, &report), SerializeReportFailed);
+            handle!(stdout.write_all(b"\n"), WriteAllFailed);
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+
+    /// Moves the entries that are stored under a stale key to the key that is derived by [`CacheEntity::key`]
+    ///
+    /// PRUNING: a stale entry is removed without comparing the values if the derived key is already taken, either by an existing entry (which has been written by a newer download) or by an earlier stale entry in key order.
+    fn migrate<T>(db: &SingleWriterTxDatabase, dry_run: bool) -> Result<KeyspaceMigrationReport, CacheMigrateCommandMigrateError>
+    where
+        T: CacheEntity + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, RkyvError>> + RkyvDeserialize<T, Strategy<Pool, RkyvError>>,
+    {
+        use CacheMigrateCommandMigrateError::*;
+        let repository = handle!(Repository::<T>::open(db), OpenRepositoryFailed);
+        let snapshot = db.read_tx();
+        let entries = handle_iter!(repository.iter(&snapshot), ReadEntryFailed);
+        let scanned = entries.len();
+        let stale_entries = entries
+            .into_iter()
+            .filter(|(key, value)| key.as_ref() != value.key().as_bytes())
+            .collect::<Vec<(Slice, T)>>();
+        let existing = handle_iter!(
+            stale_entries
+                .iter()
+                .map(|(_key, value)| repository.get(&snapshot, &value.key())),
+            GetFailed
+        );
+        let mut report = KeyspaceMigrationReport::new(T::KEYSPACE.to_string(), scanned, 0, 0);
+        // several stale entries may have the same derived key, so only the first one is moved
+        let mut inserted_keys = FxHashSet::default();
+        let mut tx = db.write_tx();
+        for ((key, value), existing) in stale_entries.iter().zip(existing) {
+            tx.remove(&repository.keyspace, key.clone());
+            if existing.is_none() && inserted_keys.insert(value.key()) {
+                handle!(repository.insert(&mut tx, value), InsertFailed);
+                report.rekeyed = report.rekeyed.saturating_add(1);
+            } else {
+                report.removed = report.removed.saturating_add(1);
+            }
+        }
+        if !dry_run {
+            handle!(tx.commit(), CommitTransactionFailed);
+        }
+        Ok(report)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CacheMigrateCommandRunError {
+    #[error("failed to open database at '{dir}'")]
+    OpenDatabaseFailed { source: FjallError, dir: PathBuf },
+    #[error("failed to migrate keyspace '{keyspace}'")]
+    MigrateFailed { source: CacheMigrateCommandMigrateError, keyspace: &'static str },
+    #[error("failed to persist database changes")]
+    PersistDatabaseFailed { source: FjallError },
+    #[error("failed to serialize migration report")]
+    SerializeReportFailed { source: serde_json::Error },
+    #[error("failed to write output")]
+    WriteAllFailed { source: io::Error },
+}
+
+#[derive(Error, Debug)]
+pub enum CacheMigrateCommandMigrateError {
+    #[error("failed to open repository")]
+    OpenRepositoryFailed { source: RepositoryOpenError },
+    #[error("failed to read {len} entries", len = source.len())]
+    ReadEntryFailed { source: ErrVec<RepositoryReadEntryError> },
+    #[error("failed to look up {len} derived keys", len = source.len())]
+    GetFailed { source: ErrVec<RepositoryGetError> },
+    #[error("failed to insert entry under the derived key")]
+    InsertFailed { source: RepositoryInsertError },
+    #[error("failed to commit database transaction")]
+    CommitTransactionFailed { source: FjallError },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rkyv::to_bytes;
+
+    #[test]
+    fn must_rekey_events_by_slug() -> Result<(), MustRekeyEventsBySlugError> {
+        use MustRekeyEventsBySlugError::*;
+        let dir = handle!(tempfile::tempdir(), TempdirFailed);
+        let db = handle!(SingleWriterTxDatabase::builder(dir.path()).open(), OpenDatabaseFailed);
+        let repository = handle!(Repository::<GammaEvent>::open(&db), OpenRepositoryFailed);
+        let event = GammaEvent {
+            id: 42,
+            slug: "will-it-rain".to_string(),
+            markets: vec![],
+            is_date_cascade: None,
+        };
+        let bytes = handle!(to_bytes::<RkyvError>(&event), SerializeFailed);
+        let mut tx = db.write_tx();
+        // the monitor command used to key the events by id
+        tx.insert(&repository.keyspace, event.id.to_string(), bytes.into_vec());
+        handle!(tx.commit(), CommitFailed);
+        let report = handle!(CacheMigrateCommand::migrate::<GammaEvent>(&db, false), MigrateFailed);
+        assert_eq!(report, KeyspaceMigrationReport::new(GammaEvent::KEYSPACE.to_string(), 1, 1, 0));
+        let snapshot = db.read_tx();
+        assert_eq!(handle!(repository.get(&snapshot, "will-it-rain"), GetFailed), Some(event));
+        assert_eq!(handle!(repository.get(&snapshot, "42"), GetFailed), None);
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum MustRekeyEventsBySlugError {
+        #[error("failed to create temporary directory")]
+        TempdirFailed { source: io::Error },
+        #[error("failed to open database")]
+        OpenDatabaseFailed { source: FjallError },
+        #[error("failed to open repository")]
+        OpenRepositoryFailed { source: RepositoryOpenError },
+        #[error("failed to serialize event")]
+        SerializeFailed { source: RkyvError },
+        #[error("failed to commit transaction")]
+        CommitFailed { source: FjallError },
+        #[error("failed to migrate events")]
+        MigrateFailed { source: CacheMigrateCommandMigrateError },
+        #[error("failed to get event")]
+        GetFailed { source: RepositoryGetError },
+    }
+}
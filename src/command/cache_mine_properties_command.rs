@@ -0,0 +1,75 @@
+use crate::{ClobMarketResponsePrecise, DEFAULT_DB_DIR, ImplicationMiner, ReportFormat, ReportFormatWriteError, Repository, RepositoryOpenError, RepositoryReadEntryError};
+use errgonomic::{ErrVec, handle};
+use fjall::{Error as FjallError, SingleWriterTxDatabase};
+use rust_decimal::Decimal;
+use std::io::stdout;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Finds the implications between the boolean features of the cached market responses (e.g. `archived => closed`)
+#[derive(clap::Parser, Clone, Debug)]
+pub struct CacheMinePropertiesCommand {
+    #[arg(long, default_value = DEFAULT_DB_DIR)]
+    pub dir: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = ReportFormat::Jsonl)]
+    pub format: ReportFormat,
+
+    /// The minimum number of markets that satisfy the antecedent
+    #[arg(long, default_value_t = 100)]
+    pub min_support: usize,
+
+    /// The minimum share of the supporting markets that satisfy the consequent (use 1 to report only the implications without counterexamples)
+    #[arg(long, default_value = "0.99")]
+    pub min_confidence: Decimal,
+
+    /// The maximum number of counterexample slugs per implication
+    #[arg(long, default_value_t = 3)]
+    pub example_limit: usize,
+}
+
+impl CacheMinePropertiesCommand {
+    pub async fn run(self) -> Result<ExitCode, CacheMinePropertiesCommandRunError> {
+        use CacheMinePropertiesCommandRunError::*;
+        let Self {
+            dir,
+            format,
+            min_support,
+            min_confidence,
+            example_limit,
+        } = self;
+        let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
+        let repository = handle!(Repository::<ClobMarketResponsePrecise>::open(&db), RepositoryOpenFailed);
+        let snapshot = db.read_tx();
+        let mut miner = ImplicationMiner::new(example_limit);
+        let mut errors = Vec::new();
+        for entry in repository.iter(&snapshot) {
+            match entry {
+                Ok((_key, market)) => miner.observe(&market),
+                Err(error) => errors.push(error),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(ReadMarketFailed {
+                source: errors.into(),
+            });
+        }
+        let implications = miner.into_implications(min_support, min_confidence);
+        let mut stdout = stdout().lock();
+        handle!(format.write(&mut stdout, &implications), WriteReportFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CacheMinePropertiesCommandRunError {
+    #[error("failed to open database at '{dir}'")]
+    OpenDatabaseFailed { source: FjallError, dir: PathBuf },
+    #[error("failed to open repository")]
+    RepositoryOpenFailed { source: RepositoryOpenError },
+    #[error("failed to read {len} market responses", len = source.len())]
+    ReadMarketFailed { source: ErrVec<RepositoryReadEntryError> },
+    #[error("failed to write report")]
+    WriteReportFailed { source: ReportFormatWriteError },
+}
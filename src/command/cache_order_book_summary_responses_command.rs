@@ -1,6 +1,8 @@
+use crate::{CacheKeyspaceCountCommand, CacheKeyspaceCountCommandRunError, CacheKeyspaceExportCommand, CacheKeyspaceExportCommandRunError, CacheKeyspaceGetCommand, CacheKeyspaceGetCommandRunError, CacheKeyspaceListCommand, CacheKeyspaceListCommandRunError, OrderBookSummaryResponsePrecise};
+use CacheOrderBookSummaryResponsesSubcommand::*;
+use errgonomic::map_err;
 use std::process::ExitCode;
 use thiserror::Error;
-// use CacheOrderBookSummaryResponsesSubcommand::*;
 
 #[derive(clap::Parser, Clone, Debug)]
 pub struct CacheOrderBookSummaryResponsesCommand {
@@ -9,18 +11,36 @@ pub struct CacheOrderBookSummaryResponsesCommand {
 }
 
 #[derive(clap::Subcommand, Clone, Debug)]
-pub enum CacheOrderBookSummaryResponsesSubcommand {}
+pub enum CacheOrderBookSummaryResponsesSubcommand {
+    List(CacheKeyspaceListCommand),
+    Get(CacheKeyspaceGetCommand),
+    Count(CacheKeyspaceCountCommand),
+    Export(CacheKeyspaceExportCommand),
+}
 
 impl CacheOrderBookSummaryResponsesCommand {
     pub async fn run(self) -> Result<ExitCode, CacheOrderBookSummaryResponsesCommandRunError> {
-        // use CacheOrderBookSummaryResponsesCommandRunError::*;
-        // let Self {
-        //     subcommand,
-        // } = self;
-        // match subcommand {}
-        Ok(ExitCode::SUCCESS)
+        use CacheOrderBookSummaryResponsesCommandRunError::*;
+        let Self {
+            subcommand,
+        } = self;
+        match subcommand {
+            List(command) => map_err!(command.run::<OrderBookSummaryResponsePrecise>().await, ListFailed),
+            Get(command) => map_err!(command.run::<OrderBookSummaryResponsePrecise>().await, GetFailed),
+            Count(command) => map_err!(command.run::<OrderBookSummaryResponsePrecise>().await, CountFailed),
+            Export(command) => map_err!(command.run::<OrderBookSummaryResponsePrecise>().await, ExportFailed),
+        }
     }
 }
 
 #[derive(Error, Debug)]
-pub enum CacheOrderBookSummaryResponsesCommandRunError {}
+pub enum CacheOrderBookSummaryResponsesCommandRunError {
+    #[error("failed to list order book summary responses")]
+    ListFailed { source: CacheKeyspaceListCommandRunError },
+    #[error("failed to get order book summary responses")]
+    GetFailed { source: CacheKeyspaceGetCommandRunError },
+    #[error("failed to count order book summary responses")]
+    CountFailed { source: CacheKeyspaceCountCommandRunError },
+    #[error("failed to export order book summary responses")]
+    ExportFailed { source: CacheKeyspaceExportCommandRunError },
+}
@@ -0,0 +1,64 @@
+use crate::{CacheEntity, ClobMarketResponsePrecise, DEFAULT_DB_DIR, MarketIndexes, MarketIndexesOpenError, MarketIndexesRebuildError, Repository, RepositoryOpenError};
+use errgonomic::handle;
+use fjall::{Error as FjallError, PersistMode, SingleWriterTxDatabase};
+use serde::Serialize;
+use std::io::{self, Write, stdout};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Rebuilds the secondary indexes of the market responses (see [`MarketIndexes`])
+///
+/// Run it after downloading the market responses with a version that didn't maintain the indexes, or after changing the index layout.
+/// The indexes are rewritten in chunks, so run it again if it fails (the indexes are incomplete until it succeeds).
+#[derive(clap::Parser, Clone, Debug)]
+pub struct CacheReindexCommand {
+    #[arg(long, default_value = DEFAULT_DB_DIR)]
+    pub dir: PathBuf,
+}
+
+impl CacheReindexCommand {
+    pub async fn run(self) -> Result<ExitCode, CacheReindexCommandRunError> {
+        use CacheReindexCommandRunError::*;
+        let Self {
+            dir,
+        } = self;
+        let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
+        let repository = handle!(Repository::<ClobMarketResponsePrecise>::open(&db), OpenRepositoryFailed);
+        let indexes = handle!(MarketIndexes::open(&db), OpenIndexesFailed);
+        let indexed = handle!(indexes.rebuild(&db, &repository), RebuildFailed);
+        handle!(db.persist(PersistMode::Buffer), PersistDatabaseFailed);
+        let report = CacheReindexReport {
+            keyspace: ClobMarketResponsePrecise::KEYSPACE,
+            indexed,
+        };
+        let mut stdout = stdout().lock();
+        handle!(serde_json::to_writer(&mut stdout, &report), SerializeReportFailed);
+        handle!(stdout.write_all(b"\n"), WriteAllFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct CacheReindexReport {
+    keyspace: &'static str,
+    indexed: usize,
+}
+
+#[derive(Error, Debug)]
+pub enum CacheReindexCommandRunError {
+    #[error("failed to open database at '{dir}'")]
+    OpenDatabaseFailed { source: FjallError, dir: PathBuf },
+    #[error("failed to open repository")]
+    OpenRepositoryFailed { source: RepositoryOpenError },
+    #[error("failed to open market indexes")]
+    OpenIndexesFailed { source: MarketIndexesOpenError },
+    #[error("failed to rebuild market indexes")]
+    RebuildFailed { source: MarketIndexesRebuildError },
+    #[error("failed to persist database changes")]
+    PersistDatabaseFailed { source: FjallError },
+    #[error("failed to serialize reindex report")]
+    SerializeReportFailed { source: serde_json::Error },
+    #[error("failed to write output")]
+    WriteAllFailed { source: io::Error },
+}
@@ -12,6 +12,16 @@ pub struct ClobCommand {
 #[derive(clap::Subcommand, Clone, Debug)]
 pub enum ClobSubcommand {
     PlaceLimitOrder(clob_place_limit_order_command::ClobPlaceLimitOrderCommand),
+    PlaceMarketOrder(clob_place_market_order_command::ClobPlaceMarketOrderCommand),
+    PlaceOrders(clob_place_orders_command::ClobPlaceOrdersCommand),
+    PostSignedOrder(clob_post_signed_order_command::ClobPostSignedOrderCommand),
+    WatchOrderBooks(clob_watch_order_books_command::ClobWatchOrderBooksCommand),
+    CancelOrder(clob_cancel_order_command::ClobCancelOrderCommand),
+    CancelAll(clob_cancel_all_command::ClobCancelAllCommand),
+    CancelMarketOrders(clob_cancel_market_orders_command::ClobCancelMarketOrdersCommand),
+    ListOpenOrders(clob_list_open_orders_command::ClobListOpenOrdersCommand),
+    GetOrder(clob_get_order_command::ClobGetOrderCommand),
+    ListTrades(clob_list_trades_command::ClobListTradesCommand),
 }
 
 impl ClobCommand {
@@ -22,6 +32,16 @@ impl ClobCommand {
         } = self;
         match subcommand {
             PlaceLimitOrder(command) => map_err!(command.run().await, ClobPlaceLimitOrderCommandRunFailed),
+            PlaceMarketOrder(command) => map_err!(command.run().await, ClobPlaceMarketOrderCommandRunFailed),
+            PlaceOrders(command) => map_err!(command.run().await, ClobPlaceOrdersCommandRunFailed),
+            PostSignedOrder(command) => map_err!(command.run().await, ClobPostSignedOrderCommandRunFailed),
+            WatchOrderBooks(command) => map_err!(command.run().await, ClobWatchOrderBooksCommandRunFailed),
+            CancelOrder(command) => map_err!(command.run().await, ClobCancelOrderCommandRunFailed),
+            CancelAll(command) => map_err!(command.run().await, ClobCancelAllCommandRunFailed),
+            CancelMarketOrders(command) => map_err!(command.run().await, ClobCancelMarketOrdersCommandRunFailed),
+            ListOpenOrders(command) => map_err!(command.run().await, ClobListOpenOrdersCommandRunFailed),
+            GetOrder(command) => map_err!(command.run().await, ClobGetOrderCommandRunFailed),
+            ListTrades(command) => map_err!(command.run().await, ClobListTradesCommandRunFailed),
         }
     }
 }
@@ -30,8 +50,92 @@ impl ClobCommand {
 pub enum ClobCommandRunError {
     #[error("failed to run clob place limit order command")]
     ClobPlaceLimitOrderCommandRunFailed { source: ClobPlaceLimitOrderCommandRunError },
+    #[error("failed to run clob place market order command")]
+    ClobPlaceMarketOrderCommandRunFailed { source: ClobPlaceMarketOrderCommandRunError },
+    #[error("failed to run clob place orders command")]
+    ClobPlaceOrdersCommandRunFailed { source: ClobPlaceOrdersCommandRunError },
+    #[error("failed to run clob post signed order command")]
+    ClobPostSignedOrderCommandRunFailed { source: ClobPostSignedOrderCommandRunError },
+    #[error("failed to run clob watch order books command")]
+    ClobWatchOrderBooksCommandRunFailed { source: ClobWatchOrderBooksCommandRunError },
+    #[error("failed to run clob cancel order command")]
+    ClobCancelOrderCommandRunFailed { source: ClobCancelOrderCommandRunError },
+    #[error("failed to run clob cancel all command")]
+    ClobCancelAllCommandRunFailed { source: ClobCancelAllCommandRunError },
+    #[error("failed to run clob cancel market orders command")]
+    ClobCancelMarketOrdersCommandRunFailed { source: ClobCancelMarketOrdersCommandRunError },
+    #[error("failed to run clob list open orders command")]
+    ClobListOpenOrdersCommandRunFailed { source: ClobListOpenOrdersCommandRunError },
+    #[error("failed to run clob get order command")]
+    ClobGetOrderCommandRunFailed { source: ClobGetOrderCommandRunError },
+    #[error("failed to run clob list trades command")]
+    ClobListTradesCommandRunFailed { source: ClobListTradesCommandRunError },
 }
 
 mod clob_place_limit_order_command;
 
 pub use clob_place_limit_order_command::*;
+
+mod clob_place_market_order_command;
+
+pub use clob_place_market_order_command::*;
+
+mod clob_place_orders_command;
+
+pub use clob_place_orders_command::*;
+
+mod clob_post_signed_order_command;
+
+pub use clob_post_signed_order_command::*;
+
+mod clob_watch_order_books_command;
+
+pub use clob_watch_order_books_command::*;
+
+mod clob_auth_args;
+
+pub use clob_auth_args::*;
+
+mod clob_signer_args;
+
+pub use clob_signer_args::*;
+
+mod clob_api_credentials_args;
+
+pub use clob_api_credentials_args::*;
+
+mod clob_cancel_orders_output;
+
+pub use clob_cancel_orders_output::*;
+
+mod clob_open_order_output;
+
+pub use clob_open_order_output::*;
+
+mod clob_trade_output;
+
+pub use clob_trade_output::*;
+
+mod clob_cancel_order_command;
+
+pub use clob_cancel_order_command::*;
+
+mod clob_cancel_all_command;
+
+pub use clob_cancel_all_command::*;
+
+mod clob_cancel_market_orders_command;
+
+pub use clob_cancel_market_orders_command::*;
+
+mod clob_list_open_orders_command;
+
+pub use clob_list_open_orders_command::*;
+
+mod clob_get_order_command;
+
+pub use clob_get_order_command::*;
+
+mod clob_list_trades_command;
+
+pub use clob_list_trades_command::*;
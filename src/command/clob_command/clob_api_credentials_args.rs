@@ -0,0 +1,70 @@
+use crate::{AddressSigner, AuthenticatedClobClient, DEFAULT_CLOB_HOST};
+use errgonomic::handle;
+use polymarket_client_sdk::auth::{ApiKey, Credentials, Signer};
+use polymarket_client_sdk::clob::{Client as PolymarketClobClient, Config as PolymarketClobConfig};
+use polymarket_client_sdk::error::Error as PolymarketError;
+use polymarket_client_sdk::types::{Address, ChainId};
+use thiserror::Error;
+
+/// The L2 API credentials for the `clob` subcommands that don't need the private key (e.g. posting a pre-signed order)
+#[derive(clap::Args, Clone, Debug)]
+pub struct ClobApiCredentialsArgs {
+    #[arg(long, default_value = DEFAULT_CLOB_HOST)]
+    pub clob_host: String,
+
+    #[arg(long, default_value_t = polymarket_client_sdk::POLYGON)]
+    pub chain_id: ChainId,
+
+    /// Address of the signer that owns the API key. Can also be provided via POLYMARKET_ADDRESS env var.
+    #[arg(long, env = "POLYMARKET_ADDRESS")]
+    pub address: Address,
+
+    /// Can also be provided via POLYMARKET_API_KEY env var.
+    #[arg(long, env = "POLYMARKET_API_KEY", hide_env_values = true)]
+    pub api_key: ApiKey,
+
+    /// Can also be provided via POLYMARKET_API_SECRET env var.
+    #[arg(long, env = "POLYMARKET_API_SECRET", hide_env_values = true)]
+    pub api_secret: String,
+
+    /// Can also be provided via POLYMARKET_API_PASSPHRASE env var.
+    #[arg(long, env = "POLYMARKET_API_PASSPHRASE", hide_env_values = true)]
+    pub api_passphrase: String,
+}
+
+impl ClobApiCredentialsArgs {
+    /// Returns the authenticated client and the API key (the API key is the `owner` of the posted orders)
+    pub async fn authenticate(self) -> Result<(AuthenticatedClobClient, ApiKey), ClobApiCredentialsArgsAuthenticateError> {
+        use ClobApiCredentialsArgsAuthenticateError::*;
+        let Self {
+            clob_host,
+            chain_id,
+            address,
+            api_key,
+            api_secret,
+            api_passphrase,
+        } = self;
+        let mut signer = AddressSigner::new(address);
+        signer.set_chain_id(Some(chain_id));
+        let client_unauthenticated = handle!(PolymarketClobClient::new(&clob_host, PolymarketClobConfig::default()), ClientNewFailed, host: clob_host);
+        // the credentials are provided, so the signer is not asked to sign the L1 authentication message
+        let credentials = Credentials::new(api_key, api_secret, api_passphrase);
+        let client = handle!(
+            client_unauthenticated
+                .authentication_builder(&signer)
+                .credentials(credentials)
+                .authenticate()
+                .await,
+            AuthenticateFailed
+        );
+        Ok((client, api_key))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobApiCredentialsArgsAuthenticateError {
+    #[error("failed to initialize clob client for host '{host}'")]
+    ClientNewFailed { source: PolymarketError, host: String },
+    #[error("failed to authenticate clob client with the API credentials")]
+    AuthenticateFailed { source: PolymarketError },
+}
@@ -0,0 +1,159 @@
+use crate::{ClobSigner, ClobSignerArgs, ClobSignerArgsSignerError, DEFAULT_CLOB_HOST};
+use clap::ValueEnum;
+use errgonomic::handle;
+use polymarket_client_sdk::auth::Normal;
+use polymarket_client_sdk::auth::Signer;
+use polymarket_client_sdk::auth::state::Authenticated;
+use polymarket_client_sdk::clob::types::SignatureType as PolymarketClobSignatureType;
+use polymarket_client_sdk::clob::{Client as PolymarketClobClient, Config as PolymarketClobConfig};
+use polymarket_client_sdk::error::Error as PolymarketError;
+use polymarket_client_sdk::types::{Address, ChainId};
+use thiserror::Error;
+
+pub type AuthenticatedClobClient = PolymarketClobClient<Authenticated<Normal>>;
+
+/// The arguments that are shared by the `clob` subcommands that require authentication
+#[derive(clap::Args, Clone, Debug)]
+pub struct ClobAuthArgs {
+    #[arg(long, default_value = DEFAULT_CLOB_HOST)]
+    pub clob_host: String,
+
+    #[arg(long, default_value_t = polymarket_client_sdk::POLYGON)]
+    pub chain_id: ChainId,
+
+    #[command(flatten)]
+    pub signer_args: ClobSignerArgs,
+
+    #[arg(long, value_enum, default_value_t = ClobSignatureType::Eoa)]
+    pub signature_type: ClobSignatureType,
+
+    #[arg(long)]
+    pub funder: Option<Address>,
+}
+
+impl ClobAuthArgs {
+    /// Returns the authenticated client and the signer (the signer is needed to sign the orders)
+    pub async fn authenticate(self) -> Result<(AuthenticatedClobClient, ClobSigner), ClobAuthArgsAuthenticateError> {
+        use ClobAuthArgsAuthenticateError::*;
+        let Self {
+            clob_host,
+            chain_id,
+            signer_args,
+            signature_type,
+            funder,
+        } = self;
+        let mut signer = handle!(signer_args.signer().await, SignerFailed);
+        signer.set_chain_id(Some(chain_id));
+        let signature_type = PolymarketClobSignatureType::from(signature_type);
+        let client_unauthenticated = handle!(PolymarketClobClient::new(&clob_host, PolymarketClobConfig::default()), ClientNewFailed, host: clob_host);
+        let authentication_builder = client_unauthenticated
+            .authentication_builder(&signer)
+            .signature_type(signature_type);
+        let authentication_builder = match funder {
+            Some(funder) => authentication_builder.funder(funder),
+            None => authentication_builder,
+        };
+        let client = handle!(authentication_builder.authenticate().await, AuthenticateFailed);
+        Ok((client, signer))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobAuthArgsAuthenticateError {
+    #[error("failed to create signer")]
+    SignerFailed { source: ClobSignerArgsSignerError },
+    #[error("failed to initialize clob client for host '{host}'")]
+    ClientNewFailed { source: PolymarketError, host: String },
+    #[error("failed to authenticate clob client")]
+    AuthenticateFailed { source: PolymarketError },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ClobSignatureType {
+    Eoa,
+    Proxy,
+    GnosisSafe,
+}
+
+impl From<ClobSignatureType> for PolymarketClobSignatureType {
+    fn from(input: ClobSignatureType) -> Self {
+        use ClobSignatureType::*;
+        match input {
+            Eoa => Self::Eoa,
+            Proxy => Self::Proxy,
+            GnosisSafe => Self::GnosisSafe,
+        }
+    }
+}
+
+/// The `signatureType` field of the order
+impl From<ClobSignatureType> for u8 {
+    fn from(input: ClobSignatureType) -> Self {
+        use ClobSignatureType::*;
+        match input {
+            Eoa => 0,
+            Proxy => 1,
+            GnosisSafe => 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClobSignerKind, MockHttpServerStartError, start_mock_clob_server, start_mock_remote_signer};
+    use alloy::signers::local::{LocalSignerError, PrivateKeySigner};
+    use url::{ParseError, Url};
+
+    #[tokio::test]
+    async fn must_authenticate_with_remote_signer() -> Result<(), ClobAuthArgsTestError> {
+        use ClobAuthArgsTestError::*;
+        let local_signer = handle!("0x0123456789012345678901234567890123456789012345678901234567890123".parse::<PrivateKeySigner>(), PrivateKeyParseFailed);
+        let remote_signer_server = handle!(start_mock_remote_signer(local_signer.clone()).await, StartServerFailed);
+        let clob = handle!(start_mock_clob_server().await, StartServerFailed);
+        let remote_signer_url = handle!(Url::parse(&remote_signer_server.url), UrlParseFailed);
+        let args = ClobAuthArgs {
+            clob_host: clob.url.clone(),
+            chain_id: polymarket_client_sdk::POLYGON,
+            signer_args: ClobSignerArgs {
+                signer: ClobSignerKind::Remote,
+                seed_phrase: None,
+                seed_phrase_password: None,
+                account_index: 0,
+                private_key: None,
+                keystore: None,
+                keystore_password: None,
+                remote_signer_url: Some(remote_signer_url),
+            },
+            signature_type: ClobSignatureType::Eoa,
+            funder: None,
+        };
+        let (_client, signer) = handle!(args.authenticate().await, AuthenticateFailed);
+        assert!(matches!(signer, ClobSigner::Remote(_)));
+        assert_eq!(signer.address(), local_signer.address());
+        // the L1 authentication headers are signed by the remote signer
+        assert!(
+            remote_signer_server
+                .requests()
+                .contains(&"/sign-hash".to_string())
+        );
+        assert!(
+            clob.requests()
+                .iter()
+                .any(|target| target.starts_with("/auth/"))
+        );
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum ClobAuthArgsTestError {
+        #[error("failed to parse private key")]
+        PrivateKeyParseFailed { source: LocalSignerError },
+        #[error("failed to start mock server")]
+        StartServerFailed { source: MockHttpServerStartError },
+        #[error("failed to parse mock server URL")]
+        UrlParseFailed { source: ParseError },
+        #[error("failed to authenticate")]
+        AuthenticateFailed { source: ClobAuthArgsAuthenticateError },
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{ClobAuthArgs, ClobAuthArgsAuthenticateError, ClobCancelOrdersOutput};
+use errgonomic::handle;
+use polymarket_client_sdk::error::Error as PolymarketError;
+use std::io::{self, Write, stdout};
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Cancels all open orders of the account
+#[derive(clap::Parser, Clone, Debug)]
+pub struct ClobCancelAllCommand {
+    #[command(flatten)]
+    pub auth: ClobAuthArgs,
+}
+
+impl ClobCancelAllCommand {
+    pub async fn run(self) -> Result<ExitCode, ClobCancelAllCommandRunError> {
+        use ClobCancelAllCommandRunError::*;
+        let Self {
+            auth,
+        } = self;
+        let (client, _signer) = handle!(auth.authenticate().await, AuthenticateFailed);
+        let response = handle!(client.cancel_all_orders().await, CancelAllOrdersFailed);
+        let output = ClobCancelOrdersOutput::from(response);
+        let mut stdout = stdout().lock();
+        handle!(serde_json::to_writer(&mut stdout, &output), SerializeOutputFailed);
+        handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobCancelAllCommandRunError {
+    #[error("failed to authenticate clob client")]
+    AuthenticateFailed { source: ClobAuthArgsAuthenticateError },
+    #[error("failed to cancel all orders")]
+    CancelAllOrdersFailed { source: PolymarketError },
+    #[error("failed to serialize output")]
+    SerializeOutputFailed { source: serde_json::Error },
+    #[error("failed to write output newline")]
+    WriteOutputNewlineFailed { source: io::Error },
+}
@@ -0,0 +1,54 @@
+use crate::{ClobAuthArgs, ClobAuthArgsAuthenticateError, ClobCancelOrdersOutput, ConditionId, TokenId};
+use errgonomic::handle;
+use polymarket_client_sdk::clob::types::request::CancelMarketOrderRequest;
+use polymarket_client_sdk::error::Error as PolymarketError;
+use std::io::{self, Write, stdout};
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Cancels the open orders of the account in the market (or only for the token of the market)
+#[derive(clap::Parser, Clone, Debug)]
+pub struct ClobCancelMarketOrdersCommand {
+    #[command(flatten)]
+    pub auth: ClobAuthArgs,
+
+    #[arg(long, required_unless_present = "token_id")]
+    pub market: Option<ConditionId>,
+
+    #[arg(long)]
+    pub token_id: Option<TokenId>,
+}
+
+impl ClobCancelMarketOrdersCommand {
+    pub async fn run(self) -> Result<ExitCode, ClobCancelMarketOrdersCommandRunError> {
+        use ClobCancelMarketOrdersCommandRunError::*;
+        let Self {
+            auth,
+            market,
+            token_id,
+        } = self;
+        let (client, _signer) = handle!(auth.authenticate().await, AuthenticateFailed);
+        let request = CancelMarketOrderRequest::builder()
+            .maybe_market(market)
+            .maybe_asset_id(token_id)
+            .build();
+        let response = handle!(client.cancel_market_orders(&request).await, CancelMarketOrdersFailed, request);
+        let output = ClobCancelOrdersOutput::from(response);
+        let mut stdout = stdout().lock();
+        handle!(serde_json::to_writer(&mut stdout, &output), SerializeOutputFailed);
+        handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobCancelMarketOrdersCommandRunError {
+    #[error("failed to authenticate clob client")]
+    AuthenticateFailed { source: ClobAuthArgsAuthenticateError },
+    #[error("failed to cancel market orders")]
+    CancelMarketOrdersFailed { source: PolymarketError, request: CancelMarketOrderRequest },
+    #[error("failed to serialize output")]
+    SerializeOutputFailed { source: serde_json::Error },
+    #[error("failed to write output newline")]
+    WriteOutputNewlineFailed { source: io::Error },
+}
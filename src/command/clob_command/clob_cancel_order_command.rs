@@ -0,0 +1,44 @@
+use crate::{ClobAuthArgs, ClobAuthArgsAuthenticateError, ClobCancelOrdersOutput};
+use errgonomic::handle;
+use polymarket_client_sdk::error::Error as PolymarketError;
+use std::io::{self, Write, stdout};
+use std::process::ExitCode;
+use thiserror::Error;
+
+#[derive(clap::Parser, Clone, Debug)]
+pub struct ClobCancelOrderCommand {
+    #[command(flatten)]
+    pub auth: ClobAuthArgs,
+
+    #[arg(long)]
+    pub order_id: String,
+}
+
+impl ClobCancelOrderCommand {
+    pub async fn run(self) -> Result<ExitCode, ClobCancelOrderCommandRunError> {
+        use ClobCancelOrderCommandRunError::*;
+        let Self {
+            auth,
+            order_id,
+        } = self;
+        let (client, _signer) = handle!(auth.authenticate().await, AuthenticateFailed);
+        let response = handle!(client.cancel_order(&order_id).await, CancelOrderFailed, order_id);
+        let output = ClobCancelOrdersOutput::from(response);
+        let mut stdout = stdout().lock();
+        handle!(serde_json::to_writer(&mut stdout, &output), SerializeOutputFailed);
+        handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobCancelOrderCommandRunError {
+    #[error("failed to authenticate clob client")]
+    AuthenticateFailed { source: ClobAuthArgsAuthenticateError },
+    #[error("failed to cancel order '{order_id}'")]
+    CancelOrderFailed { source: PolymarketError, order_id: String },
+    #[error("failed to serialize output")]
+    SerializeOutputFailed { source: serde_json::Error },
+    #[error("failed to write output newline")]
+    WriteOutputNewlineFailed { source: io::Error },
+}
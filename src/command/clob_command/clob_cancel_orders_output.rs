@@ -0,0 +1,24 @@
+use polymarket_client_sdk::clob::types::response::CancelOrdersResponse;
+use std::collections::HashMap;
+
+/// This struct is needed because [`CancelOrdersResponse`] doesn't implement [`serde::Serialize`]
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ClobCancelOrdersOutput {
+    pub canceled: Vec<String>,
+    /// A map from order id to the reason why the order was not canceled
+    pub not_canceled: HashMap<String, String>,
+}
+
+impl From<CancelOrdersResponse> for ClobCancelOrdersOutput {
+    fn from(input: CancelOrdersResponse) -> Self {
+        let CancelOrdersResponse {
+            canceled,
+            not_canceled,
+            ..
+        } = input;
+        Self {
+            canceled,
+            not_canceled: not_canceled.into_iter().collect(),
+        }
+    }
+}
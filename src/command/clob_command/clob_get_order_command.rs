@@ -0,0 +1,44 @@
+use crate::{ClobAuthArgs, ClobAuthArgsAuthenticateError, ClobOpenOrderOutput};
+use errgonomic::handle;
+use polymarket_client_sdk::error::Error as PolymarketError;
+use std::io::{self, Write, stdout};
+use std::process::ExitCode;
+use thiserror::Error;
+
+#[derive(clap::Parser, Clone, Debug)]
+pub struct ClobGetOrderCommand {
+    #[command(flatten)]
+    pub auth: ClobAuthArgs,
+
+    #[arg(long)]
+    pub order_id: String,
+}
+
+impl ClobGetOrderCommand {
+    pub async fn run(self) -> Result<ExitCode, ClobGetOrderCommandRunError> {
+        use ClobGetOrderCommandRunError::*;
+        let Self {
+            auth,
+            order_id,
+        } = self;
+        let (client, _signer) = handle!(auth.authenticate().await, AuthenticateFailed);
+        let response = handle!(client.order(&order_id).await, OrderFailed, order_id);
+        let output = ClobOpenOrderOutput::from(response);
+        let mut stdout = stdout().lock();
+        handle!(serde_json::to_writer(&mut stdout, &output), SerializeOutputFailed);
+        handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobGetOrderCommandRunError {
+    #[error("failed to authenticate clob client")]
+    AuthenticateFailed { source: ClobAuthArgsAuthenticateError },
+    #[error("failed to get order '{order_id}'")]
+    OrderFailed { source: PolymarketError, order_id: String },
+    #[error("failed to serialize output")]
+    SerializeOutputFailed { source: serde_json::Error },
+    #[error("failed to write output newline")]
+    WriteOutputNewlineFailed { source: io::Error },
+}
@@ -0,0 +1,63 @@
+use crate::{ClobAuthArgs, ClobAuthArgsAuthenticateError, ClobOpenOrderOutput, ConditionId, NEXT_CURSOR_STOP, NextCursor, TokenId};
+use errgonomic::handle;
+use polymarket_client_sdk::clob::types::request::OrdersRequest;
+use polymarket_client_sdk::error::Error as PolymarketError;
+use std::io::{self, Write, stdout};
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Prints the open orders of the account as JSONL (fetches all pages)
+#[derive(clap::Parser, Clone, Debug)]
+pub struct ClobListOpenOrdersCommand {
+    #[command(flatten)]
+    pub auth: ClobAuthArgs,
+
+    #[arg(long)]
+    pub market: Option<ConditionId>,
+
+    #[arg(long)]
+    pub token_id: Option<TokenId>,
+}
+
+impl ClobListOpenOrdersCommand {
+    pub async fn run(self) -> Result<ExitCode, ClobListOpenOrdersCommandRunError> {
+        use ClobListOpenOrdersCommandRunError::*;
+        let Self {
+            auth,
+            market,
+            token_id,
+        } = self;
+        let (client, _signer) = handle!(auth.authenticate().await, AuthenticateFailed);
+        let request = OrdersRequest::builder()
+            .maybe_market(market)
+            .maybe_asset_id(token_id)
+            .build();
+        let mut stdout = stdout().lock();
+        let mut next_cursor_opt: Option<NextCursor> = None;
+        loop {
+            let page = handle!(client.orders(&request, next_cursor_opt.clone()).await, OrdersFailed, next_cursor_opt);
+            for order in page.data {
+                let output = ClobOpenOrderOutput::from(order);
+                handle!(serde_json::to_writer(&mut stdout, &output), SerializeOutputFailed);
+                handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
+            }
+            if page.next_cursor == NEXT_CURSOR_STOP {
+                break;
+            }
+            next_cursor_opt = Some(page.next_cursor);
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobListOpenOrdersCommandRunError {
+    #[error("failed to authenticate clob client")]
+    AuthenticateFailed { source: ClobAuthArgsAuthenticateError },
+    #[error("failed to fetch open orders page with cursor '{next_cursor_opt:?}'")]
+    OrdersFailed { source: PolymarketError, next_cursor_opt: Option<NextCursor> },
+    #[error("failed to serialize output")]
+    SerializeOutputFailed { source: serde_json::Error },
+    #[error("failed to write output newline")]
+    WriteOutputNewlineFailed { source: io::Error },
+}
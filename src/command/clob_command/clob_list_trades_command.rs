@@ -0,0 +1,63 @@
+use crate::{ClobAuthArgs, ClobAuthArgsAuthenticateError, ClobTradeOutput, ConditionId, NEXT_CURSOR_STOP, NextCursor, TokenId};
+use errgonomic::handle;
+use polymarket_client_sdk::clob::types::request::TradesRequest;
+use polymarket_client_sdk::error::Error as PolymarketError;
+use std::io::{self, Write, stdout};
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Prints the trades of the account as JSONL (fetches all pages)
+#[derive(clap::Parser, Clone, Debug)]
+pub struct ClobListTradesCommand {
+    #[command(flatten)]
+    pub auth: ClobAuthArgs,
+
+    #[arg(long)]
+    pub market: Option<ConditionId>,
+
+    #[arg(long)]
+    pub token_id: Option<TokenId>,
+}
+
+impl ClobListTradesCommand {
+    pub async fn run(self) -> Result<ExitCode, ClobListTradesCommandRunError> {
+        use ClobListTradesCommandRunError::*;
+        let Self {
+            auth,
+            market,
+            token_id,
+        } = self;
+        let (client, _signer) = handle!(auth.authenticate().await, AuthenticateFailed);
+        let request = TradesRequest::builder()
+            .maybe_market(market)
+            .maybe_asset_id(token_id)
+            .build();
+        let mut stdout = stdout().lock();
+        let mut next_cursor_opt: Option<NextCursor> = None;
+        loop {
+            let page = handle!(client.trades(&request, next_cursor_opt.clone()).await, TradesFailed, next_cursor_opt);
+            for trade in page.data {
+                let output = ClobTradeOutput::from(trade);
+                handle!(serde_json::to_writer(&mut stdout, &output), SerializeOutputFailed);
+                handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
+            }
+            if page.next_cursor == NEXT_CURSOR_STOP {
+                break;
+            }
+            next_cursor_opt = Some(page.next_cursor);
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobListTradesCommandRunError {
+    #[error("failed to authenticate clob client")]
+    AuthenticateFailed { source: ClobAuthArgsAuthenticateError },
+    #[error("failed to fetch trades page with cursor '{next_cursor_opt:?}'")]
+    TradesFailed { source: PolymarketError, next_cursor_opt: Option<NextCursor> },
+    #[error("failed to serialize output")]
+    SerializeOutputFailed { source: serde_json::Error },
+    #[error("failed to write output newline")]
+    WriteOutputNewlineFailed { source: io::Error },
+}
@@ -0,0 +1,53 @@
+use crate::UintAsString;
+use polymarket_client_sdk::clob::types::response::OpenOrderResponse;
+use polymarket_client_sdk::clob::types::{OrderStatusType, OrderType, Side};
+use polymarket_client_sdk::types::{B256, Decimal, U256};
+
+/// This struct is needed because [`OpenOrderResponse`] doesn't implement [`serde::Serialize`]
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ClobOpenOrderOutput {
+    pub id: String,
+    pub status: OrderStatusType,
+    pub market: B256,
+    #[serde(with = "UintAsString")]
+    pub asset_id: U256,
+    pub side: Side,
+    pub original_size: Decimal,
+    pub size_matched: Decimal,
+    pub price: Decimal,
+    pub outcome: String,
+    pub order_type: OrderType,
+    pub associate_trades: Vec<String>,
+}
+
+impl From<OpenOrderResponse> for ClobOpenOrderOutput {
+    fn from(input: OpenOrderResponse) -> Self {
+        let OpenOrderResponse {
+            id,
+            status,
+            market,
+            asset_id,
+            side,
+            original_size,
+            size_matched,
+            price,
+            outcome,
+            order_type,
+            associate_trades,
+            ..
+        } = input;
+        Self {
+            id,
+            status,
+            market,
+            asset_id,
+            side,
+            original_size,
+            size_matched,
+            price,
+            outcome,
+            order_type,
+            associate_trades,
+        }
+    }
+}
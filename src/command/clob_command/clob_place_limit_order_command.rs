@@ -1,40 +1,22 @@
-use crate::{OrderType, Side, TokenId};
-use alloy::signers::local::{LocalSignerError, MnemonicBuilder};
+use crate::{AuthenticatedClobClient, ClobAuthArgs, ClobAuthArgsAuthenticateError, ClobClientOrderBookSnapshotError, ClobMarket, ClobMarketFallible, ClobMarketResponsePrecise, ClobMarketResponsePreciseFallible, ClobOfflineLimitOrder, ClobOfflineLimitOrderSignError, ClobSignatureType, ClobSignerArgsSignerError, ConditionId, LimitOrderValidator, LimitOrderValidatorValidateError, OrderBook, OrderBookSnapshotSource, OrderType, Repository, RepositoryOpenError, RepositoryReadEntryError, Side, TokenId};
 use chrono::{DateTime, Utc};
-use clap::ValueEnum;
-use errgonomic::handle;
+use core::num::TryFromIntError;
+use errgonomic::{handle, handle_bool, handle_opt};
+use fjall::{Error as FjallError, SingleWriterTxDatabase};
 use polymarket_client_sdk::auth::Signer;
+use polymarket_client_sdk::clob::types::OrderStatusType;
 use polymarket_client_sdk::clob::types::response::PostOrderResponse;
-use polymarket_client_sdk::clob::types::{OrderStatusType, SignatureType as PolymarketClobSignatureType};
-use polymarket_client_sdk::clob::{Client as PolymarketClobClient, Config as PolymarketClobConfig};
 use polymarket_client_sdk::error::Error as PolymarketError;
-use polymarket_client_sdk::types::{Address, B256, ChainId, Decimal};
+use polymarket_client_sdk::types::{Address, B256, Decimal};
 use std::io::{self, Write, stdout};
+use std::path::PathBuf;
 use std::process::ExitCode;
 use thiserror::Error;
 
 #[derive(clap::Parser, Clone, Debug)]
 pub struct ClobPlaceLimitOrderCommand {
-    #[arg(long, default_value = "https://clob.polymarket.com")]
-    pub host: String,
-
-    #[arg(long, default_value_t = polymarket_client_sdk::POLYGON)]
-    pub chain_id: ChainId,
-
-    /// Seed phrase (mnemonic words). Can also be provided via POLYMARKET_SEED_PHRASE env var.
-    #[arg(long, env = "POLYMARKET_SEED_PHRASE")]
-    pub seed_phrase: String,
-
-    /// Optional seed phrase passphrase (BIP-39). Can also be provided via POLYMARKET_SEED_PHRASE_PASSWORD env var.
-    #[arg(long, env = "POLYMARKET_SEED_PHRASE_PASSWORD")]
-    pub seed_phrase_password: Option<String>,
-
-    /// Account index used in derivation path m/44'/60'/0'/0/{index}
-    #[arg(long, default_value_t = 0)]
-    pub account_index: u32,
-
-    #[arg(long, value_enum, default_value_t = ClobPlaceLimitOrderCommandSignatureType::Eoa)]
-    pub signature_type: ClobPlaceLimitOrderCommandSignatureType,
+    #[command(flatten)]
+    pub auth: ClobAuthArgs,
 
     #[arg(long)]
     pub token_id: TokenId,
@@ -60,24 +42,41 @@ pub struct ClobPlaceLimitOrderCommand {
     #[arg(long, default_value_t = false)]
     pub post_only: bool,
 
-    #[arg(long)]
-    pub funder: Option<Address>,
-
     #[arg(long, default_value_t = Address::ZERO)]
     pub taker: Address,
+
+    /// Read the market from the cache at this dir instead of fetching it from the API
+    #[arg(long)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Post the order without checking it against the market constraints
+    #[arg(long, default_value_t = false)]
+    pub skip_validation: bool,
+
+    /// Sign the order offline and output it instead of posting it (the output can be posted later with `clob post-signed-order`)
+    ///
+    /// The dry run doesn't send any request to the CLOB API, so the market parameters are taken from `--tick-size`, `--neg-risk` and `--fee-rate-bps`, or from the market cached at `--cache-dir`.
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+
+    /// Tick size of the market (only used by `--dry-run`)
+    #[arg(long)]
+    pub tick_size: Option<Decimal>,
+
+    /// Whether the market is traded on the neg-risk exchange (only used by `--dry-run`)
+    #[arg(long)]
+    pub neg_risk: Option<bool>,
+
+    /// Fee rate of the order in basis points (only used by `--dry-run`)
+    #[arg(long, default_value_t = 0)]
+    pub fee_rate_bps: u32,
 }
 
 impl ClobPlaceLimitOrderCommand {
     pub async fn run(self) -> Result<ExitCode, ClobPlaceLimitOrderCommandRunError> {
         use ClobPlaceLimitOrderCommandRunError::*;
         let Self {
-            host,
-            chain_id,
-            signature_type,
-            funder,
-            seed_phrase,
-            seed_phrase_password,
-            account_index,
+            auth,
             token_id,
             side,
             price,
@@ -87,24 +86,78 @@ impl ClobPlaceLimitOrderCommand {
             taker,
             order_type,
             post_only,
+            cache_dir,
+            skip_validation,
+            dry_run,
+            tick_size,
+            neg_risk,
+            fee_rate_bps,
         } = self;
-        let mnemonic_builder = MnemonicBuilder::english().phrase(seed_phrase);
-        let mnemonic_builder = handle!(mnemonic_builder.index(account_index), MnemonicBuilderIndexFailed, account_index);
-        let mnemonic_builder = match seed_phrase_password {
-            Some(seed_phrase_password) => mnemonic_builder.password(seed_phrase_password),
-            None => mnemonic_builder,
-        };
-        let signer = handle!(mnemonic_builder.build(), MnemonicBuilderBuildFailed, account_index).with_chain_id(Some(chain_id));
-        let signature_type = PolymarketClobSignatureType::from(signature_type);
-        let client_unauthenticated = handle!(PolymarketClobClient::new(&host, PolymarketClobConfig::default()), ClientNewFailed, host);
-        let authentication_builder = client_unauthenticated
-            .authentication_builder(&signer)
-            .signature_type(signature_type);
-        let authentication_builder = match funder {
-            Some(funder) => authentication_builder.funder(funder),
-            None => authentication_builder,
-        };
-        let client = handle!(authentication_builder.authenticate().await, AuthenticateFailed);
+        if dry_run {
+            let cached_market_opt = match cache_dir {
+                Some(dir) => handle!(Self::read_cached_market(dir, token_id), ReadCachedMarketFailed),
+                None => None,
+            };
+            if let (false, Some(market)) = (skip_validation, &cached_market_opt) {
+                // the book is not available offline, so the post-only check is skipped
+                let validator = LimitOrderValidator::new(market.clone(), None);
+                handle!(validator.validate(token_id, side, price, size, false), ValidateFailed);
+            }
+            let tick_size = handle_opt!(
+                tick_size.or_else(|| cached_market_opt
+                    .as_ref()
+                    .map(|market| market.minimum_tick_size)),
+                TickSizeNotFound,
+                token_id
+            );
+            let neg_risk = handle_opt!(
+                neg_risk.or_else(|| cached_market_opt
+                    .as_ref()
+                    .map(|market| market.neg_risk.is_some())),
+                NegRiskNotFound,
+                token_id
+            );
+            let ClobAuthArgs {
+                chain_id,
+                signer_args,
+                signature_type,
+                funder,
+                ..
+            } = auth;
+            let mut signer = handle!(signer_args.signer().await, SignerFailed);
+            signer.set_chain_id(Some(chain_id));
+            // the maker of a proxy wallet order is the proxy wallet, which can't be derived offline
+            handle_bool!(!matches!(signature_type, ClobSignatureType::Eoa) && funder.is_none(), FunderNotFound, signature_type);
+            let maker = funder.unwrap_or_else(|| signer.address());
+            let expiration = handle!(u64::try_from(expiration.timestamp()), ExpirationTryFromFailed, expiration);
+            let order = ClobOfflineLimitOrder {
+                token_id,
+                side,
+                price,
+                size,
+                tick_size,
+                neg_risk,
+                chain_id,
+                maker,
+                taker,
+                signature_type: signature_type.into(),
+                nonce,
+                expiration,
+                fee_rate_bps,
+                order_type,
+                post_only,
+            };
+            let output = handle!(order.sign(&signer).await, SignOfflineFailed);
+            let mut stdout = stdout().lock();
+            handle!(serde_json::to_writer(&mut stdout, &output), SerializeSignedOrderFailed);
+            handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
+            return Ok(ExitCode::SUCCESS);
+        }
+        let (client, signer) = handle!(auth.authenticate().await, AuthenticateFailed);
+        if !skip_validation {
+            let validator = handle!(Self::validator(&client, cache_dir, token_id, post_only).await, ValidatorFailed);
+            handle!(validator.validate(token_id, side, price, size, post_only), ValidateFailed);
+        }
         let limit_order_builder = client
             .limit_order()
             .token_id(token_id)
@@ -126,22 +179,92 @@ impl ClobPlaceLimitOrderCommand {
         handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
         Ok(ExitCode::SUCCESS)
     }
+
+    /// Loads the market from the cache (if `cache_dir` is set) or from the API, and the book only if it's needed
+    async fn validator(client: &AuthenticatedClobClient, cache_dir: Option<PathBuf>, token_id: TokenId, post_only: bool) -> Result<LimitOrderValidator, ClobPlaceLimitOrderCommandValidatorError> {
+        use ClobPlaceLimitOrderCommandValidatorError::*;
+        let market_opt = match cache_dir {
+            Some(dir) => handle!(Self::read_cached_market(dir, token_id), ReadCachedMarketFailed),
+            None => None,
+        };
+        let (market, book_opt) = match market_opt {
+            Some(market) => {
+                let book_opt = if post_only {
+                    Some(handle!(Self::fetch_book(client, token_id).await, FetchBookFailed))
+                } else {
+                    None
+                };
+                (market, book_opt)
+            }
+            None => {
+                // the book contains the condition id, which is needed to fetch the market
+                let book = handle!(Self::fetch_book(client, token_id).await, FetchBookFailed);
+                let market = handle!(Self::fetch_market(client, book.condition_id()).await, FetchMarketFailed);
+                (market, Some(book))
+            }
+        };
+        Ok(LimitOrderValidator::new(market, book_opt))
+    }
+
+    async fn fetch_book(client: &AuthenticatedClobClient, token_id: TokenId) -> Result<OrderBook, ClobClientOrderBookSnapshotError> {
+        client
+            .order_book_snapshot(token_id)
+            .await
+            .map(OrderBook::from)
+    }
+
+    async fn fetch_market(client: &AuthenticatedClobClient, condition_id: ConditionId) -> Result<ClobMarket, ClobPlaceLimitOrderCommandFetchMarketError> {
+        use ClobPlaceLimitOrderCommandFetchMarketError::*;
+        let response = handle!(client.market(&condition_id.to_string()).await, MarketFailed, condition_id);
+        let market_precise = handle!(ClobMarketResponsePrecise::try_from(response), MarketResponseTryFromFailed, condition_id);
+        let market = handle!(ClobMarket::try_from(market_precise), MarketTryFromFailed, condition_id);
+        Ok(market)
+    }
+
+    /// The markets are keyed by slug, so this function scans the keyspace until it finds the market of the token
+    fn read_cached_market(dir: PathBuf, token_id: TokenId) -> Result<Option<ClobMarket>, ClobPlaceLimitOrderCommandReadCachedMarketError> {
+        use ClobPlaceLimitOrderCommandReadCachedMarketError::*;
+        let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
+        let repository = handle!(Repository::<ClobMarket>::open(&db), OpenRepositoryFailed);
+        let snapshot = db.read_tx();
+        for entry in repository.iter(&snapshot) {
+            let (_key, market) = handle!(entry, ReadEntryFailed);
+            if market.left_token_id == token_id || market.right_token_id == token_id {
+                return Ok(Some(market));
+            }
+        }
+        Ok(None)
+    }
 }
 
 #[derive(Error, Debug)]
 pub enum ClobPlaceLimitOrderCommandRunError {
-    #[error("failed to set mnemonic derivation index '{account_index}'")]
-    MnemonicBuilderIndexFailed { source: LocalSignerError, account_index: u32 },
-    #[error("failed to build signer from mnemonic at account index '{account_index}'")]
-    MnemonicBuilderBuildFailed { source: LocalSignerError, account_index: u32 },
-    #[error("failed to initialize clob client for host '{host}'")]
-    ClientNewFailed { source: PolymarketError, host: String },
+    #[error("failed to read cached market")]
+    ReadCachedMarketFailed { source: ClobPlaceLimitOrderCommandReadCachedMarketError },
+    #[error("tick size of token '{token_id}' is required for a dry run (use --tick-size or --cache-dir)")]
+    TickSizeNotFound { token_id: TokenId },
+    #[error("neg risk flag of token '{token_id}' is required for a dry run (use --neg-risk or --cache-dir)")]
+    NegRiskNotFound { token_id: TokenId },
+    #[error("failed to create signer")]
+    SignerFailed { source: ClobSignerArgsSignerError },
+    #[error("funder address is required for the '{signature_type:?}' signature type (use --funder)")]
+    FunderNotFound { signature_type: ClobSignatureType },
+    #[error("expiration '{expiration}' must not be before the Unix epoch")]
+    ExpirationTryFromFailed { source: TryFromIntError, expiration: DateTime<Utc> },
+    #[error("failed to sign order offline")]
+    SignOfflineFailed { source: ClobOfflineLimitOrderSignError },
     #[error("failed to authenticate clob client")]
-    AuthenticateFailed { source: PolymarketError },
+    AuthenticateFailed { source: ClobAuthArgsAuthenticateError },
+    #[error("failed to load market constraints")]
+    ValidatorFailed { source: ClobPlaceLimitOrderCommandValidatorError },
+    #[error("order violates market constraints")]
+    ValidateFailed { source: LimitOrderValidatorValidateError },
     #[error("failed to build limit order")]
     BuildLimitOrderFailed { source: PolymarketError },
     #[error("failed to sign order")]
     SignOrderFailed { source: PolymarketError },
+    #[error("failed to serialize signed order")]
+    SerializeSignedOrderFailed { source: serde_json::Error },
     #[error("failed to post order")]
     PostOrderFailed { source: PolymarketError },
     #[error("failed to serialize output")]
@@ -150,22 +273,34 @@ pub enum ClobPlaceLimitOrderCommandRunError {
     WriteOutputNewlineFailed { source: io::Error },
 }
 
-#[derive(ValueEnum, Clone, Copy, Debug)]
-pub enum ClobPlaceLimitOrderCommandSignatureType {
-    Eoa,
-    Proxy,
-    GnosisSafe,
+#[derive(Error, Debug)]
+pub enum ClobPlaceLimitOrderCommandValidatorError {
+    #[error("failed to read cached market")]
+    ReadCachedMarketFailed { source: ClobPlaceLimitOrderCommandReadCachedMarketError },
+    #[error("failed to fetch order book")]
+    FetchBookFailed { source: ClobClientOrderBookSnapshotError },
+    #[error("failed to fetch market")]
+    FetchMarketFailed { source: ClobPlaceLimitOrderCommandFetchMarketError },
 }
 
-impl From<ClobPlaceLimitOrderCommandSignatureType> for PolymarketClobSignatureType {
-    fn from(input: ClobPlaceLimitOrderCommandSignatureType) -> Self {
-        use ClobPlaceLimitOrderCommandSignatureType::*;
-        match input {
-            Eoa => Self::Eoa,
-            Proxy => Self::Proxy,
-            GnosisSafe => Self::GnosisSafe,
-        }
-    }
+#[derive(Error, Debug)]
+pub enum ClobPlaceLimitOrderCommandFetchMarketError {
+    #[error("failed to fetch market '{condition_id}'")]
+    MarketFailed { source: PolymarketError, condition_id: ConditionId },
+    #[error("failed to convert market response '{condition_id}'")]
+    MarketResponseTryFromFailed { source: Box<ClobMarketResponsePreciseFallible>, condition_id: ConditionId },
+    #[error("failed to convert market '{condition_id}'")]
+    MarketTryFromFailed { source: Box<ClobMarketFallible>, condition_id: ConditionId },
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPlaceLimitOrderCommandReadCachedMarketError {
+    #[error("failed to open database at '{dir}'")]
+    OpenDatabaseFailed { source: FjallError, dir: PathBuf },
+    #[error("failed to open market repository")]
+    OpenRepositoryFailed { source: RepositoryOpenError },
+    #[error("failed to read cached market entry")]
+    ReadEntryFailed { source: RepositoryReadEntryError },
 }
 
 /// This struct is needed because [`PostOrderResponse`] doesn't implement [`serde::Serialize`]
@@ -0,0 +1,157 @@
+use crate::{ClobAuthArgs, ClobAuthArgsAuthenticateError, ClobClientOrderBookSnapshotError, ClobPlaceLimitOrderCommandOutput, MarketOrderFill, MarketOrderFillSimulateError, OrderBook, OrderBookSnapshotSource, OrderBookSummaryResponsePrecise, OrderType, Repository, RepositoryGetError, RepositoryOpenError, Side, TokenId};
+use clap::ValueEnum;
+use errgonomic::{handle, handle_bool, handle_opt};
+use fjall::{Error as FjallError, SingleWriterTxDatabase};
+use polymarket_client_sdk::clob::types::Amount as PolymarketClobAmount;
+use polymarket_client_sdk::error::Error as PolymarketError;
+use polymarket_client_sdk::types::Decimal;
+use std::io::{self, Write, stdout};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Places a market order with the limit price derived from the current book
+///
+/// The `amount` is denominated in USDC for buy orders and in shares for sell orders.
+#[derive(clap::Parser, Clone, Debug)]
+pub struct ClobPlaceMarketOrderCommand {
+    #[command(flatten)]
+    pub auth: ClobAuthArgs,
+
+    #[arg(long)]
+    pub token_id: TokenId,
+
+    #[arg(long, value_enum)]
+    pub side: Side,
+
+    #[arg(long)]
+    pub amount: Decimal,
+
+    #[arg(long = "type", value_enum, default_value_t = ClobMarketOrderType::Fok)]
+    pub order_type: ClobMarketOrderType,
+
+    /// Read the book from the cache at this dir instead of fetching it from the API
+    #[arg(long)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Post the order even if the book doesn't have enough liquidity to fill the whole amount
+    #[arg(long)]
+    pub allow_partial: bool,
+}
+
+impl ClobPlaceMarketOrderCommand {
+    pub async fn run(self) -> Result<ExitCode, ClobPlaceMarketOrderCommandRunError> {
+        use ClobPlaceMarketOrderCommandRunError::*;
+        let Self {
+            auth,
+            token_id,
+            side,
+            amount,
+            order_type,
+            cache_dir,
+            allow_partial,
+        } = self;
+        let (client, signer) = handle!(auth.authenticate().await, AuthenticateFailed);
+        let orderbook = match cache_dir {
+            Some(dir) => handle!(Self::read_cached_orderbook(dir, token_id), ReadCachedOrderbookFailed),
+            None => handle!(client.order_book_snapshot(token_id).await, FetchOrderbookFailed),
+        };
+        let book = OrderBook::from(orderbook);
+        let expected = handle!(MarketOrderFill::simulate(&book, side, amount), SimulateFailed, token_id);
+        handle_bool!(!expected.is_complete && !allow_partial, InsufficientLiquidity, token_id, expected);
+        let order_amount = match side {
+            Side::Buy => handle!(PolymarketClobAmount::usdc(amount), AmountFailed, amount),
+            Side::Sell => handle!(PolymarketClobAmount::shares(amount), AmountFailed, amount),
+        };
+        let market_order_builder = client
+            .market_order()
+            .token_id(token_id)
+            .side(side.into())
+            .amount(order_amount)
+            .price(expected.worst_price)
+            .order_type(OrderType::from(order_type).into());
+        let signable_order = handle!(market_order_builder.build().await, BuildMarketOrderFailed);
+        let signed_order = handle!(client.sign(&signer, signable_order).await, SignOrderFailed);
+        let response = handle!(client.post_order(signed_order).await, PostOrderFailed);
+
+        let output = ClobPlaceMarketOrderCommandOutput {
+            expected,
+            actual: ClobPlaceLimitOrderCommandOutput::from(response),
+        };
+        let mut stdout = stdout().lock();
+        handle!(serde_json::to_writer(&mut stdout, &output), SerializeOutputFailed);
+        handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+
+    fn read_cached_orderbook(dir: PathBuf, token_id: TokenId) -> Result<OrderBookSummaryResponsePrecise, ClobPlaceMarketOrderCommandReadCachedOrderbookError> {
+        use ClobPlaceMarketOrderCommandReadCachedOrderbookError::*;
+        let db = handle!(SingleWriterTxDatabase::builder(&dir).open(), OpenDatabaseFailed, dir);
+        let repository = handle!(Repository::<OrderBookSummaryResponsePrecise>::open(&db), RepositoryOpenFailed);
+        let snapshot = db.read_tx();
+        let orderbook_opt = handle!(repository.get(&snapshot, &token_id.to_string()), GetFailed, token_id);
+        let orderbook = handle_opt!(orderbook_opt, OrderbookNotFound, token_id);
+        Ok(orderbook)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPlaceMarketOrderCommandRunError {
+    #[error("failed to authenticate clob client")]
+    AuthenticateFailed { source: ClobAuthArgsAuthenticateError },
+    #[error("failed to read cached order book")]
+    ReadCachedOrderbookFailed { source: ClobPlaceMarketOrderCommandReadCachedOrderbookError },
+    #[error("failed to fetch order book")]
+    FetchOrderbookFailed { source: ClobClientOrderBookSnapshotError },
+    #[error("failed to simulate market order for token '{token_id}'")]
+    SimulateFailed { source: MarketOrderFillSimulateError, token_id: TokenId },
+    #[error("book of token '{token_id}' can fill only {shares} shares for {notional} USDC (pass --allow-partial to post the order anyway)", shares = expected.shares, notional = expected.notional)]
+    InsufficientLiquidity { token_id: TokenId, expected: MarketOrderFill },
+    #[error("invalid market order amount '{amount}'")]
+    AmountFailed { source: PolymarketError, amount: Decimal },
+    #[error("failed to build market order")]
+    BuildMarketOrderFailed { source: PolymarketError },
+    #[error("failed to sign order")]
+    SignOrderFailed { source: PolymarketError },
+    #[error("failed to post order")]
+    PostOrderFailed { source: PolymarketError },
+    #[error("failed to serialize output")]
+    SerializeOutputFailed { source: serde_json::Error },
+    #[error("failed to write output newline")]
+    WriteOutputNewlineFailed { source: io::Error },
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPlaceMarketOrderCommandReadCachedOrderbookError {
+    #[error("failed to open database at '{dir}'")]
+    OpenDatabaseFailed { source: FjallError, dir: PathBuf },
+    #[error("failed to open order book repository")]
+    RepositoryOpenFailed { source: RepositoryOpenError },
+    #[error("failed to read cached order book for token '{token_id}'")]
+    GetFailed { source: RepositoryGetError, token_id: TokenId },
+    #[error("order book for token '{token_id}' is not cached")]
+    OrderbookNotFound { token_id: TokenId },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ClobMarketOrderType {
+    /// Fill-or-kill: the order must be filled completely or not at all
+    Fok,
+    /// Fill-and-kill: the unfilled part of the order is cancelled
+    Fak,
+}
+
+impl From<ClobMarketOrderType> for OrderType {
+    fn from(input: ClobMarketOrderType) -> Self {
+        match input {
+            ClobMarketOrderType::Fok => OrderType::Fok,
+            ClobMarketOrderType::Fak => OrderType::Fak,
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ClobPlaceMarketOrderCommandOutput {
+    pub expected: MarketOrderFill,
+    pub actual: ClobPlaceLimitOrderCommandOutput,
+}
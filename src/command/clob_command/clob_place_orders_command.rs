@@ -0,0 +1,292 @@
+use crate::{AuthenticatedClobClient, ClobAuthArgs, ClobAuthArgsAuthenticateError, ClobOrderSpec, ClobPlaceLimitOrderCommandOutput, ClobSigner};
+use core::error::Error as StdError;
+use core::iter::successors;
+use errgonomic::{ErrVec, handle, handle_bool, handle_iter, handle_opt};
+use itertools::Itertools;
+use polymarket_client_sdk::clob::types::SignedOrder;
+use polymarket_client_sdk::clob::types::response::PostOrderResponse;
+use polymarket_client_sdk::error::Error as PolymarketError;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write, stdin, stdout};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// The CLOB API accepts at most 15 orders per batch request
+const POST_ORDERS_CHUNK_SIZE: usize = 15;
+
+/// Places the orders from a JSONL input (one [`ClobOrderSpec`] per line)
+///
+/// All orders are signed before any order is posted, so an invalid line doesn't result in a partially posted batch.
+/// An order that can't be signed or posted is reported as an error line with its id, and the remaining orders are still posted.
+/// The command exits with a failure code if any output line is an error.
+#[derive(clap::Parser, Clone, Debug)]
+pub struct ClobPlaceOrdersCommand {
+    #[command(flatten)]
+    pub auth: ClobAuthArgs,
+
+    /// Read the orders from this file instead of stdin
+    #[arg(long)]
+    pub input: Option<PathBuf>,
+}
+
+impl ClobPlaceOrdersCommand {
+    pub async fn run(self) -> Result<ExitCode, ClobPlaceOrdersCommandRunError> {
+        use ClobPlaceOrdersCommandRunError::*;
+        let Self {
+            auth,
+            input,
+        } = self;
+        let reader: Box<dyn BufRead> = match input {
+            Some(input) => Box::new(BufReader::new(handle!(File::open(&input), OpenInputFailed, input))),
+            None => Box::new(stdin().lock()),
+        };
+        let specs = handle!(Self::read_specs(reader), ReadSpecsFailed);
+        let (client, signer) = handle!(auth.authenticate().await, AuthenticateFailed);
+        let mut stdout = stdout().lock();
+        let mut signed_orders = Vec::with_capacity(specs.len());
+        let mut has_errors = false;
+        for (id, spec) in specs {
+            match Self::sign_order(&client, &signer, spec).await {
+                Ok(signed_order) => signed_orders.push((id, signed_order)),
+                Err(error) => {
+                    has_errors = true;
+                    handle!(Self::write_output(&mut stdout, ClobPlaceOrdersCommandOutput::error(id, &error)), WriteOutputFailed)
+                }
+            }
+        }
+
+        for chunk in &signed_orders.into_iter().chunks(POST_ORDERS_CHUNK_SIZE) {
+            let (ids, orders): (Vec<String>, Vec<SignedOrder>) = chunk.unzip();
+            let outputs = match client.post_orders(orders).await {
+                Ok(responses) if responses.len() == ids.len() => ids
+                    .into_iter()
+                    .zip(responses)
+                    .map(|(id, response)| ClobPlaceOrdersCommandOutput::response(id, response))
+                    .collect_vec(),
+                Ok(responses) => {
+                    let error = ClobPlaceOrdersCommandPostChunkError::ResponseCountMismatch {
+                        expected: ids.len(),
+                        response_count: responses.len(),
+                    };
+                    ids.into_iter()
+                        .map(|id| ClobPlaceOrdersCommandOutput::error(id, &error))
+                        .collect_vec()
+                }
+                Err(source) => {
+                    let error = ClobPlaceOrdersCommandPostChunkError::PostOrdersFailed {
+                        source,
+                    };
+                    ids.into_iter()
+                        .map(|id| ClobPlaceOrdersCommandOutput::error(id, &error))
+                        .collect_vec()
+                }
+            };
+            for output in outputs {
+                has_errors |= output.is_error();
+                handle!(Self::write_output(&mut stdout, output), WriteOutputFailed);
+            }
+        }
+        if has_errors { Ok(ExitCode::FAILURE) } else { Ok(ExitCode::SUCCESS) }
+    }
+
+    async fn sign_order(client: &AuthenticatedClobClient, signer: &ClobSigner, spec: ClobOrderSpec) -> Result<SignedOrder, ClobPlaceOrdersCommandSignOrderError> {
+        use ClobPlaceOrdersCommandSignOrderError::*;
+        let ClobOrderSpec {
+            token_id,
+            side,
+            price,
+            size,
+            order_type,
+            expiration,
+            post_only,
+            ..
+        } = spec;
+        let limit_order_builder = client
+            .limit_order()
+            .token_id(token_id)
+            .side(side.into())
+            .price(price)
+            .size(size)
+            .order_type(order_type.into())
+            .post_only(post_only);
+        let limit_order_builder = match expiration {
+            Some(expiration) => limit_order_builder.expiration(expiration),
+            None => limit_order_builder,
+        };
+        let signable_order = handle!(limit_order_builder.build().await, BuildLimitOrderFailed);
+        let signed_order = handle!(client.sign(signer, signable_order).await, SignOrderFailed);
+        Ok(signed_order)
+    }
+
+    fn write_output(writer: &mut impl Write, output: ClobPlaceOrdersCommandOutput) -> Result<(), ClobPlaceOrdersCommandWriteOutputError> {
+        use ClobPlaceOrdersCommandWriteOutputError::*;
+        handle!(serde_json::to_writer(&mut *writer, &output), SerializeOutputFailed);
+        handle!(writer.write_all(b"\n"), WriteOutputNewlineFailed);
+        Ok(())
+    }
+
+    /// Returns the specs with their correlation ids (the line number is used if the spec doesn't have an `id`)
+    fn read_specs(reader: impl BufRead) -> Result<Vec<(String, ClobOrderSpec)>, ClobPlaceOrdersCommandReadSpecsError> {
+        use ClobPlaceOrdersCommandReadSpecsError::*;
+        let specs = handle_iter!(
+            reader
+                .lines()
+                .enumerate()
+                .filter(|(_line_index, line_result)| !line_result
+                    .as_ref()
+                    .is_ok_and(|line| line.trim().is_empty()))
+                .map(|(line_index, line_result)| {
+                    use ClobPlaceOrdersCommandReadSpecError::*;
+                    let line = handle!(line_result, ReadLineFailed, line_index);
+                    let line_number = handle_opt!(line_index.checked_add(1), LineNumberCheckedAddFailed, line_index);
+                    let spec = handle!(serde_json::from_str::<ClobOrderSpec>(&line), DeserializeLineFailed, line, line_number);
+                    let id = spec.id.clone().unwrap_or_else(|| line_number.to_string());
+                    Ok((id, spec))
+                }),
+            ReadSpecFailed
+        );
+        let duplicates = specs
+            .iter()
+            .map(|(id, _spec)| id)
+            .duplicates()
+            .cloned()
+            .collect_vec();
+        handle_bool!(!duplicates.is_empty(), DuplicateIdsFound, duplicates);
+        Ok(specs)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPlaceOrdersCommandRunError {
+    #[error("failed to open input file '{input}'")]
+    OpenInputFailed { source: io::Error, input: PathBuf },
+    #[error("failed to read order specs")]
+    ReadSpecsFailed { source: ClobPlaceOrdersCommandReadSpecsError },
+    #[error("failed to authenticate clob client")]
+    AuthenticateFailed { source: ClobAuthArgsAuthenticateError },
+    #[error("failed to write output")]
+    WriteOutputFailed { source: ClobPlaceOrdersCommandWriteOutputError },
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPlaceOrdersCommandSignOrderError {
+    #[error("failed to build limit order")]
+    BuildLimitOrderFailed { source: PolymarketError },
+    #[error("failed to sign order")]
+    SignOrderFailed { source: PolymarketError },
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPlaceOrdersCommandPostChunkError {
+    #[error("failed to post orders")]
+    PostOrdersFailed { source: PolymarketError },
+    #[error("expected {expected} responses, got {response_count}")]
+    ResponseCountMismatch { expected: usize, response_count: usize },
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPlaceOrdersCommandWriteOutputError {
+    #[error("failed to serialize output")]
+    SerializeOutputFailed { source: serde_json::Error },
+    #[error("failed to write output newline")]
+    WriteOutputNewlineFailed { source: io::Error },
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPlaceOrdersCommandReadSpecsError {
+    #[error("failed to read {len} order spec lines", len = source.len())]
+    ReadSpecFailed { source: ErrVec<ClobPlaceOrdersCommandReadSpecError> },
+    #[error("found duplicate order ids: {duplicates:?}")]
+    DuplicateIdsFound { duplicates: Vec<String> },
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPlaceOrdersCommandReadSpecError {
+    #[error("failed to read line at index {line_index}")]
+    ReadLineFailed { source: io::Error, line_index: usize },
+    #[error("failed to compute line number for index {line_index}")]
+    LineNumberCheckedAddFailed { line_index: usize },
+    #[error("failed to deserialize order spec at line {line_number}")]
+    DeserializeLineFailed { source: serde_json::Error, line: String, line_number: usize },
+}
+
+/// One output line per order: either the response of the API or the error that prevented the order from being posted
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ClobPlaceOrdersCommandOutput {
+    pub id: String,
+    #[serde(flatten)]
+    pub result: ClobPlaceOrdersCommandOutputResult,
+}
+
+impl ClobPlaceOrdersCommandOutput {
+    pub fn response(id: String, response: PostOrderResponse) -> Self {
+        Self {
+            id,
+            result: ClobPlaceOrdersCommandOutputResult::Response(ClobPlaceLimitOrderCommandOutput::from(response)),
+        }
+    }
+
+    /// The error is formatted with its sources, because the output is the only place where the error is reported
+    pub fn error(id: String, error: &(dyn StdError + 'static)) -> Self {
+        let message = successors(Some(error), |error| error.source())
+            .map(ToString::to_string)
+            .join(": ");
+        Self {
+            id,
+            result: ClobPlaceOrdersCommandOutputResult::Error(message),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.result, ClobPlaceOrdersCommandOutputResult::Error(_))
+    }
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ClobPlaceOrdersCommandOutputResult {
+    Response(ClobPlaceLimitOrderCommandOutput),
+    Error(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_read_specs_with_correlation_ids() -> Result<(), ClobPlaceOrdersCommandReadSpecsError> {
+        let input = concat!(r#"{"id":"a","token_id":"7","side":"BUY","price":"0.5","size":"10"}"#, "\n", r#"{"token_id":"7","side":"SELL","price":"0.6","size":"10","type":"FOK","post_only":false}"#, "\n");
+        let specs = ClobPlaceOrdersCommand::read_specs(input.as_bytes())?;
+        let ids = specs.iter().map(|(id, _spec)| id.as_str()).collect_vec();
+        assert_eq!(ids, vec!["a", "2"]);
+        Ok(())
+    }
+
+    #[test]
+    fn must_skip_blank_lines() -> Result<(), ClobPlaceOrdersCommandReadSpecsError> {
+        let input = concat!("\n", r#"{"token_id":"7","side":"BUY","price":"0.5","size":"10"}"#, "\n", "  \n", r#"{"token_id":"7","side":"SELL","price":"0.6","size":"10"}"#, "\n");
+        let specs = ClobPlaceOrdersCommand::read_specs(input.as_bytes())?;
+        let ids = specs.iter().map(|(id, _spec)| id.as_str()).collect_vec();
+        assert_eq!(ids, vec!["2", "4"]);
+        Ok(())
+    }
+
+    #[test]
+    fn must_mark_error_output() {
+        let error = ClobPlaceOrdersCommandPostChunkError::ResponseCountMismatch {
+            expected: 2,
+            response_count: 1,
+        };
+        let output = ClobPlaceOrdersCommandOutput::error("a".to_string(), &error);
+        assert!(output.is_error());
+        assert!(matches!(output.result, ClobPlaceOrdersCommandOutputResult::Error(ref message) if message == "expected 2 responses, got 1"));
+    }
+
+    #[test]
+    fn must_reject_duplicate_ids() {
+        let input = concat!(r#"{"id":"2","token_id":"7","side":"BUY","price":"0.5","size":"10"}"#, "\n", r#"{"token_id":"7","side":"BUY","price":"0.5","size":"10"}"#, "\n");
+        let result = ClobPlaceOrdersCommand::read_specs(input.as_bytes());
+        assert!(matches!(result, Err(ClobPlaceOrdersCommandReadSpecsError::DuplicateIdsFound { .. })));
+    }
+}
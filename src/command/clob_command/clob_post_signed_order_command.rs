@@ -0,0 +1,65 @@
+use crate::{ClobApiCredentialsArgs, ClobApiCredentialsArgsAuthenticateError, ClobPlaceLimitOrderCommandOutput, ClobSignedOrder};
+use errgonomic::handle;
+use polymarket_client_sdk::clob::types::response::PostOrderResponse;
+use polymarket_client_sdk::error::Error as PolymarketError;
+use std::io::{self, Write, stdin, stdout};
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Posts a signed order that is read from stdin (the output of `clob place-limit-order --dry-run`)
+///
+/// The order is already signed, so only the L2 API credentials are required (the private key is not needed).
+#[derive(clap::Parser, Clone, Debug)]
+pub struct ClobPostSignedOrderCommand {
+    #[command(flatten)]
+    pub credentials: ClobApiCredentialsArgs,
+}
+
+impl ClobPostSignedOrderCommand {
+    pub async fn run(self) -> Result<ExitCode, ClobPostSignedOrderCommandRunError> {
+        use ClobPostSignedOrderCommandRunError::*;
+        let signed_order = handle!(serde_json::from_reader::<_, ClobSignedOrder>(stdin().lock()), DeserializeSignedOrderFailed);
+        let response = handle!(self.post(signed_order).await, PostFailed);
+
+        let output = ClobPlaceLimitOrderCommandOutput::from(response);
+        let mut stdout = stdout().lock();
+        handle!(serde_json::to_writer(&mut stdout, &output), SerializeOutputFailed);
+        handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
+        Ok(ExitCode::SUCCESS)
+    }
+
+    pub async fn post(self, signed_order: ClobSignedOrder) -> Result<PostOrderResponse, ClobPostSignedOrderCommandPostError> {
+        use ClobPostSignedOrderCommandPostError::*;
+        let Self {
+            credentials,
+        } = self;
+        let (client, api_key) = handle!(credentials.authenticate().await, AuthenticateFailed);
+        let response = handle!(
+            client
+                .post_order(signed_order.into_signed_order(api_key))
+                .await,
+            PostOrderFailed
+        );
+        Ok(response)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPostSignedOrderCommandRunError {
+    #[error("failed to deserialize signed order from stdin")]
+    DeserializeSignedOrderFailed { source: serde_json::Error },
+    #[error("failed to post signed order")]
+    PostFailed { source: ClobPostSignedOrderCommandPostError },
+    #[error("failed to serialize output")]
+    SerializeOutputFailed { source: serde_json::Error },
+    #[error("failed to write output newline")]
+    WriteOutputNewlineFailed { source: io::Error },
+}
+
+#[derive(Error, Debug)]
+pub enum ClobPostSignedOrderCommandPostError {
+    #[error("failed to authenticate clob client")]
+    AuthenticateFailed { source: ClobApiCredentialsArgsAuthenticateError },
+    #[error("failed to post order")]
+    PostOrderFailed { source: PolymarketError },
+}
@@ -0,0 +1,169 @@
+use crate::{ClobSigner, RemoteSigner, RemoteSignerConnectError};
+use alloy::signers::local::{LocalSignerError, MnemonicBuilder, PrivateKeySigner};
+use clap::ValueEnum;
+use errgonomic::{handle, handle_opt};
+use rpassword::prompt_password;
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+use url::Url;
+
+/// The arguments that select the signer backend (only the arguments of the selected backend are used)
+#[derive(clap::Args, Clone, Debug)]
+pub struct ClobSignerArgs {
+    #[arg(long, value_enum, default_value_t = ClobSignerKind::Mnemonic)]
+    pub signer: ClobSignerKind,
+
+    /// Seed phrase (mnemonic words). Can also be provided via POLYMARKET_SEED_PHRASE env var.
+    #[arg(long, env = "POLYMARKET_SEED_PHRASE", hide_env_values = true)]
+    pub seed_phrase: Option<String>,
+
+    /// Optional seed phrase passphrase (BIP-39). Can also be provided via POLYMARKET_SEED_PHRASE_PASSWORD env var.
+    #[arg(long, env = "POLYMARKET_SEED_PHRASE_PASSWORD", hide_env_values = true)]
+    pub seed_phrase_password: Option<String>,
+
+    /// Account index used in derivation path m/44'/60'/0'/0/{index}
+    #[arg(long, default_value_t = 0)]
+    pub account_index: u32,
+
+    /// Hex-encoded private key. Can also be provided via POLYMARKET_PRIVATE_KEY env var.
+    #[arg(long, env = "POLYMARKET_PRIVATE_KEY", hide_env_values = true)]
+    pub private_key: Option<String>,
+
+    /// Path to an encrypted JSON keystore file
+    #[arg(long)]
+    pub keystore: Option<PathBuf>,
+
+    /// Keystore password. Can also be provided via POLYMARKET_KEYSTORE_PASSWORD env var (prompted if not provided).
+    #[arg(long, env = "POLYMARKET_KEYSTORE_PASSWORD", hide_env_values = true)]
+    pub keystore_password: Option<String>,
+
+    /// Base URL of the remote signer service (see [`RemoteSigner`])
+    #[arg(long)]
+    pub remote_signer_url: Option<Url>,
+}
+
+impl ClobSignerArgs {
+    pub async fn signer(self) -> Result<ClobSigner, ClobSignerArgsSignerError> {
+        use ClobSignerArgsSignerError::*;
+        let Self {
+            signer,
+            seed_phrase,
+            seed_phrase_password,
+            account_index,
+            private_key,
+            keystore,
+            keystore_password,
+            remote_signer_url,
+        } = self;
+        match signer {
+            ClobSignerKind::Mnemonic => {
+                let seed_phrase = handle_opt!(seed_phrase, SeedPhraseNotFound);
+                let mnemonic_builder = MnemonicBuilder::english().phrase(seed_phrase);
+                let mnemonic_builder = handle!(mnemonic_builder.index(account_index), MnemonicBuilderIndexFailed, account_index);
+                let mnemonic_builder = match seed_phrase_password {
+                    Some(seed_phrase_password) => mnemonic_builder.password(seed_phrase_password),
+                    None => mnemonic_builder,
+                };
+                let signer = handle!(mnemonic_builder.build(), MnemonicBuilderBuildFailed, account_index);
+                Ok(ClobSigner::Local(signer))
+            }
+            ClobSignerKind::PrivateKey => {
+                let private_key = handle_opt!(private_key, PrivateKeyNotFound);
+                let signer = handle!(private_key.parse::<PrivateKeySigner>(), PrivateKeyParseFailed);
+                Ok(ClobSigner::Local(signer))
+            }
+            ClobSignerKind::Keystore => {
+                let keystore = handle_opt!(keystore, KeystoreNotFound);
+                let keystore_password = match keystore_password {
+                    Some(keystore_password) => keystore_password,
+                    None => handle!(prompt_password("Keystore password: "), PromptPasswordFailed),
+                };
+                let signer = handle!(PrivateKeySigner::decrypt_keystore(&keystore, keystore_password), DecryptKeystoreFailed, keystore);
+                Ok(ClobSigner::Local(signer))
+            }
+            ClobSignerKind::Remote => {
+                let remote_signer_url = handle_opt!(remote_signer_url, RemoteSignerUrlNotFound);
+                let signer = handle!(RemoteSigner::connect(remote_signer_url).await, RemoteSignerConnectFailed);
+                Ok(ClobSigner::Remote(signer))
+            }
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobSignerArgsSignerError {
+    #[error("seed phrase is required for the mnemonic signer (use --seed-phrase or POLYMARKET_SEED_PHRASE)")]
+    SeedPhraseNotFound,
+    #[error("failed to set mnemonic derivation index '{account_index}'")]
+    MnemonicBuilderIndexFailed { source: LocalSignerError, account_index: u32 },
+    #[error("failed to build signer from mnemonic at account index '{account_index}'")]
+    MnemonicBuilderBuildFailed { source: LocalSignerError, account_index: u32 },
+    #[error("private key is required for the private key signer (use --private-key or POLYMARKET_PRIVATE_KEY)")]
+    PrivateKeyNotFound,
+    #[error("failed to parse private key")]
+    PrivateKeyParseFailed { source: LocalSignerError },
+    #[error("keystore path is required for the keystore signer (use --keystore)")]
+    KeystoreNotFound,
+    #[error("failed to prompt for keystore password")]
+    PromptPasswordFailed { source: io::Error },
+    #[error("failed to decrypt keystore '{keystore}'")]
+    DecryptKeystoreFailed { source: LocalSignerError, keystore: PathBuf },
+    #[error("remote signer URL is required for the remote signer (use --remote-signer-url)")]
+    RemoteSignerUrlNotFound,
+    #[error("failed to connect to remote signer")]
+    RemoteSignerConnectFailed { source: RemoteSignerConnectError },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ClobSignerKind {
+    Mnemonic,
+    PrivateKey,
+    Keystore,
+    Remote,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::Signer;
+
+    const KEYSTORE_PRIVATE_KEY: &str = "0x0123456789012345678901234567890123456789012345678901234567890123";
+
+    fn keystore_signer_args(keystore_password: &str) -> ClobSignerArgs {
+        ClobSignerArgs {
+            signer: ClobSignerKind::Keystore,
+            seed_phrase: None,
+            seed_phrase_password: None,
+            account_index: 0,
+            private_key: None,
+            keystore: Some(PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/keystore.json"))),
+            keystore_password: Some(keystore_password.to_string()),
+            remote_signer_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn must_load_signer_from_keystore() -> Result<(), ClobSignerArgsTestError> {
+        use ClobSignerArgsTestError::*;
+        let expected = handle!(KEYSTORE_PRIVATE_KEY.parse::<PrivateKeySigner>(), PrivateKeyParseFailed);
+        let signer = handle!(keystore_signer_args("password").signer().await, SignerFailed);
+        assert!(matches!(signer, ClobSigner::Local(_)));
+        assert_eq!(signer.address(), expected.address());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_reject_keystore_with_wrong_password() {
+        let result = keystore_signer_args("wrong password").signer().await;
+        assert!(matches!(result, Err(ClobSignerArgsSignerError::DecryptKeystoreFailed { .. })));
+    }
+
+    #[derive(Error, Debug)]
+    enum ClobSignerArgsTestError {
+        #[error("failed to parse private key")]
+        PrivateKeyParseFailed { source: LocalSignerError },
+        #[error("failed to create signer")]
+        SignerFailed { source: ClobSignerArgsSignerError },
+    }
+}
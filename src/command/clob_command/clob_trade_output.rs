@@ -0,0 +1,47 @@
+use crate::UintAsString;
+use polymarket_client_sdk::clob::types::Side;
+use polymarket_client_sdk::clob::types::response::TradeResponse;
+use polymarket_client_sdk::types::{B256, Decimal, U256};
+
+/// This struct is needed because [`TradeResponse`] doesn't implement [`serde::Serialize`]
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ClobTradeOutput {
+    pub id: String,
+    pub taker_order_id: String,
+    pub market: B256,
+    #[serde(with = "UintAsString")]
+    pub asset_id: U256,
+    pub side: Side,
+    pub size: Decimal,
+    pub price: Decimal,
+    pub outcome: String,
+    pub transaction_hash: B256,
+}
+
+impl From<TradeResponse> for ClobTradeOutput {
+    fn from(input: TradeResponse) -> Self {
+        let TradeResponse {
+            id,
+            taker_order_id,
+            market,
+            asset_id,
+            side,
+            size,
+            price,
+            outcome,
+            transaction_hash,
+            ..
+        } = input;
+        Self {
+            id,
+            taker_order_id,
+            market,
+            asset_id,
+            side,
+            size,
+            price,
+            outcome,
+            transaction_hash,
+        }
+    }
+}
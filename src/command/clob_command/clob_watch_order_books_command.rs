@@ -0,0 +1,67 @@
+use crate::{CLOB_MARKET_CHANNEL_URL, ClobClientOrderBookSnapshotError, DEFAULT_CLOB_HOST, Level, MarketChannelSubscriber, MarketChannelSubscriberConnectError, MarketChannelSubscriberNextUpdateError, MarketChannelUpdate, TokenId};
+use errgonomic::handle;
+use polymarket_client_sdk::clob::{Client as PolymarketClobClient, Config as PolymarketClobConfig};
+use polymarket_client_sdk::error::Error as PolymarketError;
+use std::io::{self, Write, stdout};
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Subscribes to the market channel and prints a line per update of the local order books
+#[derive(clap::Parser, Clone, Debug)]
+pub struct ClobWatchOrderBooksCommand {
+    #[arg(long, default_value = DEFAULT_CLOB_HOST)]
+    pub clob_host: String,
+
+    #[arg(long, default_value = CLOB_MARKET_CHANNEL_URL)]
+    pub url: String,
+
+    #[arg(long = "token-id", required = true)]
+    pub token_ids: Vec<TokenId>,
+}
+
+impl ClobWatchOrderBooksCommand {
+    pub async fn run(self) -> Result<ExitCode, ClobWatchOrderBooksCommandRunError> {
+        use ClobWatchOrderBooksCommandRunError::*;
+        let Self {
+            clob_host,
+            url,
+            token_ids,
+        } = self;
+        let client = handle!(PolymarketClobClient::new(&clob_host, PolymarketClobConfig::default()), ClientNewFailed, host: clob_host);
+        let mut subscriber = handle!(MarketChannelSubscriber::connect(&url, client, token_ids).await, ConnectFailed, url);
+        let mut stdout = stdout().lock();
+        while let Some(update) = handle!(subscriber.next_update().await, NextUpdateFailed) {
+            let book = subscriber.book(&update.token_id());
+            let output = ClobWatchOrderBooksCommandOutput {
+                update,
+                best_bid: book.and_then(|book| book.best_bid()),
+                best_ask: book.and_then(|book| book.best_ask()),
+            };
+            handle!(serde_json::to_writer(&mut stdout, &output), SerializeOutputFailed);
+            handle!(stdout.write_all(b"\n"), WriteOutputNewlineFailed);
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobWatchOrderBooksCommandRunError {
+    #[error("failed to initialize clob client for host '{host}'")]
+    ClientNewFailed { source: PolymarketError, host: String },
+    #[error("failed to subscribe to market channel at '{url}'")]
+    ConnectFailed { source: MarketChannelSubscriberConnectError<ClobClientOrderBookSnapshotError>, url: String },
+    #[error("failed to receive order book update")]
+    NextUpdateFailed { source: MarketChannelSubscriberNextUpdateError<ClobClientOrderBookSnapshotError> },
+    #[error("failed to serialize output")]
+    SerializeOutputFailed { source: serde_json::Error },
+    #[error("failed to write output newline")]
+    WriteOutputNewlineFailed { source: io::Error },
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ClobWatchOrderBooksCommandOutput {
+    #[serde(flatten)]
+    pub update: MarketChannelUpdate,
+    pub best_bid: Option<Level>,
+    pub best_ask: Option<Level>,
+}
@@ -0,0 +1,40 @@
+use crate::{DEFAULT_CLOB_REQUESTS_PER_SECOND, DEFAULT_GAMMA_REQUESTS_PER_SECOND, DEFAULT_HTTP_BURST, DEFAULT_HTTP_INITIAL_BACKOFF_MILLIS, DEFAULT_HTTP_MAX_BACKOFF_MILLIS, DEFAULT_HTTP_MAX_RETRIES, DEFAULT_HTTP_RETRY_BUDGET, DEFAULT_OPINION_REQUESTS_PER_SECOND, HttpMiddleware, RetryPolicy, TokenBucket};
+use core::time::Duration;
+
+/// The arguments that are shared by the commands that send HTTP requests (see [`HttpMiddleware`])
+#[derive(clap::Args, Clone, Debug)]
+pub struct HttpMiddlewareArgs {
+    /// The number of retries of a single request
+    #[arg(long, default_value_t = DEFAULT_HTTP_MAX_RETRIES)]
+    pub max_retries: u32,
+
+    #[arg(long, default_value_t = DEFAULT_HTTP_INITIAL_BACKOFF_MILLIS)]
+    pub initial_backoff_millis: u64,
+
+    #[arg(long, default_value_t = DEFAULT_HTTP_MAX_BACKOFF_MILLIS)]
+    pub max_backoff_millis: u64,
+
+    /// The number of retries for all requests of the command
+    #[arg(long, default_value_t = DEFAULT_HTTP_RETRY_BUDGET)]
+    pub retry_budget: u32,
+
+    /// The number of requests that may be sent at once before the rate limit applies
+    #[arg(long, default_value_t = DEFAULT_HTTP_BURST)]
+    pub burst: u32,
+
+    #[arg(long, default_value_t = DEFAULT_CLOB_REQUESTS_PER_SECOND)]
+    pub clob_requests_per_second: u32,
+
+    #[arg(long, default_value_t = DEFAULT_GAMMA_REQUESTS_PER_SECOND)]
+    pub gamma_requests_per_second: u32,
+
+    #[arg(long, default_value_t = DEFAULT_OPINION_REQUESTS_PER_SECOND)]
+    pub opinion_requests_per_second: u32,
+}
+
+impl HttpMiddlewareArgs {
+    pub fn middleware(&self) -> HttpMiddleware {
+        let policy = RetryPolicy::new(self.max_retries, Duration::from_millis(self.initial_backoff_millis), Duration::from_millis(self.max_backoff_millis));
+        HttpMiddleware::new(policy, self.retry_budget, TokenBucket::per_second(self.clob_requests_per_second, self.burst), TokenBucket::per_second(self.gamma_requests_per_second, self.burst), TokenBucket::per_second(self.opinion_requests_per_second, self.burst))
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{MarketExchange, MarketRelation, MarketRelationInfo, OpinionMarket, OpinionMarketPage, RelatedMarketsFormat};
+use crate::{ApiHostArgs, ApiHostArgsGammaClientError, HttpEndpoint, HttpMiddleware, HttpMiddlewareArgs, HttpMiddlewareCallError, MarketExchange, MarketRelation, MarketRelationInfo, OpinionMarket, OpinionMarketPage, RelatedMarketsFormat, SendCheckedError, send_checked};
 use async_stream::stream;
 use core::num::TryFromIntError;
 use errgonomic::{handle, handle_opt};
@@ -22,7 +22,7 @@ use url::{ParseError as UrlParseError, Url};
 
 const LIST_RELATED_MARKETS_CACHE_DIR: &str = ".cache/list_related_markets";
 const OPINION_API_KEY_ENV: &str = "OPINION_API_KEY";
-const OPINION_MARKETS_URL: &str = "https://api.opinion.trade/api/v1/markets";
+const OPINION_MARKETS_PATH: &str = "/api/v1/markets";
 const OPINION_PER_PAGE: u32 = 100;
 const OPINION_PER_PAGE_USIZE: usize = 100;
 const POLYMARKET_PAGE_SIZE: i32 = 1000;
@@ -42,6 +42,12 @@ pub struct ListRelatedMarketsCommand {
 
     #[arg(long)]
     pub limit: Option<NonZeroUsize>,
+
+    #[command(flatten)]
+    pub hosts: ApiHostArgs,
+
+    #[command(flatten)]
+    pub http: HttpMiddlewareArgs,
 }
 
 impl ListRelatedMarketsCommand {
@@ -51,7 +57,10 @@ impl ListRelatedMarketsCommand {
             format,
             offset,
             limit,
+            hosts,
+            http,
         } = self;
+        let middleware = http.middleware();
         let offset = offset.unwrap_or(0);
         let limit = limit.map(NonZeroUsize::get);
         let cache_path = Self::cache_path_for_query(offset, limit);
@@ -59,7 +68,7 @@ impl ListRelatedMarketsCommand {
         if cache_path.exists() {
             handle!(Self::write_relations_from_cache(&cache_path, format, &mut writer), WriteRelationsFromCacheFailed, cache_path);
         } else {
-            handle!(Self::write_relations_from_network_and_cache(&cache_path, offset, limit, format, &mut writer).await, WriteRelationsFromNetworkAndCacheFailed, cache_path, offset, limit);
+            handle!(Self::write_relations_from_network_and_cache(&middleware, &hosts, &cache_path, offset, limit, format, &mut writer).await, WriteRelationsFromNetworkAndCacheFailed, cache_path, offset, limit);
         }
         Ok(ExitCode::SUCCESS)
     }
@@ -93,12 +102,12 @@ impl ListRelatedMarketsCommand {
         })
     }
 
-    async fn write_relations_from_network_and_cache(cache_path: &Path, offset: usize, limit: Option<usize>, format: RelatedMarketsFormat, writer: &mut impl Write) -> Result<(), ListRelatedMarketsCommandWriteRelationsFromNetworkAndCacheError> {
+    async fn write_relations_from_network_and_cache(middleware: &HttpMiddleware, hosts: &ApiHostArgs, cache_path: &Path, offset: usize, limit: Option<usize>, format: RelatedMarketsFormat, writer: &mut impl Write) -> Result<(), ListRelatedMarketsCommandWriteRelationsFromNetworkAndCacheError> {
         use ListRelatedMarketsCommandWriteRelationsFromNetworkAndCacheError::*;
         let cache_dir = handle_opt!(cache_path.parent(), CacheParentNotFound, cache_path: cache_path.to_path_buf());
         handle!(create_dir_all(cache_dir), CreateDirAllFailed, cache_dir: cache_dir.to_path_buf());
         let mut temp_file = handle!(NamedTempFile::new_in(cache_dir), CreateTempFileFailed, cache_dir: cache_dir.to_path_buf());
-        let relation_stream = handle!(Self::network_relation_stream().await, NetworkRelationStreamFailed);
+        let relation_stream = handle!(Self::network_relation_stream(middleware, hosts).await, NetworkRelationStreamFailed);
         let mut relation_stream = pin!(relation_stream);
         let mut skipped = 0usize;
         let mut emitted = 0usize;
@@ -131,16 +140,16 @@ impl ListRelatedMarketsCommand {
         Ok(())
     }
 
-    async fn network_relation_stream() -> Result<impl Stream<Item = Result<MarketRelation, ListRelatedMarketsCommandNetworkRelationStreamItemError>>, ListRelatedMarketsCommandNetworkRelationStreamError> {
+    async fn network_relation_stream(middleware: &HttpMiddleware, hosts: &ApiHostArgs) -> Result<impl Stream<Item = Result<MarketRelation, ListRelatedMarketsCommandNetworkRelationStreamItemError>>, ListRelatedMarketsCommandNetworkRelationStreamError> {
         use ListRelatedMarketsCommandNetworkRelationStreamError::*;
-        let opinion_markets_by_question = handle!(Self::fetch_opinion_markets_by_question().await, FetchOpinionMarketsByQuestionFailed);
-        Ok(Self::network_relation_stream_from_opinion_markets(opinion_markets_by_question))
+        let client = handle!(hosts.gamma_client(), GammaClientFailed);
+        let opinion_markets_by_question = handle!(Self::fetch_opinion_markets_by_question(middleware, &hosts.opinion_host).await, FetchOpinionMarketsByQuestionFailed);
+        Ok(Self::network_relation_stream_from_opinion_markets(middleware.clone(), client, opinion_markets_by_question))
     }
 
-    fn network_relation_stream_from_opinion_markets(opinion_markets_by_question: OpinionMarketsByQuestion) -> impl Stream<Item = Result<MarketRelation, ListRelatedMarketsCommandNetworkRelationStreamItemError>> {
+    fn network_relation_stream_from_opinion_markets(middleware: HttpMiddleware, client: GammaClient, opinion_markets_by_question: OpinionMarketsByQuestion) -> impl Stream<Item = Result<MarketRelation, ListRelatedMarketsCommandNetworkRelationStreamItemError>> {
         stream! {
             use ListRelatedMarketsCommandNetworkRelationStreamItemError::*;
-            let client = GammaClient::default();
             let mut offset: i32 = 0;
             loop {
                 let request = MarketsRequest::builder()
@@ -149,7 +158,7 @@ impl ListRelatedMarketsCommand {
                     .order("id".to_string())
                     .ascending(true)
                     .build();
-                let markets_result = client.markets(&request).await;
+                let markets_result = middleware.call(HttpEndpoint::Gamma, || client.markets(&request)).await;
                 let markets = match markets_result {
                     Ok(markets) => markets,
                     Err(source) => {
@@ -209,7 +218,7 @@ impl ListRelatedMarketsCommand {
         }
     }
 
-    async fn fetch_opinion_markets_by_question() -> Result<OpinionMarketsByQuestion, ListRelatedMarketsCommandFetchOpinionMarketsByQuestionError> {
+    async fn fetch_opinion_markets_by_question(middleware: &HttpMiddleware, opinion_host: &str) -> Result<OpinionMarketsByQuestion, ListRelatedMarketsCommandFetchOpinionMarketsByQuestionError> {
         use ListRelatedMarketsCommandFetchOpinionMarketsByQuestionError::*;
         let api_key = handle!(var(OPINION_API_KEY_ENV), MissingApiKeyFailed);
         let client = handle!(
@@ -221,17 +230,20 @@ impl ListRelatedMarketsCommand {
         let mut page: u32 = 1;
         let mut output = OpinionMarketsByQuestion::default();
         loop {
-            let request_url = handle!(Self::opinion_market_page_url(page), OpinionMarketPageUrlFailed, page);
+            let request_url = handle!(Self::opinion_market_page_url(opinion_host, page), OpinionMarketPageUrlFailed, page);
             let response = handle!(
-                client
-                    .get(request_url)
-                    .header("x-api-key", api_key.as_str())
-                    .send()
+                middleware
+                    .call(HttpEndpoint::Opinion, || {
+                        send_checked(
+                            client
+                                .get(request_url.clone())
+                                .header("x-api-key", api_key.as_str()),
+                        )
+                    })
                     .await,
                 SendFailed,
                 page
             );
-            let response = handle!(response.error_for_status(), ErrorForStatusFailed, page);
             let page_payload = handle!(response.json::<OpinionMarketPage>().await, JsonFailed, page);
             let OpinionMarketPage {
                 data,
@@ -256,9 +268,10 @@ impl ListRelatedMarketsCommand {
         Ok(output)
     }
 
-    fn opinion_market_page_url(page: u32) -> Result<Url, ListRelatedMarketsCommandOpinionMarketPageUrlError> {
+    fn opinion_market_page_url(opinion_host: &str, page: u32) -> Result<Url, ListRelatedMarketsCommandOpinionMarketPageUrlError> {
         use ListRelatedMarketsCommandOpinionMarketPageUrlError::*;
-        let mut url = handle!(Url::parse(OPINION_MARKETS_URL), ParseFailed);
+        let markets_url = format!("{}{OPINION_MARKETS_PATH}", opinion_host.trim_end_matches('/'));
+        let mut url = handle!(Url::parse(&markets_url), ParseFailed, markets_url);
         let per_page_string = OPINION_PER_PAGE.to_string();
         let page_string = page.to_string();
         {
@@ -384,6 +397,8 @@ pub enum ListRelatedMarketsCommandWriteRelationToCacheLineError {
 
 #[derive(Error, Debug)]
 pub enum ListRelatedMarketsCommandNetworkRelationStreamError {
+    #[error("failed to create Gamma client")]
+    GammaClientFailed { source: ApiHostArgsGammaClientError },
     #[error("failed to fetch opinion markets")]
     FetchOpinionMarketsByQuestionFailed { source: Box<ListRelatedMarketsCommandFetchOpinionMarketsByQuestionError> },
 }
@@ -391,7 +406,7 @@ pub enum ListRelatedMarketsCommandNetworkRelationStreamError {
 #[derive(Error, Debug)]
 pub enum ListRelatedMarketsCommandNetworkRelationStreamItemError {
     #[error("failed to fetch polymarket markets for request '{request:?}'")]
-    MarketsFailed { source: PolymarketError, request: Box<MarketsRequest> },
+    MarketsFailed { source: HttpMiddlewareCallError<PolymarketError>, request: Box<MarketsRequest> },
     #[error("failed to convert market count '{market_count}' to i32")]
     TryFromMarketCountFailed { source: TryFromIntError, market_count: usize },
     #[error("failed to increment polymarket offset {offset} by {market_count_i32}")]
@@ -407,9 +422,7 @@ pub enum ListRelatedMarketsCommandFetchOpinionMarketsByQuestionError {
     #[error("failed to build opinion market URL for page {page}")]
     OpinionMarketPageUrlFailed { source: ListRelatedMarketsCommandOpinionMarketPageUrlError, page: u32 },
     #[error("failed to fetch opinion market page {page}")]
-    SendFailed { source: reqwest::Error, page: u32 },
-    #[error("opinion market page {page} returned non-success status")]
-    ErrorForStatusFailed { source: reqwest::Error, page: u32 },
+    SendFailed { source: HttpMiddlewareCallError<SendCheckedError>, page: u32 },
     #[error("failed to parse opinion market page {page}")]
     JsonFailed { source: reqwest::Error, page: u32 },
     #[error("failed to increment opinion page {page}")]
@@ -418,8 +431,8 @@ pub enum ListRelatedMarketsCommandFetchOpinionMarketsByQuestionError {
 
 #[derive(Error, Debug)]
 pub enum ListRelatedMarketsCommandOpinionMarketPageUrlError {
-    #[error("failed to parse opinion markets URL")]
-    ParseFailed { source: UrlParseError },
+    #[error("failed to parse opinion markets URL '{markets_url}'")]
+    ParseFailed { source: UrlParseError, markets_url: String },
 }
 
 #[derive(Error, Debug)]
@@ -1,3 +1,5 @@
+use core::num::NonZeroUsize;
+use core::time::Duration;
 use std::sync::LazyLock;
 
 pub const YES: &str = "Yes";
@@ -15,13 +17,73 @@ pub const GAMMA_EVENTS_KEYSPACE: &str = "GammaEvent";
 pub const CLOB_MARKET_RESPONSES_KEYSPACE: &str = "ClobMarketResponsePrecise";
 pub const CLOB_ORDER_BOOK_SUMMARY_RESPONSE_KEYSPACE: &str = "OrderBookSummaryResponsePrecise";
 
+/// The keyspace for the history of [`OrderBookSummaryResponsePrecise`](crate::OrderBookSummaryResponsePrecise) (keyed by [`OrderBookSnapshotKey`](crate::OrderBookSnapshotKey))
+pub const CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE: &str = "OrderBookSnapshot";
+
+/// The keyspace for [`DownloadCheckpoint`](crate::DownloadCheckpoint) (keyed by [`DownloadEndpoint`](crate::DownloadEndpoint))
+pub const DOWNLOAD_CHECKPOINTS_KEYSPACE: &str = "DownloadCheckpoint";
+
+/// The defaults of [`ApiHostArgs`](crate::ApiHostArgs)
+pub const DEFAULT_CLOB_HOST: &str = "https://clob.polymarket.com";
+pub const DEFAULT_GAMMA_HOST: &str = "https://gamma-api.polymarket.com";
+pub const DEFAULT_OPINION_HOST: &str = "https://api.opinion.trade";
+
+/// The CLOB WebSocket endpoint for the market channel (level 2 price data)
+pub const CLOB_MARKET_CHANNEL_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
+
+/// The server closes the WebSocket connection if the client doesn't send a `PING` message every 10 seconds
+pub const CLOB_WEBSOCKET_PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Separates the key from the value in the output of the list commands (see [`OutputKind::KeyValue`](crate::OutputKind::KeyValue))
+pub const KEY_VALUE_SEPARATOR: &str = ": ";
+
 /// The keyspace for [`Market`](crate::ClobMarket)
 pub const CLOB_MARKETS_KEYSPACE: &str = "ClobMarket";
 
+/// The secondary indexes of [`CLOB_MARKET_RESPONSES_KEYSPACE`] (see [`MarketIndexes`](crate::MarketIndexes))
+pub const CLOB_MARKET_SLUGS_BY_CONDITION_ID_KEYSPACE: &str = "ClobMarketSlugByConditionId";
+pub const CLOB_MARKET_SLUGS_BY_QUESTION_ID_KEYSPACE: &str = "ClobMarketSlugByQuestionId";
+pub const CLOB_MARKET_SLUGS_BY_EVENT_ID_KEYSPACE: &str = "ClobMarketSlugByEventId";
+pub const CLOB_MARKET_SLUGS_BY_TAG_KEYSPACE: &str = "ClobMarketSlugByTag";
+pub const CLOB_MARKET_SLUGS_BY_END_DATE_KEYSPACE: &str = "ClobMarketSlugByEndDate";
+
 // /// The keyspace for [`OrderBook`](crate::OrderBook)
 // pub const CLOB_ORDER_BOOKS_KEYSPACE: &str = "clob_order_books";
 
 /// Important: some markets have non-boolean outcomes (for example: ["Western Carolina vs. UNC Greensboro"](https://gamma-api.polymarket.com/markets/522329))
 pub static BOOLEAN_OUTCOMES: LazyLock<Vec<String>> = LazyLock::new(|| vec!["Yes".to_string(), "No".to_string()]);
 
+/// The number of token ids in a single order books request (see [`ClobClient::order_books`](crate::ClobClient::order_books))
+pub const CLOB_ORDER_BOOKS_CHUNK_SIZE: NonZeroUsize = NonZeroUsize::new(500).unwrap();
+
+/// The number of order books requests that are in flight at the same time
+pub const CLOB_ORDER_BOOKS_CONCURRENCY: NonZeroUsize = NonZeroUsize::new(4).unwrap();
+
+/// The number of entries that `cache check` reads before evaluating the properties in parallel
+pub const CACHE_CHECK_CHUNK_SIZE: usize = 4096;
+
+/// The number of entries that `cache reindex` writes in a single transaction
+pub const CACHE_REINDEX_CHUNK_SIZE: usize = 4096;
+
+/// The defaults of [`HttpMiddleware`](crate::HttpMiddleware) (see [`HttpMiddlewareArgs`](crate::HttpMiddlewareArgs))
+pub const DEFAULT_HTTP_MAX_RETRIES: u32 = 8;
+pub const DEFAULT_HTTP_INITIAL_BACKOFF_MILLIS: u64 = 500;
+pub const DEFAULT_HTTP_MAX_BACKOFF_MILLIS: u64 = 60_000;
+pub const DEFAULT_HTTP_RETRY_BUDGET: u32 = 1_000;
+pub const DEFAULT_HTTP_BURST: u32 = 5;
+pub const DEFAULT_CLOB_REQUESTS_PER_SECOND: u32 = 10;
+pub const DEFAULT_GAMMA_REQUESTS_PER_SECOND: u32 = 10;
+pub const DEFAULT_OPINION_REQUESTS_PER_SECOND: u32 = 2;
+
 pub const TIMESTAMP_2023_01_01_00_00_00_Z: i64 = 1672531200;
+
+/// The EIP-712 domain name and version of the CTF exchange contracts that verify the order signatures
+pub const CLOB_EXCHANGE_DOMAIN_NAME: &str = "Polymarket CTF Exchange";
+pub const CLOB_EXCHANGE_DOMAIN_VERSION: &str = "1";
+
+/// The CLOB amounts are denominated in the base units of USDC and of the conditional tokens (both have 6 decimals)
+pub const CLOB_AMOUNT_DECIMALS: u32 = 6;
+
+/// The remote signer is on the order path, so a hung service must not block the command indefinitely
+pub const REMOTE_SIGNER_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+pub const REMOTE_SIGNER_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
@@ -12,3 +12,17 @@ mod get_middle_diffs;
 pub use get_middle_diffs::*;
 mod format_debug_diff;
 pub use format_debug_diff::*;
+mod get_order_book_snapshots_in_range;
+pub use get_order_book_snapshots_in_range::*;
+mod get_order_book_snapshot_as_of;
+pub use get_order_book_snapshot_as_of::*;
+mod encode_cached_value;
+pub use encode_cached_value::*;
+mod encode_cached_value_line;
+pub use encode_cached_value_line::*;
+mod sortable_timestamp_bytes;
+pub use sortable_timestamp_bytes::*;
+mod random_u64;
+pub use random_u64::*;
+mod send_checked;
+pub use send_checked::*;
@@ -0,0 +1,30 @@
+use crate::TranscodeFormat;
+use errgonomic::handle;
+use rkyv::api::high::HighSerializer;
+use rkyv::rancor::Error as RkyvError;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use rkyv::{Serialize as RkyvSerialize, to_bytes};
+use serde::Serialize;
+use thiserror::Error;
+
+/// Encodes a cached value (read through a [`Repository`](crate::Repository)) in the output format
+pub fn encode_cached_value<T>(format: TranscodeFormat, value: &T) -> Result<Vec<u8>, EncodeCachedValueError>
+where
+    T: Serialize + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+{
+    use EncodeCachedValueError::*;
+    use TranscodeFormat::*;
+    match format {
+        Rkyv => Ok(handle!(to_bytes::<RkyvError>(value), ToBytesFailed).into_vec()),
+        SerdeJson => Ok(handle!(serde_json::to_vec(value), ToVecFailed)),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum EncodeCachedValueError {
+    #[error("failed to serialize cached value to rkyv")]
+    ToBytesFailed { source: RkyvError },
+    #[error("failed to serialize cached value to serde_json")]
+    ToVecFailed { source: serde_json::Error },
+}
@@ -0,0 +1,31 @@
+use crate::{EncodeCachedValueError, TranscodeFormat, encode_cached_value};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use errgonomic::handle;
+use rkyv::Serialize as RkyvSerialize;
+use rkyv::api::high::HighSerializer;
+use rkyv::rancor::Error as RkyvError;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Same as [`encode_cached_value`], but the rkyv bytes are base64-encoded, so that the value can be printed as a single line of text
+pub fn encode_cached_value_line<T>(format: TranscodeFormat, value: &T) -> Result<Vec<u8>, EncodeCachedValueLineError>
+where
+    T: Serialize + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+{
+    use EncodeCachedValueLineError::*;
+    use TranscodeFormat::*;
+    let value_bytes = handle!(encode_cached_value(format, value), EncodeCachedValueFailed);
+    match format {
+        Rkyv => Ok(STANDARD.encode(value_bytes).into_bytes()),
+        SerdeJson => Ok(value_bytes),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum EncodeCachedValueLineError {
+    #[error("failed to encode cached value")]
+    EncodeCachedValueFailed { source: EncodeCachedValueError },
+}
@@ -0,0 +1,12 @@
+use crate::{OrderBookSummaryResponsePrecise, Repository, RepositoryReadEntryError, TokenId, get_order_book_snapshots_in_range};
+use fjall::Snapshot;
+use time::OffsetDateTime;
+
+/// Returns the latest order book snapshot of the token with `updated_at <= as_of`
+///
+/// The `repository` must be opened on the [`CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE`](crate::CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE).
+pub fn get_order_book_snapshot_as_of(snapshot: &Snapshot, repository: &Repository<OrderBookSummaryResponsePrecise>, token_id: TokenId, as_of: OffsetDateTime) -> Result<Option<OrderBookSummaryResponsePrecise>, RepositoryReadEntryError> {
+    get_order_book_snapshots_in_range(snapshot, repository, token_id, ..=as_of)
+        .next_back()
+        .transpose()
+}
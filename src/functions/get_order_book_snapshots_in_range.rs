@@ -0,0 +1,151 @@
+use crate::{OrderBookSnapshotKey, OrderBookSummaryResponsePrecise, Repository, RepositoryReadEntryError, TokenId};
+use core::ops::{Bound, RangeBounds};
+use fjall::Snapshot;
+use time::OffsetDateTime;
+
+/// Returns the order book snapshots of the token with `updated_at` within the range (ordered by `updated_at`)
+///
+/// An inverted range (the start is after the end) returns no snapshots.
+///
+/// The `repository` must be opened on the [`CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE`](crate::CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE).
+pub fn get_order_book_snapshots_in_range<'a>(snapshot: &'a Snapshot, repository: &'a Repository<OrderBookSummaryResponsePrecise>, token_id: TokenId, range: impl RangeBounds<OffsetDateTime>) -> impl DoubleEndedIterator<Item = Result<OrderBookSummaryResponsePrecise, RepositoryReadEntryError>> + 'a {
+    let prefix = OrderBookSnapshotKey::token_id_prefix(token_id);
+    let key = |updated_at: &OffsetDateTime| {
+        OrderBookSnapshotKey::new(token_id, *updated_at)
+            .to_bytes()
+            .to_vec()
+    };
+    let start = match range.start_bound() {
+        Bound::Included(updated_at) => Bound::Included(key(updated_at)),
+        Bound::Excluded(updated_at) => Bound::Excluded(key(updated_at)),
+        Bound::Unbounded => Bound::Included(prefix.to_vec()),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(updated_at) => Bound::Included(key(updated_at)),
+        Bound::Excluded(updated_at) => Bound::Excluded(key(updated_at)),
+        Bound::Unbounded => Bound::Included(
+            [
+                prefix.as_slice(),
+                [u8::MAX; OrderBookSnapshotKey::UPDATED_AT_LEN].as_slice(),
+            ]
+            .concat(),
+        ),
+    };
+    // the keyspace range must not be inverted, so an inverted range is replaced by an empty range
+    let (start, end) = if is_inverted(&start, &end) {
+        (Bound::Included(prefix.to_vec()), Bound::Excluded(prefix.to_vec()))
+    } else {
+        (start, end)
+    };
+    repository
+        .range(snapshot, (start, end))
+        .map(|entry| entry.map(|(_key, order_book)| order_book))
+}
+
+fn is_inverted(start: &Bound<Vec<u8>>, end: &Bound<Vec<u8>>) -> bool {
+    match (start, end) {
+        (Bound::Included(start), Bound::Included(end)) => start > end,
+        (Bound::Included(start) | Bound::Excluded(start), Bound::Included(end) | Bound::Excluded(end)) => start >= end,
+        (Bound::Unbounded, _) | (_, Bound::Unbounded) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE, FixtureOrderBookError, RepositoryInsertError, RepositoryOpenError, fixture_order_book, get_order_book_snapshot_as_of};
+    use errgonomic::handle;
+    use fjall::{Error as FjallError, SingleWriterTxDatabase};
+    use std::io;
+    use std::path::Path;
+    use thiserror::Error;
+    use time::macros::datetime;
+
+    const T1: OffsetDateTime = datetime!(2025-01-01 00:00 UTC);
+    const T2: OffsetDateTime = datetime!(2025-01-01 00:01 UTC);
+    const T3: OffsetDateTime = datetime!(2025-01-01 00:02 UTC);
+
+    #[test]
+    fn must_get_order_book_snapshots_in_range() -> Result<(), OrderBookSnapshotsTestError> {
+        use OrderBookSnapshotsTestError::*;
+        let dir = handle!(tempfile::tempdir(), TempdirFailed);
+        let (db, repository) = open_snapshots(dir.path())?;
+        let snapshot = db.read_tx();
+        let updated_ats = |range: (Bound<OffsetDateTime>, Bound<OffsetDateTime>)| {
+            get_order_book_snapshots_in_range(&snapshot, &repository, token_id(), range)
+                .map(|result| result.map(|order_book| order_book.updated_at))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        assert_eq!(handle!(updated_ats((Bound::Unbounded, Bound::Unbounded)), ReadFailed), vec![T1, T2, T3]);
+        assert_eq!(handle!(updated_ats((Bound::Included(T1), Bound::Included(T2))), ReadFailed), vec![T1, T2]);
+        assert_eq!(handle!(updated_ats((Bound::Excluded(T1), Bound::Excluded(T3))), ReadFailed), vec![T2]);
+        // an empty range
+        assert_eq!(handle!(updated_ats((Bound::Included(T2), Bound::Excluded(T2))), ReadFailed), vec![]);
+        // an inverted range
+        assert_eq!(handle!(updated_ats((Bound::Included(T3), Bound::Included(T1))), ReadFailed), vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn must_get_order_book_snapshot_as_of() -> Result<(), OrderBookSnapshotsTestError> {
+        use OrderBookSnapshotsTestError::*;
+        let dir = handle!(tempfile::tempdir(), TempdirFailed);
+        let (db, repository) = open_snapshots(dir.path())?;
+        let snapshot = db.read_tx();
+        let updated_at = |as_of: OffsetDateTime| get_order_book_snapshot_as_of(&snapshot, &repository, token_id(), as_of).map(|order_book| order_book.map(|order_book| order_book.updated_at));
+        // an exact timestamp
+        assert_eq!(handle!(updated_at(T2), ReadFailed), Some(T2));
+        // a time between the snapshots
+        assert_eq!(handle!(updated_at(datetime!(2025-01-01 00:01:30 UTC)), ReadFailed), Some(T2));
+        // a time before the first snapshot
+        assert_eq!(handle!(updated_at(datetime!(2024-12-31 23:59 UTC)), ReadFailed), None);
+        // a time after the last snapshot (the snapshot of the other token is not returned)
+        assert_eq!(handle!(updated_at(datetime!(2025-01-02 00:00 UTC)), ReadFailed), Some(T3));
+        Ok(())
+    }
+
+    fn token_id() -> TokenId {
+        TokenId::from(7u64)
+    }
+
+    /// Stores the snapshots of the token at `T1`, `T2` and `T3`, and a later snapshot of another token
+    fn open_snapshots(dir: &Path) -> Result<(SingleWriterTxDatabase, Repository<OrderBookSummaryResponsePrecise>), OrderBookSnapshotsTestError> {
+        use OrderBookSnapshotsTestError::*;
+        let order_book = handle!(fixture_order_book(), FixtureOrderBookFailed);
+        let db = handle!(SingleWriterTxDatabase::builder(dir).open(), OpenDatabaseFailed);
+        let repository = handle!(Repository::<OrderBookSummaryResponsePrecise>::open_named(&db, CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE), OpenRepositoryFailed);
+        let mut tx = db.write_tx();
+        for (token_id, updated_at) in [
+            (token_id(), T1),
+            (token_id(), T2),
+            (token_id(), T3),
+            (TokenId::from(8u64), datetime!(2025-01-01 12:00 UTC)),
+        ] {
+            let mut order_book = order_book.clone();
+            order_book.token_id = token_id;
+            order_book.updated_at = updated_at;
+            let key = OrderBookSnapshotKey::new(token_id, updated_at);
+            handle!(repository.insert_with_key(&mut tx, key.to_bytes().as_slice(), &order_book), InsertFailed);
+        }
+        handle!(tx.commit(), CommitFailed);
+        Ok((db, repository))
+    }
+
+    #[derive(Error, Debug)]
+    enum OrderBookSnapshotsTestError {
+        #[error("failed to load orderbook fixture")]
+        FixtureOrderBookFailed { source: FixtureOrderBookError },
+        #[error("failed to create temporary directory")]
+        TempdirFailed { source: io::Error },
+        #[error("failed to open database")]
+        OpenDatabaseFailed { source: FjallError },
+        #[error("failed to open repository")]
+        OpenRepositoryFailed { source: RepositoryOpenError },
+        #[error("failed to insert order book snapshot")]
+        InsertFailed { source: RepositoryInsertError },
+        #[error("failed to commit transaction")]
+        CommitFailed { source: FjallError },
+        #[error("failed to read order book snapshots")]
+        ReadFailed { source: RepositoryReadEntryError },
+    }
+}
@@ -0,0 +1,8 @@
+use std::hash::{BuildHasher, RandomState};
+
+/// Returns a random value that is good enough for jitter (not for cryptography)
+///
+/// Every [`RandomState`] is seeded with different keys, so the hash of an empty input is different on every call.
+pub fn random_u64() -> u64 {
+    RandomState::new().hash_one(())
+}
@@ -0,0 +1,36 @@
+use core::time::Duration;
+use errgonomic::handle;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use thiserror::Error;
+
+/// Sends the request and returns an error for a non-success status
+///
+/// Unlike [`Response::error_for_status`], the error keeps the `Retry-After` header (only the delay in seconds is supported, not the HTTP date).
+pub async fn send_checked(request: RequestBuilder) -> Result<Response, SendCheckedError> {
+    use SendCheckedError::*;
+    let response = handle!(request.send().await, SendFailed);
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+    Err(StatusFailed {
+        status,
+        retry_after,
+        url: response.url().to_string(),
+    })
+}
+
+#[derive(Error, Debug)]
+pub enum SendCheckedError {
+    #[error("failed to send request")]
+    SendFailed { source: reqwest::Error },
+    #[error("request to '{url}' failed with status '{status}'")]
+    StatusFailed { status: StatusCode, retry_after: Option<Duration>, url: String },
+}
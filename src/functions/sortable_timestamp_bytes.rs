@@ -0,0 +1,14 @@
+use time::OffsetDateTime;
+
+const SIGN_BIT: u128 = 1 << 127;
+
+/// Returns the unix nanoseconds of the timestamp (16 bytes, big-endian, sign bit flipped), so the lexicographic order of the bytes matches the order of the timestamps
+pub fn sortable_timestamp_bytes(timestamp: OffsetDateTime) -> [u8; 16] {
+    let nanos = u128::from_be_bytes(timestamp.unix_timestamp_nanos().to_be_bytes());
+    (nanos ^ SIGN_BIT).to_be_bytes()
+}
+
+/// Returns the unix nanoseconds that were encoded by [`sortable_timestamp_bytes`]
+pub fn sortable_timestamp_nanos(bytes: [u8; 16]) -> i128 {
+    i128::from_be_bytes((u128::from_be_bytes(bytes) ^ SIGN_BIT).to_be_bytes())
+}
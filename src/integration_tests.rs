@@ -1,2 +1,7 @@
+mod cache_download;
+mod cache_gamma_events_monitor;
+mod cache_keyspace;
+mod clob_post_signed_order;
 mod get_markets;
 mod get_orderbooks;
+mod markets_stream;
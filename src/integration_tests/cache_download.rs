@@ -0,0 +1,110 @@
+use crate::{CacheDownloadCommand, CassetteServer, ClobMarketResponsePrecise, DEFAULT_CLOB_HOST, DEFAULT_GAMMA_HOST, DOWNLOAD_CHECKPOINTS_KEYSPACE, DownloadEndpoint, GammaEvent, MockHttpServer, Repository, open_keyspace, start_mock_clob_server, start_mock_gamma_server};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use clap::Parser;
+use fjall::SingleWriterTxDatabase;
+use std::path::Path;
+use std::process::ExitCode;
+use url::form_urlencoded;
+
+#[tokio::test]
+async fn test_cache_download() {
+    let clob = CassetteServer::start("cache_download_clob", DEFAULT_CLOB_HOST)
+        .await
+        .unwrap();
+    let gamma = CassetteServer::start("cache_download_gamma", DEFAULT_GAMMA_HOST)
+        .await
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let dir_string = dir.path().to_string_lossy().to_string();
+    let args = [
+        "download",
+        "--dir",
+        &dir_string,
+        "--page-limit",
+        "2",
+        "--clob-host",
+        clob.url(),
+        "--gamma-host",
+        gamma.url(),
+    ];
+    let command = CacheDownloadCommand::try_parse_from(args).unwrap();
+    let exit_code = command.run().await.unwrap();
+    clob.finish().unwrap();
+    gamma.finish().unwrap();
+    assert_eq!(exit_code, ExitCode::SUCCESS);
+    let db = SingleWriterTxDatabase::builder(dir.path()).open().unwrap();
+    let snapshot = db.read_tx();
+    let market_repository = Repository::<ClobMarketResponsePrecise>::open(&db).unwrap();
+    let markets = market_repository
+        .iter(&snapshot)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert!(!markets.is_empty());
+    let event_repository = Repository::<GammaEvent>::open(&db).unwrap();
+    let events = event_repository
+        .iter(&snapshot)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert!(!events.is_empty());
+}
+
+#[tokio::test]
+async fn test_cache_download_resume() {
+    let clob = start_mock_clob_server().await.unwrap();
+    let gamma = start_mock_gamma_server().await.unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let download = |offset: Option<&str>| {
+        let dir_string = dir.path().to_string_lossy().to_string();
+        let mut args = vec![
+            "download".to_string(),
+            "--dir".to_string(),
+            dir_string,
+            "--page-limit".to_string(),
+            "1".to_string(),
+            "--clob-host".to_string(),
+            clob.url.clone(),
+            "--gamma-host".to_string(),
+            gamma.url.clone(),
+        ];
+        if let Some(offset) = offset {
+            args.extend(["--offset".to_string(), offset.to_string()]);
+        }
+        CacheDownloadCommand::try_parse_from(args).unwrap().run()
+    };
+    // the first run starts from the beginning
+    download(None).await.unwrap();
+    assert_eq!(last_requested_cursor(&clob), Some("MA==".to_string()));
+    // the second run resumes from the checkpoint
+    download(None).await.unwrap();
+    assert_eq!(last_requested_cursor(&clob), Some("MTAwMA==".to_string()));
+    // a database without a checkpoint resumes after the stored markets
+    let stored_count = remove_markets_checkpoint(dir.path());
+    download(None).await.unwrap();
+    assert_eq!(last_requested_cursor(&clob), Some(STANDARD.encode(stored_count.to_string())));
+    // the offset overrides the checkpoint
+    download(Some("5")).await.unwrap();
+    assert_eq!(last_requested_cursor(&clob), Some(STANDARD.encode("5")));
+}
+
+fn last_requested_cursor(server: &MockHttpServer) -> Option<String> {
+    server.requests().into_iter().rev().find_map(|target| {
+        let (_path, query) = target.split_once('?')?;
+        form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _value)| key == "next_cursor")
+            .map(|(_key, value)| value.into_owned())
+    })
+}
+
+/// Returns the number of stored markets
+fn remove_markets_checkpoint(dir: &Path) -> usize {
+    let db = SingleWriterTxDatabase::builder(dir).open().unwrap();
+    let checkpoint_keyspace = open_keyspace(&db, DOWNLOAD_CHECKPOINTS_KEYSPACE).unwrap();
+    let mut tx = db.write_tx();
+    tx.remove(&checkpoint_keyspace, DownloadEndpoint::ClobMarkets.key());
+    tx.commit().unwrap();
+    let repository = Repository::<ClobMarketResponsePrecise>::open(&db).unwrap();
+    let stored_count = repository.count(&db.read_tx()).unwrap();
+    assert!(stored_count > 0);
+    stored_count
+}
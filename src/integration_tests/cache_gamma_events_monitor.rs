@@ -0,0 +1,39 @@
+use crate::{CacheGammaEventsMonitorTimeSpreadOpportunitiesCommand, CassetteServer, DEFAULT_GAMMA_HOST, GammaEvent, Repository};
+use clap::Parser;
+use fjall::{PersistMode, SingleWriterTxDatabase};
+use polymarket_client_sdk::gamma::types::response::Event as GammaEventRaw;
+use std::process::ExitCode;
+
+#[tokio::test]
+async fn test_cache_gamma_events_monitor() {
+    let gamma = CassetteServer::start("cache_gamma_events_monitor_gamma", DEFAULT_GAMMA_HOST)
+        .await
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let event_raw = serde_json::from_str::<GammaEventRaw>(include_str!("../../fixtures/gamma_event/another-us-strike-on-venezuela-by.json")).unwrap();
+    let event = GammaEvent::try_from(event_raw).unwrap();
+    assert_eq!(event.is_date_cascade, Some(true), "the monitor refreshes only the date cascades");
+    {
+        let db = SingleWriterTxDatabase::builder(dir.path()).open().unwrap();
+        let repository = Repository::<GammaEvent>::open(&db).unwrap();
+        let mut tx = db.write_tx();
+        repository.insert(&mut tx, &event).unwrap();
+        tx.commit().unwrap();
+        db.persist(PersistMode::Buffer).unwrap();
+    }
+    let dir_string = dir.path().to_string_lossy().to_string();
+    let args = [
+        "monitor",
+        "--dir",
+        &dir_string,
+        "--max-iterations",
+        "1",
+        "--gamma-host",
+        gamma.url(),
+    ];
+    let command = CacheGammaEventsMonitorTimeSpreadOpportunitiesCommand::try_parse_from(args).unwrap();
+    let exit_code = command.run().await.unwrap();
+    assert_eq!(exit_code, ExitCode::SUCCESS);
+    assert_eq!(gamma.server.request_count(), 1);
+    gamma.finish().unwrap();
+}
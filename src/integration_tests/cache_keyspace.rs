@@ -0,0 +1,58 @@
+use crate::{CacheKeyspaceGetCommand, CacheKeyspaceListCommand, GammaEvent, KEY_VALUE_SEPARATOR, Repository};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use clap::Parser;
+use fjall::SingleWriterTxDatabase;
+use rkyv::rancor::Error as RkyvError;
+use rkyv::{from_bytes, to_bytes};
+
+fn event(id: u64, slug: &str) -> GammaEvent {
+    GammaEvent {
+        id,
+        slug: slug.to_string(),
+        markets: vec![],
+        is_date_cascade: None,
+    }
+}
+
+#[test]
+fn test_cache_keyspace_list_and_get() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir_string = dir.path().to_string_lossy().to_string();
+    let events = [event(1, "will-it-rain"), event(2, "will-it-snow")];
+    {
+        let db = SingleWriterTxDatabase::builder(dir.path()).open().unwrap();
+        let repository = Repository::<GammaEvent>::open(&db).unwrap();
+        let mut tx = db.write_tx();
+        for event in &events {
+            repository.insert(&mut tx, event).unwrap();
+        }
+        tx.commit().unwrap();
+    }
+
+    let command = CacheKeyspaceListCommand::try_parse_from(["list", "--dir", &dir_string, "--offset", "1"]).unwrap();
+    let mut output = Vec::new();
+    command.write::<GammaEvent>(&mut output).unwrap();
+    let expected = format!("will-it-snow{KEY_VALUE_SEPARATOR}{}\n", serde_json::to_string(&events[1]).unwrap());
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+    // the rkyv bytes may contain newlines, so they are base64-encoded
+    let command = CacheKeyspaceGetCommand::try_parse_from([
+        "get",
+        "--dir",
+        &dir_string,
+        "--format",
+        "rkyv",
+        "will-it-rain",
+    ])
+    .unwrap();
+    let mut output = Vec::new();
+    command.write::<GammaEvent>(&mut output).unwrap();
+    let line = String::from_utf8(output).unwrap();
+    let bytes = STANDARD.decode(line.trim_end()).unwrap();
+    assert_eq!(from_bytes::<GammaEvent, RkyvError>(&bytes).unwrap(), events[0]);
+    assert_eq!(bytes, to_bytes::<RkyvError>(&events[0]).unwrap().into_vec());
+
+    let command = CacheKeyspaceGetCommand::try_parse_from(["get", "--dir", &dir_string, "will-it-hail"]).unwrap();
+    assert!(command.write::<GammaEvent>(&mut Vec::new()).is_err());
+}
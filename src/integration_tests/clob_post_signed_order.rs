@@ -0,0 +1,72 @@
+use crate::{ClobOfflineLimitOrder, ClobPostSignedOrderCommand, MockHttpServer, OrderType, Side, mock_clob_response};
+use alloy::primitives::{Address, U256, hex};
+use alloy::signers::Signer;
+use alloy::signers::local::PrivateKeySigner;
+use clap::Parser;
+use polymarket_client_sdk::POLYGON;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+#[tokio::test]
+async fn test_clob_post_signed_order() {
+    // the mock server keeps the posted orders, so that the test can check what has been sent
+    let posted_orders = Arc::new(Mutex::new(Vec::new()));
+    let posted_orders_for_server = posted_orders.clone();
+    let clob = MockHttpServer::start_with_handler(move |request| {
+        if request.path() == "/order" {
+            posted_orders_for_server
+                .lock()
+                .unwrap()
+                .push(request.body.clone());
+        }
+        mock_clob_response(request)
+    })
+    .await
+    .unwrap();
+    let signer = "0x0123456789012345678901234567890123456789012345678901234567890123"
+        .parse::<PrivateKeySigner>()
+        .unwrap();
+    let order = ClobOfflineLimitOrder {
+        token_id: U256::from(7),
+        side: Side::Buy,
+        price: Decimal::new(5, 1),
+        size: Decimal::TEN,
+        tick_size: Decimal::new(1, 2),
+        neg_risk: false,
+        chain_id: POLYGON,
+        maker: signer.address(),
+        taker: Address::ZERO,
+        signature_type: 0,
+        nonce: 0,
+        expiration: 0,
+        fee_rate_bps: 0,
+        order_type: OrderType::Gtc,
+        post_only: false,
+    };
+    let signed_order = order.sign(&signer).await.unwrap();
+    let address = signer.address().to_string();
+    let args = [
+        "post-signed-order",
+        "--clob-host",
+        &clob.url,
+        "--address",
+        &address,
+        "--api-key",
+        "00000000-0000-0000-0000-000000000001",
+        "--api-secret",
+        "c2VjcmV0",
+        "--api-passphrase",
+        "passphrase",
+    ];
+    let command = ClobPostSignedOrderCommand::try_parse_from(args).unwrap();
+    let signature = hex::encode_prefixed(signed_order.signature.as_bytes());
+    let response = command.post(signed_order).await.unwrap();
+    assert!(response.success);
+    let posted_orders = posted_orders.lock().unwrap().clone();
+    assert_eq!(posted_orders.len(), 1);
+    let posted_order = serde_json::from_str::<Value>(&posted_orders[0]).unwrap();
+    assert_eq!(posted_order["order"]["signature"], signature);
+    assert_eq!(posted_order["owner"], "00000000-0000-0000-0000-000000000001");
+    assert_eq!(posted_order["orderType"], "GTC");
+}
@@ -1,22 +1,20 @@
-use crate::{ClobClient, ClobClientMarketsError, ClobMarketResponsePrecise, NEXT_CURSOR_START};
+use crate::{ApiHostArgs, ClobClientMarketsError, ClobMarketResponsePrecise, HttpMiddleware, start_mock_clob_server};
 use futures::StreamExt;
 use std::env;
 use std::fs::File;
 use std::io::Write;
-use std::str::FromStr;
 use tokio::pin;
 
-pub const MAX_PAGES_DEFAULT: &str = "4";
-
 #[tokio::test]
 async fn test_markets() {
-    let next_cursor = env::var("NEXT_CURSOR").unwrap_or(NEXT_CURSOR_START.into());
-    let max_pages_string = env::var("MAX_PAGES").unwrap_or(MAX_PAGES_DEFAULT.into());
     let markets_filename = env::var("MARKETS_FILENAME").ok();
-    let max_pages = usize::from_str(&max_pages_string).unwrap();
-    let client = ClobClient::default();
-    let markets_stream = client.get_markets_stream_at_cursor(next_cursor);
-    let markets_stream = markets_stream.take(max_pages);
+    let clob = start_mock_clob_server().await.unwrap();
+    let hosts = ApiHostArgs {
+        clob_host: clob.url.clone(),
+        ..ApiHostArgs::default()
+    };
+    let client = hosts.clob_client(HttpMiddleware::default()).unwrap();
+    let markets_stream = client.markets_stream();
     pin!(markets_stream);
     let markets_results = markets_stream
         .collect::<Vec<Result<Vec<ClobMarketResponsePrecise>, ClobClientMarketsError>>>()
@@ -25,6 +23,8 @@ async fn test_markets() {
         .into_iter()
         .collect::<Result<Vec<Vec<ClobMarketResponsePrecise>>, ClobClientMarketsError>>()
         .unwrap();
+    // the mock server serves two pages
+    assert_eq!(markets_pages.len(), 2);
     let markets = markets_pages
         .into_iter()
         .flatten()
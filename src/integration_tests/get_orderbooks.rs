@@ -1,13 +1,18 @@
-use crate::{ClobClient, ClobMarketResponsePrecise};
+use crate::{ApiHostArgs, ClobMarketResponsePrecise, HttpMiddleware, TokenId, start_mock_clob_server};
 use futures::{StreamExt, TryStreamExt};
 use std::future::ready;
 use tokio::pin;
 
 // TODO: Migrate to GammaClient + get markets that are guaranteed to be active
-// TODO: This test is slow because finding a market with `enable_order_book = true` takes a lot of time (paging through markets)
 #[tokio::test]
 async fn test_orderbooks() {
-    let client = ClobClient::default();
+    // the first page of the mock server has no markets with `enable_order_book = true`, so the test pages through the markets
+    let clob = start_mock_clob_server().await.unwrap();
+    let hosts = ApiHostArgs {
+        clob_host: clob.url.clone(),
+        ..ApiHostArgs::default()
+    };
+    let client = hosts.clob_client(HttpMiddleware::default()).unwrap();
     let markets_stream_raw = client.markets_stream();
     let markets_stream_filtered = markets_stream_raw.try_filter_map(|markets| {
         let markets = markets
@@ -24,11 +29,17 @@ async fn test_orderbooks() {
         .expect("must return some markets with enable_order_book = true")
         .unwrap();
     assert!(!markets.is_empty(), "expecting markets.len() > 0, otherwise we're not really testing the orderbooks");
-    // TODO:
-    // let token_ids = markets
-    //     .iter()
-    //     .flat_map(|market| market.tokens.token_ids_array())
-    //     .collect::<Vec<TokenId>>();
-    // let orderbooks = client.orderbooks(&token_ids).await;
-    // assert_eq!(orderbooks.len(), markets.len() * 2);
+    let token_ids = markets
+        .iter()
+        .flat_map(|market| market.tokens.token_ids_array())
+        .collect::<Vec<TokenId>>();
+    let batch = client.order_books(&token_ids).await;
+    let failures = batch
+        .failures
+        .iter()
+        .filter(|failure| !failure.is_order_book_not_found())
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "expecting no failures, got {failures:#?}");
+    assert!(!batch.order_books.is_empty());
+    assert!(batch.order_books.len() <= token_ids.len());
 }
@@ -0,0 +1,23 @@
+use crate::{ApiHostArgs, CassetteServer, DEFAULT_CLOB_HOST, HttpMiddleware};
+use futures::{StreamExt, TryStreamExt};
+
+#[tokio::test]
+async fn test_markets_stream() {
+    let cassette = CassetteServer::start("clob_markets_stream", DEFAULT_CLOB_HOST)
+        .await
+        .unwrap();
+    let hosts = ApiHostArgs {
+        clob_host: cassette.url().to_string(),
+        ..ApiHostArgs::default()
+    };
+    let client = hosts.clob_client(HttpMiddleware::default()).unwrap();
+    let pages = client
+        .markets_stream()
+        .take(2)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    cassette.finish().unwrap();
+    assert_eq!(pages.len(), 2);
+    assert!(pages.iter().all(|page| !page.is_empty()));
+}
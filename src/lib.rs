@@ -29,6 +29,18 @@ mod test_helpers;
 #[cfg(test)]
 pub use test_helpers::*;
 
+#[cfg(test)]
+mod mock_polymarket_server;
+
+#[cfg(test)]
+pub use mock_polymarket_server::*;
+
+#[cfg(test)]
+mod cassette;
+
+#[cfg(test)]
+pub use cassette::*;
+
 mod traits;
 
 pub use traits::*;
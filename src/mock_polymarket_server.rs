@@ -0,0 +1,95 @@
+//! Local stand-ins for the Polymarket APIs that serve the recorded responses from `fixtures/`
+
+use crate::{MockHttpRequest, MockHttpResponse, MockHttpServer, MockHttpServerStartError, NEXT_CURSOR_STOP};
+use serde_json::Value;
+
+/// The `next_cursor` of `fixtures/markets_response.json`
+pub const MOCK_CLOB_MARKETS_LAST_PAGE_CURSOR: &str = "MTAwMA==";
+
+pub const MOCK_CLOB_MARKETS_FIRST_PAGE: &str = include_str!("../fixtures/markets_response.json");
+
+pub const MOCK_CLOB_MARKET: &str = include_str!("../fixtures/market.json");
+
+pub const MOCK_CLOB_ORDER_BOOKS: [&str; 2] = [
+    include_str!("../fixtures/orderbook.json"),
+    include_str!("../fixtures/orderbook_response.json"),
+];
+
+pub const MOCK_CLOB_POST_ORDER_RESPONSE: &str = r#"{"errorMsg":"","orderID":"0x0000000000000000000000000000000000000000000000000000000000000001","takingAmount":"0","makingAmount":"0","status":"live","transactionsHashes":[],"tradeIDs":[],"success":true}"#;
+
+pub const MOCK_CLOB_API_CREDENTIALS: &str = r#"{"apiKey":"00000000-0000-4000-8000-000000000001","secret":"c2VjcmV0c2VjcmV0c2VjcmV0c2VjcmV0c2VjcmV0","passphrase":"passphrase"}"#;
+
+pub const MOCK_GAMMA_EVENTS: [&str; 2] = [
+    include_str!("../fixtures/gamma_event/another-us-strike-on-venezuela-by.json"),
+    include_str!("../fixtures/gamma_event/nobel-peace-prize-winner-2026-139.json"),
+];
+
+/// Serves `GET /markets` (two pages: the recorded page and a page with the market from `fixtures/market.json`), `POST /books`, `POST /order` and the L1 authentication endpoints
+pub async fn start_mock_clob_server() -> Result<MockHttpServer, MockHttpServerStartError> {
+    MockHttpServer::start_with_handler(mock_clob_response).await
+}
+
+/// Serves `GET /events`, `GET /events/slug/{slug}` and `GET /markets` (the markets of the recorded events)
+pub async fn start_mock_gamma_server() -> Result<MockHttpServer, MockHttpServerStartError> {
+    MockHttpServer::start_with_handler(mock_gamma_response).await
+}
+
+pub fn mock_clob_response(request: &MockHttpRequest) -> MockHttpResponse {
+    match (request.method.as_str(), request.path()) {
+        ("GET", "/markets") => match request.query_param("next_cursor").as_deref() {
+            Some(MOCK_CLOB_MARKETS_LAST_PAGE_CURSOR) => MockHttpResponse::new(200, format!(r#"{{"data":[{}],"next_cursor":"{NEXT_CURSOR_STOP}","limit":1000,"count":1}}"#, MOCK_CLOB_MARKET.trim())),
+            _ => MockHttpResponse::new(200, MOCK_CLOB_MARKETS_FIRST_PAGE),
+        },
+        ("POST", "/books") => {
+            // the recorded order books are returned only for the requested tokens, so the other tokens are reported as not found
+            let order_books = MOCK_CLOB_ORDER_BOOKS
+                .iter()
+                .filter_map(|order_book| serde_json::from_str::<Value>(order_book).ok())
+                .filter(|order_book| {
+                    order_book
+                        .get("asset_id")
+                        .and_then(Value::as_str)
+                        .is_some_and(|asset_id| request.body.contains(asset_id))
+                })
+                .collect::<Vec<_>>();
+            MockHttpResponse::new(200, Value::Array(order_books).to_string())
+        }
+        // the API key is created (or derived) without checking the L1 headers
+        ("POST", "/auth/api-key") | ("GET", "/auth/derive-api-key") => MockHttpResponse::new(200, MOCK_CLOB_API_CREDENTIALS),
+        // every posted order is accepted and rests on the book
+        ("POST", "/order") => MockHttpResponse::new(200, MOCK_CLOB_POST_ORDER_RESPONSE),
+        _ => MockHttpResponse::not_found(),
+    }
+}
+
+pub fn mock_gamma_response(request: &MockHttpRequest) -> MockHttpResponse {
+    let events = MOCK_GAMMA_EVENTS
+        .iter()
+        .filter_map(|event| serde_json::from_str::<Value>(event).ok())
+        .collect::<Vec<_>>();
+    // every list endpoint returns a single page
+    let is_first_page = request
+        .query_param("offset")
+        .is_none_or(|offset| offset == "0");
+    match (request.method.as_str(), request.path()) {
+        ("GET", "/events") if is_first_page => MockHttpResponse::new(200, Value::Array(events).to_string()),
+        ("GET", "/markets") if is_first_page => {
+            let markets = events
+                .iter()
+                .filter_map(|event| event.get("markets").and_then(Value::as_array))
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>();
+            MockHttpResponse::new(200, Value::Array(markets).to_string())
+        }
+        ("GET", "/events" | "/markets") => MockHttpResponse::new(200, "[]"),
+        ("GET", path) => match path.strip_prefix("/events/slug/") {
+            Some(slug) => events
+                .into_iter()
+                .find(|event| event.get("slug").and_then(Value::as_str) == Some(slug))
+                .map_or_else(MockHttpResponse::not_found, |event| MockHttpResponse::new(200, event.to_string())),
+            None => MockHttpResponse::not_found(),
+        },
+        _ => MockHttpResponse::not_found(),
+    }
+}
@@ -1,4 +1,4 @@
-use crate::Property;
+use crate::{ClobMarket, GammaEvent, OrderBookSummaryResponsePrecise, Property};
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 
 pub type PropertyFactory<T> = fn() -> Box<dyn Property<T>>;
@@ -6,6 +6,15 @@ pub type PropertyFactory<T> = fn() -> Box<dyn Property<T>>;
 #[linkme::distributed_slice]
 pub static MARKET_RESPONSE_PROPERTIES: [PropertyFactory<MarketResponse>] = [..];
 
+#[linkme::distributed_slice]
+pub static CLOB_MARKET_PROPERTIES: [PropertyFactory<ClobMarket>] = [..];
+
+#[linkme::distributed_slice]
+pub static ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES: [PropertyFactory<OrderBookSummaryResponsePrecise>] = [..];
+
+#[linkme::distributed_slice]
+pub static GAMMA_EVENT_PROPERTIES: [PropertyFactory<GammaEvent>] = [..];
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! register_property {
@@ -60,3 +69,43 @@ pub use active_xor_closed::*;
 mod max_winner_token_count_is_one;
 
 pub use max_winner_token_count_is_one::*;
+
+mod clob_market_token_ids_are_distinct;
+
+pub use clob_market_token_ids_are_distinct::*;
+
+mod if_clob_market_is_accepting_orders_then_order_book_is_enabled;
+
+pub use if_clob_market_is_accepting_orders_then_order_book_is_enabled::*;
+
+mod order_book_is_not_crossed;
+
+pub use order_book_is_not_crossed::*;
+
+mod order_book_prices_are_tick_multiples;
+
+pub use order_book_prices_are_tick_multiples::*;
+
+mod gamma_event_has_markets;
+
+pub use gamma_event_has_markets::*;
+
+mod gamma_market_clob_token_ids_len_is_two;
+
+pub use gamma_market_clob_token_ids_len_is_two::*;
+
+mod tradeable_market_tokens_have_order_books;
+
+pub use tradeable_market_tokens_have_order_books::*;
+
+mod gamma_market_clob_token_ids_exist_in_clob_markets;
+
+pub use gamma_market_clob_token_ids_exist_in_clob_markets::*;
+
+mod neg_risk_event_markets_have_consistent_flags;
+
+pub use neg_risk_event_markets_have_consistent_flags::*;
+
+mod order_book_condition_id_matches_market;
+
+pub use order_book_condition_id_matches_market::*;
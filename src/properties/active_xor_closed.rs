@@ -1,13 +1,12 @@
-use crate::{MARKET_RESPONSE_PROPERTIES, Property};
-use fjall::Snapshot;
+use crate::{MARKET_RESPONSE_PROPERTIES, Property, PropertyContext, PropertyContextReadError};
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 
 #[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
 pub struct ActiveXorClosed;
 
 impl Property<MarketResponse> for ActiveXorClosed {
-    fn holds(&mut self, market_response: &MarketResponse, _snapshot: &Snapshot) -> bool {
-        market_response.active != market_response.closed
+    fn holds(&mut self, market_response: &MarketResponse, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(market_response.active != market_response.closed)
     }
 }
 
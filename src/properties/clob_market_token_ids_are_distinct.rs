@@ -0,0 +1,16 @@
+use crate::{CLOB_MARKET_PROPERTIES, ClobMarket, Property, PropertyContext, PropertyContextReadError};
+
+#[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
+pub struct ClobMarketTokenIdsAreDistinct;
+
+impl Property<ClobMarket> for ClobMarketTokenIdsAreDistinct {
+    fn is_invariant(&self) -> bool {
+        true
+    }
+
+    fn holds(&mut self, value: &ClobMarket, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(value.left_token_id != value.right_token_id)
+    }
+}
+
+register_property!(ClobMarketTokenIdsAreDistinct, ClobMarket, CLOB_MARKET_PROPERTIES);
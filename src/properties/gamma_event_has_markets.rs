@@ -0,0 +1,12 @@
+use crate::{GAMMA_EVENT_PROPERTIES, GammaEvent, Property, PropertyContext, PropertyContextReadError};
+
+#[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
+pub struct GammaEventHasMarkets;
+
+impl Property<GammaEvent> for GammaEventHasMarkets {
+    fn holds(&mut self, value: &GammaEvent, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(!value.markets.is_empty())
+    }
+}
+
+register_property!(GammaEventHasMarkets, GammaEvent, GAMMA_EVENT_PROPERTIES);
@@ -0,0 +1,39 @@
+use crate::{GAMMA_EVENT_PROPERTIES, GammaEvent, Property, PropertyContext, PropertyContextReadError, TokenId};
+use rustc_hash::FxHashSet;
+
+/// Holds if every CLOB token id of every market of the event belongs to some cached [`ClobMarket`](crate::ClobMarket)
+///
+/// The token ids of the cached markets are loaded on the first call (if some entry can't be read, the call returns an error and the next call retries the loading).
+#[derive(Default, Eq, PartialEq, Clone, Debug)]
+pub struct GammaMarketClobTokenIdsExistInClobMarkets {
+    token_ids: Option<FxHashSet<TokenId>>,
+}
+
+impl Property<GammaEvent> for GammaMarketClobTokenIdsExistInClobMarkets {
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn holds(&mut self, value: &GammaEvent, context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        let token_ids = match &mut self.token_ids {
+            Some(token_ids) => token_ids,
+            None => {
+                let mut token_ids = FxHashSet::default();
+                for market in context.clob_markets() {
+                    let market = market?;
+                    token_ids.extend([market.left_token_id, market.right_token_id]);
+                }
+                self.token_ids.insert(token_ids)
+            }
+        };
+        let holds = value
+            .markets
+            .iter()
+            .filter_map(|market| market.clob_token_ids.as_ref())
+            .flatten()
+            .all(|token_id| token_ids.contains(token_id));
+        Ok(holds)
+    }
+}
+
+register_property!(GammaMarketClobTokenIdsExistInClobMarkets, GammaEvent, GAMMA_EVENT_PROPERTIES);
@@ -0,0 +1,18 @@
+use crate::{GAMMA_EVENT_PROPERTIES, GammaEvent, Property, PropertyContext, PropertyContextReadError};
+
+/// Holds if every market of the event has either no CLOB token ids or exactly two of them
+#[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
+pub struct GammaMarketClobTokenIdsLenIsTwo;
+
+impl Property<GammaEvent> for GammaMarketClobTokenIdsLenIsTwo {
+    fn holds(&mut self, value: &GammaEvent, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(value.markets.iter().all(|market| {
+            market
+                .clob_token_ids
+                .as_ref()
+                .is_none_or(|token_ids| token_ids.len() == 2)
+        }))
+    }
+}
+
+register_property!(GammaMarketClobTokenIdsLenIsTwo, GammaEvent, GAMMA_EVENT_PROPERTIES);
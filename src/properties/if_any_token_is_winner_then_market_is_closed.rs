@@ -1,14 +1,13 @@
-use crate::{MARKET_RESPONSE_PROPERTIES, Property};
-use fjall::Snapshot;
+use crate::{MARKET_RESPONSE_PROPERTIES, Property, PropertyContext, PropertyContextReadError};
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 
 #[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
 pub struct IfAnyTokenIsWinnerThenMarketIsClosed;
 
 impl Property<MarketResponse> for IfAnyTokenIsWinnerThenMarketIsClosed {
-    fn holds(&mut self, value: &MarketResponse, _snapshot: &Snapshot) -> bool {
+    fn holds(&mut self, value: &MarketResponse, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
         let has_winner = value.tokens.iter().any(|token| token.winner);
-        !has_winner || value.closed
+        Ok(!has_winner || value.closed)
     }
 }
 
@@ -0,0 +1,12 @@
+use crate::{CLOB_MARKET_PROPERTIES, ClobMarket, Property, PropertyContext, PropertyContextReadError};
+
+#[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
+pub struct IfClobMarketIsAcceptingOrdersThenOrderBookIsEnabled;
+
+impl Property<ClobMarket> for IfClobMarketIsAcceptingOrdersThenOrderBookIsEnabled {
+    fn holds(&mut self, value: &ClobMarket, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(!value.accepting_orders || value.enable_order_book)
+    }
+}
+
+register_property!(IfClobMarketIsAcceptingOrdersThenOrderBookIsEnabled, ClobMarket, CLOB_MARKET_PROPERTIES);
@@ -1,6 +1,5 @@
-use crate::{MARKET_RESPONSE_PROPERTIES, Property};
+use crate::{MARKET_RESPONSE_PROPERTIES, Property, PropertyContext, PropertyContextReadError};
 use alloy::primitives::U256;
-use fjall::Snapshot;
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 use rust_decimal::Decimal;
 
@@ -8,15 +7,15 @@ use rust_decimal::Decimal;
 pub struct IfConditionIdIsNoneThenTokensArePlaceholders;
 
 impl Property<MarketResponse> for IfConditionIdIsNoneThenTokensArePlaceholders {
-    fn holds(&mut self, value: &MarketResponse, _snapshot: &Snapshot) -> bool {
-        if value.condition_id.is_none() {
+    fn holds(&mut self, value: &MarketResponse, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(if value.condition_id.is_none() {
             value
                 .tokens
                 .iter()
                 .all(|token| token.token_id == U256::ZERO && token.outcome.is_empty() && token.price == Decimal::ZERO && !token.winner)
         } else {
             true
-        }
+        })
     }
 }
 
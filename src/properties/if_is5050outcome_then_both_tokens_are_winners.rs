@@ -1,13 +1,12 @@
-use crate::{MARKET_RESPONSE_PROPERTIES, Property};
-use fjall::Snapshot;
+use crate::{MARKET_RESPONSE_PROPERTIES, Property, PropertyContext, PropertyContextReadError};
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 
 #[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
 pub struct IfIs5050OutcomeThenBothTokensAreWinners;
 
 impl Property<MarketResponse> for IfIs5050OutcomeThenBothTokensAreWinners {
-    fn holds(&mut self, value: &MarketResponse, _snapshot: &Snapshot) -> bool {
-        if value.is_50_50_outcome { value.tokens.iter().all(|token| token.winner) } else { true }
+    fn holds(&mut self, value: &MarketResponse, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(if value.is_50_50_outcome { value.tokens.iter().all(|token| token.winner) } else { true })
     }
 }
 
@@ -1,5 +1,4 @@
-use crate::{MARKET_RESPONSE_PROPERTIES, Property};
-use fjall::Snapshot;
+use crate::{MARKET_RESPONSE_PROPERTIES, Property, PropertyContext, PropertyContextReadError};
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 use rustc_hash::FxHashSet;
 
@@ -9,9 +8,17 @@ pub struct MarketSlugIsUnique {
 }
 
 impl Property<MarketResponse> for MarketSlugIsUnique {
-    fn holds(&mut self, market_response: &MarketResponse, _snapshot: &Snapshot) -> bool {
+    fn is_invariant(&self) -> bool {
+        true
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn holds(&mut self, market_response: &MarketResponse, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
         // returns true if the set didn't contain this value
-        self.slugs.insert(market_response.market_slug.clone())
+        Ok(self.slugs.insert(market_response.market_slug.clone()))
     }
 }
 
@@ -1,14 +1,13 @@
-use crate::{MARKET_RESPONSE_PROPERTIES, Property};
-use fjall::Snapshot;
+use crate::{MARKET_RESPONSE_PROPERTIES, Property, PropertyContext, PropertyContextReadError};
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 
 #[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
 pub struct MaxWinnerTokenCountIsOne;
 
 impl Property<MarketResponse> for MaxWinnerTokenCountIsOne {
-    fn holds(&mut self, value: &MarketResponse, _snapshot: &Snapshot) -> bool {
+    fn holds(&mut self, value: &MarketResponse, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
         let winners = value.tokens.iter().filter(|token| token.winner);
-        winners.count() <= 1
+        Ok(winners.count() <= 1)
     }
 }
 
@@ -0,0 +1,39 @@
+use crate::{CLOB_MARKET_PROPERTIES, ClobMarket, EventId, Property, PropertyContext, PropertyContextReadError};
+use rustc_hash::FxHashMap;
+
+/// Holds if the cached order books of the market have the same `neg_risk` flag as the market, and all markets that are grouped by the same [`NegRisk::event_id`](crate::NegRisk::event_id) have the same `neg_risk` flag in their order books
+#[derive(Default, Eq, PartialEq, Clone, Debug)]
+pub struct NegRiskEventMarketsHaveConsistentFlags {
+    event_flags: FxHashMap<EventId, bool>,
+}
+
+impl Property<ClobMarket> for NegRiskEventMarketsHaveConsistentFlags {
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn holds(&mut self, value: &ClobMarket, context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        let market_flag = value.neg_risk.is_some();
+        for token_id in [value.left_token_id, value.right_token_id] {
+            let Some(orderbook) = context.order_book(token_id)? else {
+                continue;
+            };
+            let is_consistent_with_event = match &value.neg_risk {
+                Some(neg_risk) => {
+                    *self
+                        .event_flags
+                        .entry(neg_risk.event_id)
+                        .or_insert(orderbook.neg_risk)
+                        == orderbook.neg_risk
+                }
+                None => true,
+            };
+            if orderbook.neg_risk != market_flag || !is_consistent_with_event {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+register_property!(NegRiskEventMarketsHaveConsistentFlags, ClobMarket, CLOB_MARKET_PROPERTIES);
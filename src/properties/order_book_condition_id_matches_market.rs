@@ -0,0 +1,36 @@
+use crate::{ConditionId, ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES, OrderBookSummaryResponsePrecise, Property, PropertyContext, PropertyContextReadError, TokenId};
+use rustc_hash::FxHashMap;
+
+/// Holds if the order book belongs to a cached [`ClobMarket`](crate::ClobMarket) with the same `condition_id`
+///
+/// The condition ids of the cached markets are loaded on the first call (if some entry can't be read, the call returns an error and the next call retries the loading).
+#[derive(Default, Eq, PartialEq, Clone, Debug)]
+pub struct OrderBookConditionIdMatchesMarket {
+    condition_ids: Option<FxHashMap<TokenId, ConditionId>>,
+}
+
+impl Property<OrderBookSummaryResponsePrecise> for OrderBookConditionIdMatchesMarket {
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn holds(&mut self, value: &OrderBookSummaryResponsePrecise, context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        let condition_ids = match &mut self.condition_ids {
+            Some(condition_ids) => condition_ids,
+            None => {
+                let mut condition_ids = FxHashMap::default();
+                for market in context.clob_markets() {
+                    let market = market?;
+                    condition_ids.extend([
+                        (market.left_token_id, market.condition_id),
+                        (market.right_token_id, market.condition_id),
+                    ]);
+                }
+                self.condition_ids.insert(condition_ids)
+            }
+        };
+        Ok(condition_ids.get(&value.token_id) == Some(&value.condition_id))
+    }
+}
+
+register_property!(OrderBookConditionIdMatchesMarket, OrderBookSummaryResponsePrecise, ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES);
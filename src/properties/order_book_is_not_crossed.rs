@@ -0,0 +1,12 @@
+use crate::{ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES, OrderBookSummaryResponsePrecise, Property, PropertyContext, PropertyContextReadError};
+
+#[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
+pub struct OrderBookIsNotCrossed;
+
+impl Property<OrderBookSummaryResponsePrecise> for OrderBookIsNotCrossed {
+    fn holds(&mut self, value: &OrderBookSummaryResponsePrecise, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(!value.is_crossed())
+    }
+}
+
+register_property!(OrderBookIsNotCrossed, OrderBookSummaryResponsePrecise, ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES);
@@ -0,0 +1,16 @@
+use crate::{ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES, OrderBookSummaryResponsePrecise, Property, PropertyContext, PropertyContextReadError};
+
+#[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
+pub struct OrderBookPricesAreTickMultiples;
+
+impl Property<OrderBookSummaryResponsePrecise> for OrderBookPricesAreTickMultiples {
+    fn holds(&mut self, value: &OrderBookSummaryResponsePrecise, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(value.bids.keys().chain(value.asks.keys()).all(|price| {
+            price
+                .checked_rem(value.min_tick_size)
+                .is_some_and(|remainder| remainder.is_zero())
+        }))
+    }
+}
+
+register_property!(OrderBookPricesAreTickMultiples, OrderBookSummaryResponsePrecise, ORDER_BOOK_SUMMARY_RESPONSE_PROPERTIES);
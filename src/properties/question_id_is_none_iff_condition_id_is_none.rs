@@ -1,13 +1,16 @@
-use crate::{MARKET_RESPONSE_PROPERTIES, Property};
-use fjall::Snapshot;
+use crate::{MARKET_RESPONSE_PROPERTIES, Property, PropertyContext, PropertyContextReadError};
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 
 #[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
 pub struct QuestionIdIsNoneIffConditionIdIsNone;
 
 impl Property<MarketResponse> for QuestionIdIsNoneIffConditionIdIsNone {
-    fn holds(&mut self, value: &MarketResponse, _snapshot: &Snapshot) -> bool {
-        value.question_id.is_none() == value.condition_id.is_none()
+    fn is_invariant(&self) -> bool {
+        true
+    }
+
+    fn holds(&mut self, value: &MarketResponse, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(value.question_id.is_none() == value.condition_id.is_none())
     }
 }
 
@@ -1,6 +1,5 @@
-use crate::{MARKET_RESPONSE_PROPERTIES, Property};
+use crate::{MARKET_RESPONSE_PROPERTIES, Property, PropertyContext, PropertyContextReadError};
 use alloy::primitives::U256;
-use fjall::Snapshot;
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 use rustc_hash::FxHashSet;
 
@@ -10,11 +9,19 @@ pub struct TokenIdIsUniqueOrZero {
 }
 
 impl Property<MarketResponse> for TokenIdIsUniqueOrZero {
-    fn holds(&mut self, market_response: &MarketResponse, _snapshot: &Snapshot) -> bool {
-        market_response
+    fn is_invariant(&self) -> bool {
+        true
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn holds(&mut self, market_response: &MarketResponse, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(market_response
             .tokens
             .iter()
-            .all(|token| if token.token_id.is_zero() { true } else { self.token_ids.insert(token.token_id) })
+            .all(|token| if token.token_id.is_zero() { true } else { self.token_ids.insert(token.token_id) }))
     }
 }
 
@@ -1,5 +1,4 @@
-use crate::{MARKET_RESPONSE_PROPERTIES, Property};
-use fjall::Snapshot;
+use crate::{MARKET_RESPONSE_PROPERTIES, Property, PropertyContext, PropertyContextReadError};
 use polymarket_client_sdk::clob::types::response::MarketResponse;
 use rust_decimal::Decimal;
 
@@ -7,11 +6,11 @@ use rust_decimal::Decimal;
 pub struct TokenPricesAreBetweenZeroAndOne;
 
 impl Property<MarketResponse> for TokenPricesAreBetweenZeroAndOne {
-    fn holds(&mut self, value: &MarketResponse, _snapshot: &Snapshot) -> bool {
-        value
+    fn holds(&mut self, value: &MarketResponse, _context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        Ok(value
             .tokens
             .iter()
-            .all(|token| token.price >= Decimal::ZERO && token.price <= Decimal::ONE)
+            .all(|token| token.price >= Decimal::ZERO && token.price <= Decimal::ONE))
     }
 }
 
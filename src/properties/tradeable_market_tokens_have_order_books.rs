@@ -0,0 +1,21 @@
+use crate::{MARKET_RESPONSE_PROPERTIES, Property, PropertyContext, PropertyContextReadError, ShouldDownloadOrderbooks};
+use polymarket_client_sdk::clob::types::response::MarketResponse;
+
+/// Holds if every token of a market whose order books are downloaded has an entry in the order book keyspace
+#[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
+pub struct TradeableMarketTokensHaveOrderBooks;
+
+impl Property<MarketResponse> for TradeableMarketTokensHaveOrderBooks {
+    fn holds(&mut self, value: &MarketResponse, context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        if value.should_download_orderbooks() {
+            for token in &value.tokens {
+                if !context.has_order_book(token.token_id)? {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+register_property!(TradeableMarketTokensHaveOrderBooks, MarketResponse, MARKET_RESPONSE_PROPERTIES);
@@ -1,11 +1,27 @@
+use crate::{ClobMarketResponsePrecise, ClobMarketResponsePreciseFallible, ConvertOrderBookSummaryResponseToOrderbookError, OrderBookSummaryResponsePrecise};
+use alloy::dyn_abi::TypedData;
+use alloy::primitives::{B256, hex};
+use alloy::signers::SignerSync;
+use alloy::signers::local::PrivateKeySigner;
 use async_jsonl::{Jsonl, JsonlDeserialize};
+use core::future::ready;
 use errgonomic::{handle, handle_bool, map_err};
 use futures::{Stream, StreamExt};
+use polymarket_client_sdk::clob::types::response::{MarketResponse, OrderBookSummaryResponse};
 use serde::Deserialize;
+use serde_json::json;
 use std::env::{VarError, var};
 use std::ffi::{OsStr, OsString};
+use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::spawn;
+use tokio::task::JoinHandle;
+use url::form_urlencoded;
 
 pub const MARKET_RESPONSE_PAGE_CACHE_LIMIT_ENV: &str = "MARKET_RESPONSE_PAGE_CACHE_LIMIT";
 pub const CACHE_DIR: &str = ".cache";
@@ -33,6 +49,24 @@ pub fn parse_boolish(value: &str) -> Result<bool, ParseBoolishError> {
     }
 }
 
+/// Returns the order book from `fixtures/orderbook.json`
+pub fn fixture_order_book() -> Result<OrderBookSummaryResponsePrecise, FixtureOrderBookError> {
+    use FixtureOrderBookError::*;
+    let input = include_str!("../fixtures/orderbook.json").trim();
+    let response = handle!(serde_json::de::from_str::<OrderBookSummaryResponse>(input), DeserializeFailed);
+    let order_book = handle!(OrderBookSummaryResponsePrecise::try_from(response), TryFromFailed);
+    Ok(order_book)
+}
+
+/// Returns the market from `fixtures/market.json`
+pub fn fixture_market_response() -> Result<ClobMarketResponsePrecise, FixtureMarketResponseError> {
+    use FixtureMarketResponseError::*;
+    let input = include_str!("../fixtures/market.json");
+    let market_response = handle!(serde_json::de::from_str::<MarketResponse>(input), DeserializeFailed);
+    let market = handle!(ClobMarketResponsePrecise::try_from(market_response), TryFromFailed);
+    Ok(market)
+}
+
 pub fn cache_dir_path() -> PathBuf {
     PathBuf::from(CACHE_DIR)
 }
@@ -66,6 +100,22 @@ where
     Ok(stream)
 }
 
+#[derive(Error, Debug)]
+pub enum FixtureOrderBookError {
+    #[error("failed to deserialize orderbook fixture")]
+    DeserializeFailed { source: serde_json::Error },
+    #[error("failed to convert orderbook response")]
+    TryFromFailed { source: ConvertOrderBookSummaryResponseToOrderbookError },
+}
+
+#[derive(Error, Debug)]
+pub enum FixtureMarketResponseError {
+    #[error("failed to deserialize market fixture")]
+    DeserializeFailed { source: serde_json::Error },
+    #[error("failed to convert market response")]
+    TryFromFailed { source: Box<ClobMarketResponsePreciseFallible> },
+}
+
 #[derive(Error, Debug)]
 pub enum ParseBoolishError {
     #[error("invalid boolish value")]
@@ -89,3 +139,237 @@ pub enum ReadJsonlCacheStreamError {
     #[error("failed to parse cache at '{cache_path}'")]
     ParseCacheFailed { source: anyhow::Error, cache_path: PathBuf },
 }
+
+/// A canned response of [`MockHttpServer`]
+#[derive(Clone, Debug)]
+pub struct MockHttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockHttpResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: body.into(),
+        }
+    }
+
+    pub fn not_found() -> Self {
+        Self::new(404, "")
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}\r\n"))
+            .collect::<String>();
+        format!("HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n{headers}\r\n{}", self.status, self.body.len(), self.body).into_bytes()
+    }
+}
+
+/// A request that was received by [`MockHttpServer`]
+#[derive(Clone, Debug)]
+pub struct MockHttpRequest {
+    pub method: String,
+    /// The path and the query
+    pub target: String,
+    pub body: String,
+}
+
+impl MockHttpRequest {
+    pub fn path(&self) -> &str {
+        self.target
+            .split_once('?')
+            .map_or(self.target.as_str(), |(path, _query)| path)
+    }
+
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        let (_path, query) = self.target.split_once('?')?;
+        form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _value)| key == name)
+            .map(|(_key, value)| value.into_owned())
+    }
+}
+
+/// A local HTTP server that returns the responses of the handler
+///
+/// The server records the request targets (path and query). It stops when dropped.
+pub struct MockHttpServer {
+    pub url: String,
+    pub request_count: Arc<AtomicUsize>,
+    pub requests: Arc<Mutex<Vec<String>>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockHttpServer {
+    /// Returns the canned responses in order (the last response is repeated)
+    pub async fn start(responses: Vec<MockHttpResponse>) -> Result<Self, MockHttpServerStartError> {
+        let index = AtomicUsize::new(0);
+        Self::start_with_handler(move |_request| {
+            let index = index.fetch_add(1, Ordering::SeqCst);
+            responses
+                .get(index)
+                .or(responses.last())
+                .cloned()
+                .unwrap_or_else(MockHttpResponse::not_found)
+        })
+        .await
+    }
+
+    pub async fn start_with_handler(handler: impl Fn(&MockHttpRequest) -> MockHttpResponse + Send + Sync + 'static) -> Result<Self, MockHttpServerStartError> {
+        Self::start_with_async_handler(move |request| ready(handler(&request))).await
+    }
+
+    pub async fn start_with_async_handler<H, Fut>(handler: H) -> Result<Self, MockHttpServerStartError>
+    where
+        H: Fn(MockHttpRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = MockHttpResponse> + Send,
+    {
+        use MockHttpServerStartError::*;
+        let listener = handle!(TcpListener::bind("127.0.0.1:0").await, BindFailed);
+        let address = handle!(listener.local_addr(), LocalAddrFailed);
+        let url = format!("http://{address}");
+        let request_count = Arc::new(AtomicUsize::new(0));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let request_count_for_server = request_count.clone();
+        let requests_for_server = requests.clone();
+        let handle = spawn(async move {
+            while let Ok((mut stream, _address)) = listener.accept().await {
+                request_count_for_server.fetch_add(1, Ordering::SeqCst);
+                let _result = Self::respond(&mut stream, &handler, &requests_for_server).await;
+            }
+        });
+        Ok(Self {
+            url,
+            request_count,
+            requests,
+            handle,
+        })
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.request_count.load(Ordering::SeqCst)
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    async fn respond<Fut: Future<Output = MockHttpResponse>>(stream: &mut TcpStream, handler: &impl Fn(MockHttpRequest) -> Fut, requests: &Mutex<Vec<String>>) -> Result<(), MockHttpServerRespondError> {
+        use MockHttpServerRespondError::*;
+        let request = handle!(Self::read_request(stream).await, ReadRequestFailed);
+        requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(request.target.clone());
+        let response = handler(request).await;
+        handle!(stream.write_all(&response.to_bytes()).await, WriteAllFailed);
+        handle!(stream.shutdown().await, ShutdownFailed);
+        Ok(())
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> Result<MockHttpRequest, MockHttpServerReadRequestError> {
+        use MockHttpServerReadRequestError::*;
+        let mut bytes = Vec::new();
+        let mut buffer = [0u8; 4096];
+        let head_len = loop {
+            if let Some(position) = bytes.windows(4).position(|window| window == b"\r\n\r\n") {
+                break position.saturating_add(4);
+            }
+            let len = handle!(stream.read(&mut buffer).await, ReadFailed);
+            if len == 0 {
+                break bytes.len();
+            }
+            bytes.extend_from_slice(buffer.get(..len).unwrap_or_default());
+        };
+        let head = String::from_utf8_lossy(bytes.get(..head_len).unwrap_or_default()).into_owned();
+        let content_length = head
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _value)| name.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_name, value)| value.trim().parse::<usize>().ok())
+            .unwrap_or_default();
+        // the body must be read completely, otherwise closing the connection resets it before the client reads the response
+        while bytes.len() < head_len.saturating_add(content_length) {
+            let len = handle!(stream.read(&mut buffer).await, ReadFailed);
+            if len == 0 {
+                break;
+            }
+            bytes.extend_from_slice(buffer.get(..len).unwrap_or_default());
+        }
+        // the request line is "METHOD TARGET VERSION"
+        let mut request_line = head.lines().next().unwrap_or_default().split(' ');
+        let method = request_line.next().unwrap_or_default().to_string();
+        let target = request_line.next().unwrap_or_default().to_string();
+        let body = String::from_utf8_lossy(bytes.get(head_len..).unwrap_or_default()).into_owned();
+        Ok(MockHttpRequest {
+            method,
+            target,
+            body,
+        })
+    }
+}
+
+impl Drop for MockHttpServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// A remote signer service (see [`RemoteSigner`](crate::RemoteSigner)) that signs with a local key
+pub async fn start_mock_remote_signer(signer: PrivateKeySigner) -> Result<MockHttpServer, MockHttpServerStartError> {
+    MockHttpServer::start_with_handler(move |request| mock_remote_signer_response(&signer, request)).await
+}
+
+pub fn mock_remote_signer_response(signer: &PrivateKeySigner, request: &MockHttpRequest) -> MockHttpResponse {
+    let hash_opt = match (request.method.as_str(), request.path()) {
+        ("GET", "/address") => return MockHttpResponse::new(200, json!({ "address": signer.address() }).to_string()),
+        ("POST", "/sign-typed-data") => serde_json::from_str::<TypedData>(&request.body)
+            .ok()
+            .and_then(|typed_data| typed_data.eip712_signing_hash().ok()),
+        ("POST", "/sign-hash") => serde_json::from_str::<serde_json::Value>(&request.body)
+            .ok()
+            .and_then(|body| serde_json::from_value::<B256>(body.get("hash")?.clone()).ok()),
+        _ => return MockHttpResponse::not_found(),
+    };
+    match hash_opt.and_then(|hash| signer.sign_hash_sync(&hash).ok()) {
+        Some(signature) => MockHttpResponse::new(200, json!({ "signature": hex::encode_prefixed(signature.as_bytes()) }).to_string()),
+        None => MockHttpResponse::new(400, "invalid request"),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum MockHttpServerStartError {
+    #[error("failed to bind mock http server")]
+    BindFailed { source: io::Error },
+    #[error("failed to get mock http server address")]
+    LocalAddrFailed { source: io::Error },
+}
+
+#[derive(Error, Debug)]
+pub enum MockHttpServerRespondError {
+    #[error("failed to read request")]
+    ReadRequestFailed { source: MockHttpServerReadRequestError },
+    #[error("failed to write response")]
+    WriteAllFailed { source: io::Error },
+    #[error("failed to shut down connection")]
+    ShutdownFailed { source: io::Error },
+}
+
+#[derive(Error, Debug)]
+pub enum MockHttpServerReadRequestError {
+    #[error("failed to read from connection")]
+    ReadFailed { source: io::Error },
+}
@@ -5,3 +5,19 @@ pub use should_download_orderbooks::*;
 mod property;
 
 pub use property::*;
+
+mod order_book_snapshot_source;
+
+pub use order_book_snapshot_source::*;
+
+mod table_row;
+
+pub use table_row::*;
+
+mod cache_entity;
+
+pub use cache_entity::*;
+
+mod retryable;
+
+pub use retryable::*;
@@ -0,0 +1,9 @@
+use rkyv::Archive as RkyvArchive;
+
+/// A value that is stored in its own keyspace of the cache (see [`Repository`](crate::Repository))
+pub trait CacheEntity: RkyvArchive + Sized {
+    const KEYSPACE: &'static str;
+
+    /// Returns the key of the value in [`Self::KEYSPACE`] (every command must store the value under this key)
+    fn key(&self) -> String;
+}
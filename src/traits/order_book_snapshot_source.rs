@@ -0,0 +1,37 @@
+use crate::{ConvertOrderBookSummaryResponseToOrderbookError, OrderBookSummaryResponsePrecise, TokenId};
+use errgonomic::handle;
+use polymarket_client_sdk::auth::state::State;
+use polymarket_client_sdk::clob::Client;
+use polymarket_client_sdk::clob::types::request::OrderBookSummaryRequest;
+use polymarket_client_sdk::error::Error as PolymarketError;
+use std::error::Error as StdError;
+use thiserror::Error;
+
+/// A source of full order book snapshots (used to resnapshot the local books after a gap is detected)
+pub trait OrderBookSnapshotSource {
+    type Error: StdError + Send + Sync + 'static;
+
+    fn order_book_snapshot(&self, token_id: TokenId) -> impl Future<Output = Result<OrderBookSummaryResponsePrecise, Self::Error>> + Send;
+}
+
+impl<S: State + Send + Sync> OrderBookSnapshotSource for Client<S> {
+    type Error = ClobClientOrderBookSnapshotError;
+
+    async fn order_book_snapshot(&self, token_id: TokenId) -> Result<OrderBookSummaryResponsePrecise, Self::Error> {
+        use ClobClientOrderBookSnapshotError::*;
+        let request = OrderBookSummaryRequest::builder()
+            .token_id(token_id)
+            .build();
+        let response = handle!(self.order_book(&request).await, OrderBookFailed, token_id);
+        let orderbook = handle!(OrderBookSummaryResponsePrecise::try_from(response), TryFromFailed, token_id);
+        Ok(orderbook)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobClientOrderBookSnapshotError {
+    #[error("failed to fetch order book for token '{token_id}'")]
+    OrderBookFailed { source: PolymarketError, token_id: TokenId },
+    #[error("failed to convert order book for token '{token_id}'")]
+    TryFromFailed { source: ConvertOrderBookSummaryResponseToOrderbookError, token_id: TokenId },
+}
@@ -1,12 +1,24 @@
-use crate::{PropertyName, property_name};
-use fjall::Snapshot;
+use crate::{PropertyContext, PropertyContextReadError, PropertyName, property_name};
 
 /// A trait for checking whether the property holds for a value
-/// [`&Snapshot`](Snapshot) is passed to facilitate checking cross-keyspace properties (e.g. foreign key fields)
+/// [`&PropertyContext`](PropertyContext) is passed to facilitate checking cross-keyspace properties (e.g. foreign key fields)
 pub trait Property<T> {
     fn name(&self) -> PropertyName {
         property_name::<Self>()
     }
 
-    fn holds(&mut self, value: &T, snapshot: &Snapshot) -> bool;
+    /// Returns true if the rest of the code relies on this property (a failure of an invariant makes `cache check` exit with a non-zero code)
+    fn is_invariant(&self) -> bool {
+        false
+    }
+
+    /// Returns true if the property keeps state between the calls (e.g. a set of seen values or a lazily loaded cache)
+    ///
+    /// Stateful properties are evaluated sequentially in key order, while stateless properties are evaluated in parallel (every worker creates its own instance)
+    fn is_stateful(&self) -> bool {
+        false
+    }
+
+    /// Returns an error if a related value can't be read from the context (the error is counted separately in the report instead of being counted as a failure)
+    fn holds(&mut self, value: &T, context: &PropertyContext) -> Result<bool, PropertyContextReadError>;
 }
@@ -0,0 +1,75 @@
+use crate::{RetryDecision, SendCheckedError};
+use core::error::Error as StdError;
+use polymarket_client_sdk::error::{Error as PolymarketError, Status as PolymarketStatus};
+use reqwest::StatusCode;
+
+/// An error of a request that may succeed if it is sent again (see [`HttpMiddleware::call`](crate::HttpMiddleware::call))
+pub trait Retryable {
+    fn retry_decision(&self) -> RetryDecision;
+}
+
+/// Retries `429 Too Many Requests`, `5xx`, timeouts and connection errors
+impl Retryable for reqwest::Error {
+    fn retry_decision(&self) -> RetryDecision {
+        let is_transient_status = self.status().is_some_and(is_transient_status);
+        if is_transient_status || self.is_timeout() || self.is_connect() {
+            RetryDecision::Retry
+        } else {
+            RetryDecision::Fail
+        }
+    }
+}
+
+/// The SDK doesn't expose the response headers, so `Retry-After` is not supported for its errors
+impl Retryable for PolymarketError {
+    fn retry_decision(&self) -> RetryDecision {
+        source_chain_retry_decision(self)
+    }
+}
+
+/// The SDK returns this error for a response with a non-success status (the response is not a [`reqwest::Error`])
+impl Retryable for PolymarketStatus {
+    fn retry_decision(&self) -> RetryDecision {
+        if is_transient_status(self.status_code) { RetryDecision::Retry } else { RetryDecision::Fail }
+    }
+}
+
+impl Retryable for SendCheckedError {
+    fn retry_decision(&self) -> RetryDecision {
+        use SendCheckedError::*;
+        match self {
+            SendFailed {
+                source,
+                ..
+            } => source.retry_decision(),
+            StatusFailed {
+                status,
+                retry_after,
+                ..
+            } => match (is_transient_status(*status), retry_after) {
+                (true, Some(retry_after)) => RetryDecision::RetryAfter(*retry_after),
+                (true, None) => RetryDecision::Retry,
+                (false, _) => RetryDecision::Fail,
+            },
+        }
+    }
+}
+
+pub fn is_transient_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Returns the decision for the first [`reqwest::Error`] or SDK status error in the source chain of the `error` (the other errors are permanent)
+pub fn source_chain_retry_decision(error: &(dyn StdError + 'static)) -> RetryDecision {
+    let mut current = Some(error);
+    while let Some(error) = current {
+        if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+            return error.retry_decision();
+        }
+        if let Some(error) = error.downcast_ref::<PolymarketStatus>() {
+            return error.retry_decision();
+        }
+        current = error.source();
+    }
+    RetryDecision::Fail
+}
@@ -0,0 +1,7 @@
+/// A value that can be written as a row of a table (see [`ReportFormat`](crate::ReportFormat))
+pub trait TableRow {
+    /// Returns the names of the columns (must have the same length as [`Self::cells`])
+    fn columns(&self) -> Vec<&'static str>;
+
+    fn cells(&self) -> Vec<String>;
+}
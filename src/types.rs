@@ -62,6 +62,10 @@ mod book_side_map;
 
 pub use book_side_map::*;
 
+mod book_side;
+
+pub use book_side::*;
+
 mod price;
 
 pub use price::*;
@@ -186,6 +190,10 @@ mod expr;
 
 pub use expr::*;
 
+mod expr_property;
+
+pub use expr_property::*;
+
 mod market_exchange;
 
 pub use market_exchange::*;
@@ -205,3 +213,155 @@ pub use opinion_market::*;
 mod opinion_market_page;
 
 pub use opinion_market_page::*;
+
+mod download_endpoint;
+
+pub use download_endpoint::*;
+
+mod download_checkpoint;
+
+pub use download_checkpoint::*;
+
+mod order_book_snapshot_key;
+
+pub use order_book_snapshot_key::*;
+
+mod order_book;
+
+pub use order_book::*;
+
+mod market_channel_message;
+
+pub use market_channel_message::*;
+
+mod market_channel_book_message;
+
+pub use market_channel_book_message::*;
+
+mod market_channel_price_change_message;
+
+pub use market_channel_price_change_message::*;
+
+mod market_channel_price_change;
+
+pub use market_channel_price_change::*;
+
+mod market_channel_tick_size_change_message;
+
+pub use market_channel_tick_size_change_message::*;
+
+mod market_channel_last_trade_price_message;
+
+pub use market_channel_last_trade_price_message::*;
+
+mod market_channel_update;
+
+pub use market_channel_update::*;
+
+mod market_channel_subscriber;
+
+pub use market_channel_subscriber::*;
+
+mod market_order_fill;
+
+pub use market_order_fill::*;
+
+mod limit_order_validator;
+
+pub use limit_order_validator::*;
+
+mod clob_signed_order;
+
+pub use clob_signed_order::*;
+
+mod clob_order_domain;
+
+pub use clob_order_domain::*;
+
+mod clob_offline_limit_order;
+
+pub use clob_offline_limit_order::*;
+
+mod clob_order_spec;
+
+pub use clob_order_spec::*;
+
+mod remote_signer;
+
+pub use remote_signer::*;
+
+mod clob_signer;
+
+pub use clob_signer::*;
+
+mod address_signer;
+
+pub use address_signer::*;
+
+mod property_context;
+
+pub use property_context::*;
+
+mod report_format;
+
+pub use report_format::*;
+
+mod market_response_feature;
+
+pub use market_response_feature::*;
+
+mod market_response_literal;
+
+pub use market_response_literal::*;
+
+mod implication;
+
+pub use implication::*;
+
+mod implication_miner;
+
+pub use implication_miner::*;
+
+mod comparison;
+
+pub use comparison::*;
+
+mod market_filter;
+
+pub use market_filter::*;
+
+mod market_indexes;
+
+pub use market_indexes::*;
+
+mod repository;
+
+pub use repository::*;
+
+mod keyspace_migration_report;
+
+pub use keyspace_migration_report::*;
+
+mod order_book_batch;
+
+pub use order_book_batch::*;
+
+mod http_endpoint;
+
+pub use http_endpoint::*;
+
+mod retry_decision;
+
+pub use retry_decision::*;
+
+mod retry_policy;
+
+pub use retry_policy::*;
+
+mod token_bucket;
+
+pub use token_bucket::*;
+
+mod http_middleware;
+
+pub use http_middleware::*;
@@ -0,0 +1,49 @@
+use alloy::primitives::{Address, B256, ChainId, Signature};
+use alloy::signers::{Error as SignerError, Result as SignerResult, Signer};
+use async_trait::async_trait;
+use thiserror::Error;
+
+/// A signer that only knows its address
+///
+/// The CLOB client needs a signer to authenticate, but the requests with the L2 API credentials are signed with the API secret, so the private key is not needed to post a pre-signed order.
+#[derive(Clone, Debug)]
+pub struct AddressSigner {
+    address: Address,
+    chain_id: Option<ChainId>,
+}
+
+impl AddressSigner {
+    pub fn new(address: Address) -> Self {
+        Self {
+            address,
+            chain_id: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Signer for AddressSigner {
+    async fn sign_hash(&self, _hash: &B256) -> SignerResult<Signature> {
+        Err(SignerError::other(AddressSignerSignHashError::PrivateKeyNotAvailable {
+            address: self.address,
+        }))
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AddressSignerSignHashError {
+    #[error("private key of '{address}' is not available (only the L2 API credentials have been provided)")]
+    PrivateKeyNotAvailable { address: Address },
+}
@@ -0,0 +1,55 @@
+use crate::{Amount, BookSideMap, Level, Price};
+use derive_more::{AsRef, Deref, Into};
+use indexmap::IndexMap;
+use rustc_hash::FxBuildHasher;
+use std::collections::BTreeMap;
+
+/// One side of a local [`OrderBook`](crate::OrderBook), keyed by ascending price
+///
+/// Unlike [`BookSideMap`], which preserves the order of the API response, this type keeps the levels sorted, so level updates and best price lookups are `O(log n)`.
+#[derive(Default, PartialEq, Eq, Clone, Debug, Deref, AsRef, Into)]
+pub struct BookSide(BTreeMap<Price, Amount>);
+
+impl BookSide {
+    pub fn min_price(&self) -> Option<&Price> {
+        self.0.first_key_value().map(|(price, _)| price)
+    }
+
+    pub fn max_price(&self) -> Option<&Price> {
+        self.0.last_key_value().map(|(price, _)| price)
+    }
+
+    pub fn min(&self) -> Option<Level> {
+        self.0.first_key_value().map(Level::from)
+    }
+
+    pub fn max(&self) -> Option<Level> {
+        self.0.last_key_value().map(Level::from)
+    }
+
+    /// Sets the size of the price level (removes the level if the size is zero)
+    pub(crate) fn set(&mut self, price: Price, size: Amount) {
+        if size.is_zero() {
+            self.0.remove(&price);
+        } else {
+            self.0.insert(price, size);
+        }
+    }
+}
+
+impl From<BookSideMap> for BookSide {
+    fn from(map: BookSideMap) -> Self {
+        Self(map.into_inner().into_iter().collect())
+    }
+}
+
+/// Note that the levels are sorted by ascending price, so the order may differ from the original response
+impl From<BookSide> for BookSideMap {
+    fn from(side: BookSide) -> Self {
+        let BookSide(map) = side;
+        BookSideMap::new(
+            map.into_iter()
+                .collect::<IndexMap<Price, Amount, FxBuildHasher>>(),
+        )
+    }
+}
@@ -94,10 +94,12 @@ impl BookSideMap {
         self.0
     }
 
+    /// Scans all levels because the map preserves the response order (see [`BookSide`](crate::BookSide) for sorted lookups)
     pub fn min_price(&self) -> Option<&Price> {
         self.keys().min()
     }
 
+    /// Scans all levels because the map preserves the response order (see [`BookSide`](crate::BookSide) for sorted lookups)
     pub fn max_price(&self) -> Option<&Price> {
         self.keys().max()
     }
@@ -1,24 +1,40 @@
-use crate::{ClobMarketResponsePrecise, ClobMarketResponsePreciseFallible, NEXT_CURSOR_START, NextCursor, TokenId, get_page_stream, is_launched};
+use crate::{CLOB_ORDER_BOOKS_CHUNK_SIZE, CLOB_ORDER_BOOKS_CONCURRENCY, ClobMarketResponsePrecise, ClobMarketResponsePreciseFallible, ConvertOrderBookSummaryResponseToOrderbookError, HttpEndpoint, HttpMiddleware, HttpMiddlewareCallError, NEXT_CURSOR_START, NextCursor, OrderBookBatch, OrderBookSummaryResponsePrecise, TokenId, format_debug_diff, get_page_stream, is_launched};
 use derive_more::{Deref, DerefMut};
 use derive_new::new;
 use errgonomic::{ErrVec, handle, handle_iter};
-use futures::Stream;
+use futures::{Stream, StreamExt, stream};
+use itertools::Itertools;
 use polymarket_client_sdk::clob::Client;
-use polymarket_client_sdk::clob::types::response::Page;
+use polymarket_client_sdk::clob::types::request::OrderBookSummaryRequest;
+use polymarket_client_sdk::clob::types::response::{OrderBookSummaryResponse, Page};
 use polymarket_client_sdk::error::Error as PolymarketError;
+use rustc_hash::FxHashSet;
 use std::fmt::Debug;
+use std::num::NonZeroUsize;
+use strum::EnumIs;
 use thiserror::Error;
 
 #[derive(new, Deref, DerefMut, Default, Clone, Debug)]
 pub struct ClobClient {
+    #[deref]
+    #[deref_mut]
     pub inner: Client,
+    /// Applies to every request of this client (the derefed [`Self::inner`] client bypasses it)
+    #[new(default)]
+    pub middleware: HttpMiddleware,
 }
 
 impl ClobClient {
     /// This function returns only launched markets (see [`is_launched`]).
     pub async fn markets(&self, next_cursor: Option<String>) -> Result<Page<ClobMarketResponsePrecise>, ClobClientMarketsError> {
         use ClobClientMarketsError::*;
-        let page = handle!(self.inner.markets(next_cursor.clone()).await, MarketsFailed, next_cursor);
+        let page = handle!(
+            self.middleware
+                .call(HttpEndpoint::Clob, || self.inner.markets(next_cursor.clone()))
+                .await,
+            MarketsFailed,
+            next_cursor
+        );
         let Page {
             limit,
             count,
@@ -40,8 +56,90 @@ impl ClobClient {
             .build())
     }
 
-    pub async fn order_books(&self, _token_ids: impl IntoIterator<Item = &TokenId>) {
-        todo!()
+    /// Fetches the order books in chunks of [`CLOB_ORDER_BOOKS_CHUNK_SIZE`] tokens with at most [`CLOB_ORDER_BOOKS_CONCURRENCY`] requests in flight.
+    ///
+    /// A failed request or conversion doesn't fail the other tokens (see [`OrderBookBatch::failures`]).
+    pub async fn order_books(&self, token_ids: impl IntoIterator<Item = &TokenId>) -> OrderBookBatch {
+        self.order_books_with_limits(token_ids, CLOB_ORDER_BOOKS_CHUNK_SIZE, CLOB_ORDER_BOOKS_CONCURRENCY)
+            .await
+    }
+
+    /// Same as [`Self::order_books`], but with custom limits
+    ///
+    /// The order books are returned in the order of the chunks.
+    pub async fn order_books_with_limits(&self, token_ids: impl IntoIterator<Item = &TokenId>, chunk_size: NonZeroUsize, concurrency: NonZeroUsize) -> OrderBookBatch {
+        let chunks = token_ids
+            .into_iter()
+            .copied()
+            .chunks(chunk_size.get())
+            .into_iter()
+            .map(Iterator::collect)
+            .collect::<Vec<Vec<TokenId>>>();
+        stream::iter(chunks)
+            .map(|chunk| self.order_books_chunk(chunk))
+            .buffered(concurrency.get())
+            .fold(OrderBookBatch::default(), |mut batch, chunk_batch| async move {
+                batch.append(chunk_batch);
+                batch
+            })
+            .await
+    }
+
+    async fn order_books_chunk(&self, token_ids: Vec<TokenId>) -> OrderBookBatch {
+        use ClobClientOrderBookError::*;
+        let requests = token_ids
+            .iter()
+            .map(|token_id| {
+                OrderBookSummaryRequest::builder()
+                    .token_id(*token_id)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        let mut batch = OrderBookBatch::default();
+        match self
+            .middleware
+            .call(HttpEndpoint::Clob, || self.inner.order_books(&requests))
+            .await
+        {
+            Ok(responses) => {
+                let mut missing_token_ids = token_ids.iter().copied().collect::<FxHashSet<TokenId>>();
+                for response in responses {
+                    missing_token_ids.remove(&response.asset_id);
+                    match Self::order_book_from_response(response) {
+                        Ok(order_book) => batch.order_books.push(order_book),
+                        Err(error) => batch.failures.push(error),
+                    }
+                }
+                // the tokens are reported in the order of the request
+                let not_found = token_ids
+                    .into_iter()
+                    .filter(|token_id| missing_token_ids.contains(token_id))
+                    .map(|token_id| OrderBookNotFound {
+                        token_id,
+                    });
+                batch.failures.extend(not_found);
+            }
+            Err(source) => batch.failures.push(OrderBooksFailed {
+                source,
+                token_ids,
+            }),
+        }
+        batch
+    }
+
+    /// Converts the response and verifies that the conversion is lossless
+    fn order_book_from_response(response: OrderBookSummaryResponse) -> Result<OrderBookSummaryResponsePrecise, ClobClientOrderBookError> {
+        use ClobClientOrderBookError::*;
+        let token_id = response.asset_id;
+        let order_book = handle!(OrderBookSummaryResponsePrecise::try_from(response.clone()), TryFromFailed, token_id);
+        let response_round_trip = OrderBookSummaryResponse::from(order_book.clone());
+        if response != response_round_trip {
+            return Err(RoundTripFailed {
+                token_id,
+                diff: format_debug_diff(&response, &response_round_trip, "response", "response_round_trip"),
+            });
+        }
+        Ok(order_book)
     }
 
     pub fn markets_stream(&self) -> impl Stream<Item = Result<Vec<ClobMarketResponsePrecise>, ClobClientMarketsError>> + '_ {
@@ -56,7 +154,187 @@ impl ClobClient {
 #[derive(Error, Debug)]
 pub enum ClobClientMarketsError {
     #[error("failed to fetch markets page")]
-    MarketsFailed { source: PolymarketError, next_cursor: Option<String> },
+    MarketsFailed { source: HttpMiddlewareCallError<PolymarketError>, next_cursor: Option<String> },
     #[error("failed to convert {len} markets", len = source.len())]
     MarketTryFromFailed { source: ErrVec<ClobMarketResponsePreciseFallible> },
 }
+
+#[derive(EnumIs, Error, Debug)]
+pub enum ClobClientOrderBookError {
+    #[error("failed to fetch order books for {len} tokens", len = token_ids.len())]
+    OrderBooksFailed { source: HttpMiddlewareCallError<PolymarketError>, token_ids: Vec<TokenId> },
+    #[error("failed to convert order book for token '{token_id}'")]
+    TryFromFailed { source: ConvertOrderBookSummaryResponseToOrderbookError, token_id: TokenId },
+    #[error("round-tripped order book for token '{token_id}' does not match original: '{diff}'")]
+    RoundTripFailed { token_id: TokenId, diff: String },
+    #[error("order book for token '{token_id}' is missing from the response")]
+    OrderBookNotFound { token_id: TokenId },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MOCK_CLOB_ORDER_BOOKS, MockHttpRequest, MockHttpResponse, MockHttpServer, MockHttpServerStartError, RetryPolicy, TokenBucket, mock_clob_response, start_mock_clob_server};
+    use alloy::primitives::U256;
+    use core::time::Duration;
+    use errgonomic::handle_opt;
+    use polymarket_client_sdk::clob::Config;
+    use serde_json::Value;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::time::sleep;
+
+    /// The client doesn't retry, so every chunk is requested exactly once
+    fn client(server: &MockHttpServer) -> Result<ClobClient, PolymarketError> {
+        let inner = Client::new(&server.url, Config::default())?;
+        let bucket = || TokenBucket::new(Duration::ZERO, 1);
+        let middleware = HttpMiddleware::new(RetryPolicy::new(0, Duration::ZERO, Duration::ZERO), 0, bucket(), bucket(), bucket());
+        Ok(ClobClient {
+            inner,
+            middleware,
+        })
+    }
+
+    /// The tokens of the recorded order books (see [`MOCK_CLOB_ORDER_BOOKS`])
+    fn recorded_token_ids() -> Vec<TokenId> {
+        MOCK_CLOB_ORDER_BOOKS
+            .iter()
+            .filter_map(|order_book| serde_json::from_str::<OrderBookSummaryResponse>(order_book).ok())
+            .map(|order_book| order_book.asset_id)
+            .collect()
+    }
+
+    fn limit(value: usize) -> NonZeroUsize {
+        NonZeroUsize::new(value).unwrap_or(NonZeroUsize::MIN)
+    }
+
+    fn order_book_token_ids(batch: &OrderBookBatch) -> Vec<TokenId> {
+        batch
+            .order_books
+            .iter()
+            .map(|order_book| order_book.token_id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn must_fetch_order_books_in_chunks() -> Result<(), ClobClientTestError> {
+        use ClobClientTestError::*;
+        let server = handle!(start_mock_clob_server().await, StartServerFailed);
+        let client = handle!(client(&server), ClientNewFailed);
+        let recorded_token_ids = recorded_token_ids();
+        assert_eq!(recorded_token_ids.len(), 2);
+        let unknown_token_id = U256::from(1);
+        let token_ids = recorded_token_ids
+            .iter()
+            .copied()
+            .chain([unknown_token_id])
+            .collect::<Vec<_>>();
+        let batch = client
+            .order_books_with_limits(&token_ids, limit(2), limit(2))
+            .await;
+        // the chunks are [recorded, recorded] and [unknown]
+        assert_eq!(server.request_count(), 2);
+        assert_eq!(order_book_token_ids(&batch), recorded_token_ids);
+        assert!(matches!(batch.failures.as_slice(), [ClobClientOrderBookError::OrderBookNotFound { token_id }] if *token_id == unknown_token_id));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_bound_concurrent_requests() -> Result<(), ClobClientTestError> {
+        use ClobClientTestError::*;
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let in_flight_for_server = in_flight.clone();
+        let max_in_flight_for_server = max_in_flight.clone();
+        let server = handle!(
+            MockHttpServer::start_with_async_handler(move |request: MockHttpRequest| {
+                let in_flight = in_flight_for_server.clone();
+                let max_in_flight = max_in_flight_for_server.clone();
+                async move {
+                    let previous = in_flight.fetch_add(1, Ordering::SeqCst);
+                    max_in_flight.fetch_max(previous.saturating_add(1), Ordering::SeqCst);
+                    sleep(Duration::from_millis(50)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    mock_clob_response(&request)
+                }
+            })
+            .await,
+            StartServerFailed
+        );
+        let client = handle!(client(&server), ClientNewFailed);
+        let token_ids = recorded_token_ids().repeat(3);
+        let batch = client
+            .order_books_with_limits(&token_ids, limit(1), limit(2))
+            .await;
+        assert_eq!(server.request_count(), 6);
+        assert_eq!(order_book_token_ids(&batch), token_ids);
+        assert!(batch.failures.is_empty());
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_report_failed_chunk() -> Result<(), ClobClientTestError> {
+        use ClobClientTestError::*;
+        let recorded_token_ids = recorded_token_ids();
+        let token_id = handle_opt!(recorded_token_ids.first().copied(), RecordedTokenIdNotFound);
+        let failing_token_id = handle_opt!(recorded_token_ids.last().copied(), RecordedTokenIdNotFound);
+        let failing_token_id_string = failing_token_id.to_string();
+        let server = handle!(
+            MockHttpServer::start_with_handler(move |request| {
+                if request.body.contains(&failing_token_id_string) {
+                    MockHttpResponse::new(400, "")
+                } else {
+                    mock_clob_response(request)
+                }
+            })
+            .await,
+            StartServerFailed
+        );
+        let client = handle!(client(&server), ClientNewFailed);
+        let batch = client
+            .order_books_with_limits(&recorded_token_ids, limit(1), limit(1))
+            .await;
+        // the other chunk is not affected
+        assert_eq!(order_book_token_ids(&batch), vec![token_id]);
+        assert!(matches!(batch.failures.as_slice(), [ClobClientOrderBookError::OrderBooksFailed { token_ids, .. }] if *token_ids == vec![failing_token_id]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_report_round_trip_failure() -> Result<(), ClobClientTestError> {
+        use ClobClientTestError::*;
+        let recorded_order_book = handle_opt!(MOCK_CLOB_ORDER_BOOKS.first(), RecordedTokenIdNotFound);
+        let mut order_book = handle!(serde_json::from_str::<Value>(recorded_order_book), FromStrFailed);
+        let token_id = handle!(serde_json::from_value::<OrderBookSummaryResponse>(order_book.clone()), FromValueFailed).asset_id;
+        // a duplicated price level is accepted by the conversion, but it is lost in the round trip
+        let bids = handle_opt!(order_book.get_mut("bids").and_then(Value::as_array_mut), BidsNotFound);
+        let bid = handle_opt!(bids.first().cloned(), BidsNotFound);
+        bids.push(bid);
+        let body = Value::Array(vec![order_book]).to_string();
+        let server = handle!(MockHttpServer::start(vec![MockHttpResponse::new(200, body)]).await, StartServerFailed);
+        let client = handle!(client(&server), ClientNewFailed);
+        let batch = client
+            .order_books_with_limits([&token_id], limit(1), limit(1))
+            .await;
+        assert!(batch.order_books.is_empty());
+        assert!(matches!(batch.failures.as_slice(), [ClobClientOrderBookError::RoundTripFailed { token_id: failed_token_id, .. }] if *failed_token_id == token_id));
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum ClobClientTestError {
+        #[error("failed to start mock CLOB server")]
+        StartServerFailed { source: MockHttpServerStartError },
+        #[error("failed to create CLOB client")]
+        ClientNewFailed { source: PolymarketError },
+        #[error("recorded token id not found")]
+        RecordedTokenIdNotFound,
+        #[error("failed to parse recorded order book")]
+        FromStrFailed { source: serde_json::Error },
+        #[error("failed to convert recorded order book")]
+        FromValueFailed { source: serde_json::Error },
+        #[error("recorded order book has no bids")]
+        BidsNotFound,
+    }
+}
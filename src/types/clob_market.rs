@@ -1,4 +1,4 @@
-use crate::{Amount, ClobMarketResponsePrecise, ConditionId, DurationPositiveSeconds, EventId, NegRisk, QuestionId, Rewards, RkyvDecimal, RkyvOffsetDateTime, TokenId, Tokens, TryFromNegRiskTripleError, WinnerId};
+use crate::{Amount, CLOB_MARKETS_KEYSPACE, CacheEntity, ClobMarketResponsePrecise, ConditionId, DurationPositiveSeconds, EventId, NegRisk, QuestionId, Rewards, RkyvDecimal, RkyvOffsetDateTime, TokenId, Tokens, TryFromNegRiskTripleError, WinnerId};
 use alloy::primitives::Address;
 use derive_more::{From, Into};
 use rkyv::with::Map;
@@ -50,6 +50,14 @@ impl ClobMarket {
     }
 }
 
+impl CacheEntity for ClobMarket {
+    const KEYSPACE: &'static str = CLOB_MARKETS_KEYSPACE;
+
+    fn key(&self) -> String {
+        self.slug.clone()
+    }
+}
+
 impl TryFrom<ClobMarketResponsePrecise> for ClobMarket {
     type Error = ClobMarketFallible;
 
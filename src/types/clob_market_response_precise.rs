@@ -1,4 +1,4 @@
-use crate::{Amount, ConditionId, ConvertVecTokenRawToTokensError, DurationPositiveSeconds, EventId, QuestionId, Rewards, RkyvDecimal, RkyvOffsetDateTime, TokenId, Tokens, into_chrono_date_time};
+use crate::{Amount, CLOB_MARKET_RESPONSES_KEYSPACE, CacheEntity, ConditionId, ConvertVecTokenRawToTokensError, DurationPositiveSeconds, EventId, QuestionId, Rewards, RkyvDecimal, RkyvOffsetDateTime, TokenId, Tokens, into_chrono_date_time};
 use alloy_primitives::Address;
 use derive_more::{From, Into};
 use polymarket_client_sdk::clob::types::response::{MarketResponse, Rewards as RewardsRaw, Token as TokenRaw};
@@ -119,6 +119,14 @@ pub struct ClobMarketResponsePreciseFallible {
 }
 
 /// NOTE: Some markets have an invalid `neg_risk_market_id` (e.g. "0x12309") because they were created by Polymarket just for testing
+impl CacheEntity for ClobMarketResponsePrecise {
+    const KEYSPACE: &'static str = CLOB_MARKET_RESPONSES_KEYSPACE;
+
+    fn key(&self) -> String {
+        self.market_slug.clone()
+    }
+}
+
 impl TryFrom<MarketResponse> for ClobMarketResponsePrecise {
     type Error = ClobMarketResponsePreciseFallible;
 
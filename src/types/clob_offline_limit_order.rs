@@ -0,0 +1,201 @@
+use crate::{Amount, CLOB_AMOUNT_DECIMALS, ClobOrderDomain, ClobSignedOrder, OrderType, Price, Side, TokenId, random_u64};
+use alloy::primitives::{Address, ChainId, U256};
+use alloy::signers::{Error as SignerError, Signer};
+use errgonomic::{handle, handle_opt};
+use polymarket_client_sdk::clob::types::Order;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use thiserror::Error;
+
+/// The sizes are rounded down to this number of decimals (same as the official clients)
+const SIZE_DECIMALS: u32 = 2;
+
+/// The salt must fit into a JSON number without losing precision
+const SALT_MASK: u64 = 0x001F_FFFF_FFFF_FFFF;
+
+/// A limit order that is built and signed without any request to the CLOB API
+///
+/// The `tick_size` and `neg_risk` are normally fetched from the API, so they must be provided by the caller.
+#[derive(Clone, Debug)]
+pub struct ClobOfflineLimitOrder {
+    pub token_id: TokenId,
+    pub side: Side,
+    pub price: Price,
+    pub size: Amount,
+    pub tick_size: Price,
+    pub neg_risk: bool,
+    pub chain_id: ChainId,
+    /// The address that holds the funds (the signer for EOA orders, the proxy wallet otherwise)
+    pub maker: Address,
+    pub taker: Address,
+    pub signature_type: u8,
+    pub nonce: u64,
+    /// Unix timestamp in seconds (zero means no expiration)
+    pub expiration: u64,
+    pub fee_rate_bps: u32,
+    pub order_type: OrderType,
+    pub post_only: bool,
+}
+
+impl ClobOfflineLimitOrder {
+    pub async fn sign(self, signer: &impl Signer) -> Result<ClobSignedOrder, ClobOfflineLimitOrderSignError> {
+        use ClobOfflineLimitOrderSignError::*;
+        let domain = handle_opt!(ClobOrderDomain::new(self.chain_id, self.neg_risk), ExchangeNotFound, chain_id: self.chain_id, neg_risk: self.neg_risk);
+        let (maker_amount, taker_amount) = handle_opt!(self.amounts(), AmountsFailed, price: self.price, size: self.size, tick_size: self.tick_size);
+        let side = match self.side {
+            Side::Buy => 0,
+            Side::Sell => 1,
+        };
+        let order = Order {
+            salt: U256::from(Self::salt()),
+            maker: self.maker,
+            signer: signer.address(),
+            taker: self.taker,
+            tokenId: self.token_id,
+            makerAmount: maker_amount,
+            takerAmount: taker_amount,
+            expiration: U256::from(self.expiration),
+            nonce: U256::from(self.nonce),
+            feeRateBps: U256::from(self.fee_rate_bps),
+            side,
+            signatureType: self.signature_type,
+        };
+        // the signer receives the typed order (not a bare digest), so that a remote signer can inspect it
+        let signature = handle!(
+            signer
+                .sign_typed_data(&order, &domain.eip712_domain())
+                .await,
+            SignTypedDataFailed
+        );
+        let Order {
+            salt,
+            maker,
+            signer,
+            taker,
+            tokenId: token_id,
+            makerAmount: maker_amount,
+            takerAmount: taker_amount,
+            expiration,
+            nonce,
+            feeRateBps: fee_rate_bps,
+            side,
+            signatureType: signature_type,
+        } = order;
+        Ok(ClobSignedOrder {
+            salt,
+            maker,
+            signer,
+            taker,
+            token_id,
+            maker_amount,
+            taker_amount,
+            expiration,
+            nonce,
+            fee_rate_bps,
+            side,
+            signature_type,
+            signature,
+            order_type: self.order_type.into(),
+            owner: None,
+            post_only: Some(self.post_only),
+            domain: Some(domain),
+        })
+    }
+
+    /// Returns the maker and taker amounts in base units (a buy order gives USDC for shares, a sell order gives shares for USDC)
+    ///
+    /// The price is rounded to the tick size, the size is rounded down to [`SIZE_DECIMALS`], and the notional is rounded down to the price decimals plus [`SIZE_DECIMALS`].
+    fn amounts(&self) -> Option<(U256, U256)> {
+        let price_decimals = self.tick_size.normalize().scale();
+        let price = self
+            .price
+            .round_dp_with_strategy(price_decimals, RoundingStrategy::MidpointNearestEven);
+        let size = self
+            .size
+            .round_dp_with_strategy(SIZE_DECIMALS, RoundingStrategy::ToZero);
+        let notional = size
+            .checked_mul(price)?
+            .round_dp_with_strategy(price_decimals.saturating_add(SIZE_DECIMALS), RoundingStrategy::ToZero);
+        let (maker_amount, taker_amount) = match self.side {
+            Side::Buy => (notional, size),
+            Side::Sell => (size, notional),
+        };
+        Some((Self::base_units(maker_amount)?, Self::base_units(taker_amount)?))
+    }
+
+    fn base_units(amount: Decimal) -> Option<U256> {
+        let unit = Decimal::from(10u64.checked_pow(CLOB_AMOUNT_DECIMALS)?);
+        let units = amount.checked_mul(unit)?.trunc().to_u128()?;
+        Some(U256::from(units))
+    }
+
+    fn salt() -> u64 {
+        random_u64() & SALT_MASK
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClobOfflineLimitOrderSignError {
+    #[error("exchange contract is not known for chain '{chain_id}' (neg risk: {neg_risk})")]
+    ExchangeNotFound { chain_id: ChainId, neg_risk: bool },
+    #[error("failed to compute the order amounts for price '{price}', size '{size}' and tick size '{tick_size}'")]
+    AmountsFailed { price: Price, size: Amount, tick_size: Price },
+    #[error("failed to sign order")]
+    SignTypedDataFailed { source: SignerError },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::SignatureError;
+    use alloy::signers::local::{LocalSignerError, PrivateKeySigner};
+    use alloy::sol_types::SolStruct;
+    use polymarket_client_sdk::POLYGON;
+
+    #[tokio::test]
+    async fn must_sign_order_for_the_exchange_domain() -> Result<(), ClobOfflineLimitOrderTestError> {
+        use ClobOfflineLimitOrderTestError::*;
+        let signer = handle!("0x0123456789012345678901234567890123456789012345678901234567890123".parse::<PrivateKeySigner>(), PrivateKeyParseFailed);
+        let order = ClobOfflineLimitOrder {
+            token_id: U256::from(7),
+            side: Side::Buy,
+            price: Decimal::new(55, 2),
+            size: Decimal::new(10_005, 3),
+            tick_size: Decimal::new(1, 2),
+            neg_risk: true,
+            chain_id: POLYGON,
+            maker: signer.address(),
+            taker: Address::ZERO,
+            signature_type: 0,
+            nonce: 0,
+            expiration: 0,
+            fee_rate_bps: 0,
+            order_type: OrderType::Gtc,
+            post_only: false,
+        };
+        let signed_order = handle!(order.sign(&signer).await, SignFailed);
+        // 10 shares for 5.5 USDC
+        assert_eq!(signed_order.maker_amount, U256::from(5_500_000));
+        assert_eq!(signed_order.taker_amount, U256::from(10_000_000));
+        let domain = handle_opt!(signed_order.domain.clone(), DomainNotFound);
+        assert_eq!(Some(domain.clone()), ClobOrderDomain::new(POLYGON, true));
+        let hash = signed_order
+            .order()
+            .eip712_signing_hash(&domain.eip712_domain());
+        let address = handle!(signed_order.signature.recover_address_from_prehash(&hash), RecoverAddressFailed);
+        assert_eq!(address, signer.address());
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum ClobOfflineLimitOrderTestError {
+        #[error("failed to parse private key")]
+        PrivateKeyParseFailed { source: LocalSignerError },
+        #[error("failed to sign order")]
+        SignFailed { source: ClobOfflineLimitOrderSignError },
+        #[error("domain not found")]
+        DomainNotFound,
+        #[error("failed to recover address")]
+        RecoverAddressFailed { source: SignatureError },
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{CLOB_EXCHANGE_DOMAIN_NAME, CLOB_EXCHANGE_DOMAIN_VERSION};
+use alloy::primitives::{Address, ChainId, U256};
+use alloy::sol_types::Eip712Domain;
+use polymarket_client_sdk::contract_config;
+use std::borrow::Cow;
+
+/// The EIP-712 domain of the exchange contract that verifies the signature of an order
+///
+/// The neg-risk markets are traded on a separate exchange contract, so the domain depends on the market.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClobOrderDomain {
+    pub name: String,
+    pub version: String,
+    pub chain_id: ChainId,
+    pub verifying_contract: Address,
+}
+
+impl ClobOrderDomain {
+    /// Returns `None` if the exchange is not deployed on the chain
+    pub fn new(chain_id: ChainId, neg_risk: bool) -> Option<Self> {
+        contract_config(chain_id, neg_risk).map(|config| Self {
+            name: CLOB_EXCHANGE_DOMAIN_NAME.to_string(),
+            version: CLOB_EXCHANGE_DOMAIN_VERSION.to_string(),
+            chain_id,
+            verifying_contract: config.exchange,
+        })
+    }
+
+    pub fn eip712_domain(&self) -> Eip712Domain {
+        Eip712Domain::new(Some(Cow::Owned(self.name.clone())), Some(Cow::Owned(self.version.clone())), Some(U256::from(self.chain_id)), Some(self.verifying_contract), None)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{OrderType, Side, TokenId, serialize_as_decimal};
+use chrono::{DateTime, Utc};
+use polymarket_client_sdk::types::Decimal;
+
+/// A limit order that is read from a line of the `clob place-orders` input
+///
+/// The `id` is copied to the output line, so that the results can be matched to the inputs.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ClobOrderSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(serialize_with = "serialize_as_decimal")]
+    pub token_id: TokenId,
+    pub side: Side,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub price: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub size: Decimal,
+    #[serde(rename = "type", default = "ClobOrderSpec::default_order_type")]
+    pub order_type: OrderType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub post_only: bool,
+}
+
+impl ClobOrderSpec {
+    fn default_order_type() -> OrderType {
+        OrderType::Gtc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_deserialize_minimal_spec() -> Result<(), serde_json::Error> {
+        let spec = serde_json::from_str::<ClobOrderSpec>(r#"{"token_id":"7","side":"BUY","price":"0.5","size":"10"}"#)?;
+        assert_eq!(spec.id, None);
+        assert_eq!(spec.token_id, TokenId::from(7u64));
+        assert_eq!(spec.order_type, OrderType::Gtc);
+        assert!(!spec.post_only);
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use crate::ClobOrderDomain;
+use polymarket_client_sdk::auth::ApiKey;
+use polymarket_client_sdk::clob::types::{Order, OrderType as PolymarketClobOrderType, SignedOrder};
+use polymarket_client_sdk::types::{Address, Signature, U256};
+
+/// A signed order that can be written to and read from JSON (the order fields are the EIP-712 message that is signed by the `signer`)
+///
+/// This struct is needed because [`SignedOrder`] doesn't implement [`serde::Deserialize`].
+/// The `owner` is the API key that posts the order, so it is not known if the order has been signed offline.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClobSignedOrder {
+    pub salt: U256,
+    pub maker: Address,
+    pub signer: Address,
+    pub taker: Address,
+    pub token_id: U256,
+    pub maker_amount: U256,
+    pub taker_amount: U256,
+    pub expiration: U256,
+    pub nonce: U256,
+    pub fee_rate_bps: U256,
+    pub side: u8,
+    pub signature_type: u8,
+    pub signature: Signature,
+    pub order_type: PolymarketClobOrderType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<ApiKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_only: Option<bool>,
+    /// The domain that the order has been signed for (informational, the exchange derives the domain from the market)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<ClobOrderDomain>,
+}
+
+impl From<SignedOrder> for ClobSignedOrder {
+    fn from(input: SignedOrder) -> Self {
+        let SignedOrder {
+            order,
+            signature,
+            order_type,
+            owner,
+            post_only,
+            ..
+        } = input;
+        let Order {
+            salt,
+            maker,
+            signer,
+            taker,
+            tokenId: token_id,
+            makerAmount: maker_amount,
+            takerAmount: taker_amount,
+            expiration,
+            nonce,
+            feeRateBps: fee_rate_bps,
+            side,
+            signatureType: signature_type,
+        } = order;
+        Self {
+            salt,
+            maker,
+            signer,
+            taker,
+            token_id,
+            maker_amount,
+            taker_amount,
+            expiration,
+            nonce,
+            fee_rate_bps,
+            side,
+            signature_type,
+            signature,
+            order_type,
+            owner: Some(owner),
+            post_only,
+            domain: None,
+        }
+    }
+}
+
+impl ClobSignedOrder {
+    /// Returns the EIP-712 message that has been signed
+    pub fn order(&self) -> Order {
+        Order {
+            salt: self.salt,
+            maker: self.maker,
+            signer: self.signer,
+            taker: self.taker,
+            tokenId: self.token_id,
+            makerAmount: self.maker_amount,
+            takerAmount: self.taker_amount,
+            expiration: self.expiration,
+            nonce: self.nonce,
+            feeRateBps: self.fee_rate_bps,
+            side: self.side,
+            signatureType: self.signature_type,
+        }
+    }
+
+    /// The `owner` must be the API key that posts the order, so it replaces the `owner` of this struct
+    pub fn into_signed_order(self, owner: ApiKey) -> SignedOrder {
+        SignedOrder {
+            order: self.order(),
+            signature: self.signature,
+            order_type: self.order_type,
+            owner,
+            post_only: self.post_only,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use errgonomic::{handle, handle_opt};
+    use serde_json::Value;
+    use thiserror::Error;
+
+    #[test]
+    fn must_round_trip_through_signed_order() -> Result<(), ClobSignedOrderTestError> {
+        use ClobSignedOrderTestError::*;
+        let input = r#"{
+            "salt": "0x1",
+            "maker": "0x0000000000000000000000000000000000000001",
+            "signer": "0x0000000000000000000000000000000000000001",
+            "taker": "0x0000000000000000000000000000000000000000",
+            "tokenId": "0x7",
+            "makerAmount": "0x4c4b40",
+            "takerAmount": "0x989680",
+            "expiration": "0x0",
+            "nonce": "0x0",
+            "feeRateBps": "0x0",
+            "side": 0,
+            "signatureType": 0,
+            "signature": { "r": "0x1", "s": "0x2", "yParity": "0x1" },
+            "orderType": "GTC",
+            "owner": "00000000-0000-0000-0000-000000000001"
+        }"#;
+        let order = handle!(serde_json::from_str::<ClobSignedOrder>(input), DeserializeFailed);
+        let owner = handle_opt!(order.owner, OwnerNotFound);
+        let order_round_trip = ClobSignedOrder::from(order.clone().into_signed_order(owner));
+        assert_eq!(order, order_round_trip);
+        let value = handle!(serde_json::to_value(&order_round_trip), SerializeFailed);
+        let value_expected = handle!(serde_json::from_str::<Value>(input), DeserializeFailed);
+        assert_eq!(value.get("makerAmount"), value_expected.get("makerAmount"));
+        assert_eq!(value.get("orderType"), value_expected.get("orderType"));
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum ClobSignedOrderTestError {
+        #[error("failed to deserialize signed order")]
+        DeserializeFailed { source: serde_json::Error },
+        #[error("failed to serialize signed order")]
+        SerializeFailed { source: serde_json::Error },
+        #[error("owner not found")]
+        OwnerNotFound,
+    }
+}
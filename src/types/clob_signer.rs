@@ -0,0 +1,66 @@
+use crate::RemoteSigner;
+use alloy::dyn_abi::TypedData;
+use alloy::primitives::{Address, B256, ChainId, Signature};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::{Result as SignerResult, Signer};
+use alloy::sol_types::{Eip712Domain, SolStruct};
+use async_trait::async_trait;
+use derive_more::From;
+
+/// The signer that is used to authenticate the CLOB client and to sign the orders
+///
+/// The local variant covers the mnemonic, private key and keystore sources (all of them produce a [`PrivateKeySigner`]).
+#[derive(From, Clone, Debug)]
+pub enum ClobSigner {
+    Local(PrivateKeySigner),
+    Remote(RemoteSigner),
+}
+
+#[async_trait]
+impl Signer for ClobSigner {
+    async fn sign_hash(&self, hash: &B256) -> SignerResult<Signature> {
+        match self {
+            ClobSigner::Local(signer) => signer.sign_hash(hash).await,
+            ClobSigner::Remote(signer) => signer.sign_hash(hash).await,
+        }
+    }
+
+    /// Delegates explicitly, because the default implementation signs the hash (so the remote signer could not inspect the order)
+    async fn sign_typed_data<T: SolStruct + Send + Sync>(&self, payload: &T, domain: &Eip712Domain) -> SignerResult<Signature>
+    where
+        Self: Sized,
+    {
+        match self {
+            ClobSigner::Local(signer) => signer.sign_typed_data(payload, domain).await,
+            ClobSigner::Remote(signer) => signer.sign_typed_data(payload, domain).await,
+        }
+    }
+
+    async fn sign_dynamic_typed_data(&self, payload: &TypedData) -> SignerResult<Signature> {
+        match self {
+            ClobSigner::Local(signer) => signer.sign_dynamic_typed_data(payload).await,
+            ClobSigner::Remote(signer) => signer.sign_dynamic_typed_data(payload).await,
+        }
+    }
+
+    fn address(&self) -> Address {
+        match self {
+            ClobSigner::Local(signer) => signer.address(),
+            ClobSigner::Remote(signer) => signer.address(),
+        }
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        match self {
+            ClobSigner::Local(signer) => signer.chain_id(),
+            ClobSigner::Remote(signer) => signer.chain_id(),
+        }
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        match self {
+            ClobSigner::Local(signer) => signer.set_chain_id(chain_id),
+            ClobSigner::Remote(signer) => signer.set_chain_id(chain_id),
+        }
+    }
+}
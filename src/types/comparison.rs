@@ -0,0 +1,29 @@
+use strum::{Display, EnumString};
+
+#[derive(Display, EnumString, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, Debug)]
+pub enum Comparison {
+    #[strum(serialize = "<")]
+    Lt,
+    #[strum(serialize = "<=")]
+    Le,
+    #[strum(serialize = "=")]
+    Eq,
+    #[strum(serialize = ">=")]
+    Ge,
+    #[strum(serialize = ">")]
+    Gt,
+}
+
+impl Comparison {
+    /// Returns true if `left <op> right`
+    pub fn holds<T: PartialOrd>(self, left: &T, right: &T) -> bool {
+        use Comparison::*;
+        match self {
+            Lt => left < right,
+            Le => left <= right,
+            Eq => left == right,
+            Ge => left >= right,
+            Gt => left > right,
+        }
+    }
+}
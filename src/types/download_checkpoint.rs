@@ -0,0 +1,27 @@
+use crate::{NEXT_CURSOR_STOP, NextCursor, RkyvOffsetDateTime};
+use derive_new::new;
+use time::OffsetDateTime;
+
+/// The last committed position of a paginated download
+///
+/// The checkpoint is written in the same transaction as the page data, so it always points right after the last persisted page.
+#[derive(new, serde::Serialize, serde::Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize, Eq, PartialEq, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DownloadCheckpoint {
+    /// The cursor returned with the last committed page (only for cursor-paginated endpoints)
+    pub next_cursor: Option<NextCursor>,
+    /// The number of items received from the endpoint, including the items that were filtered out before writing
+    pub offset: u64,
+    /// The number of committed pages
+    pub page_count: u64,
+    #[rkyv(with = RkyvOffsetDateTime)]
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl DownloadCheckpoint {
+    /// Returns true if the endpoint has signaled that there are no more pages after this checkpoint
+    pub fn is_final(&self) -> bool {
+        self.next_cursor.as_deref() == Some(NEXT_CURSOR_STOP)
+    }
+}
@@ -0,0 +1,16 @@
+use strum::{Display, IntoStaticStr};
+
+/// A paginated endpoint that is downloaded by [`CacheDownloadCommand`](crate::CacheDownloadCommand)
+///
+/// The variant name is used as a key in the [`DOWNLOAD_CHECKPOINTS_KEYSPACE`](crate::DOWNLOAD_CHECKPOINTS_KEYSPACE)
+#[derive(Display, IntoStaticStr, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub enum DownloadEndpoint {
+    ClobMarkets,
+    GammaEvents,
+}
+
+impl DownloadEndpoint {
+    pub fn key(self) -> &'static str {
+        self.into()
+    }
+}
@@ -1,5 +1,16 @@
+use core::error::Error as StdError;
+use core::iter::Peekable;
+use core::str::{CharIndices, FromStr};
+use errgonomic::handle_opt;
 use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use thiserror::Error;
 
+/// A boolean expression over variables
+///
+/// The text syntax is `!a`, `a & b`, `a | b` and parentheses (`!` binds tighter than `&`, which binds tighter than `|`)
+///
+/// A variable is a sequence of characters other than whitespace, `!`, `&`, `|` and parentheses, which is parsed by [`FromStr`] of `V` (a double-quoted part of a variable may contain any characters except the double quote, e.g. `question:"will it rain"`)
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
 pub enum Expr<V> {
     Var(V),
@@ -7,3 +18,241 @@ pub enum Expr<V> {
     Or(Box<Self>, Box<Self>),
     Not(Box<Self>),
 }
+
+impl<V> Expr<V> {
+    /// Evaluates the expression with the values of the variables that are returned by `value_of`
+    pub fn eval(&self, value_of: &mut impl FnMut(&V) -> bool) -> bool {
+        use Expr::*;
+        match self {
+            Var(var) => value_of(var),
+            And(left, right) => {
+                let left = left.eval(value_of);
+                let right = right.eval(value_of);
+                left && right
+            }
+            Or(left, right) => {
+                let left = left.eval(value_of);
+                let right = right.eval(value_of);
+                left || right
+            }
+            Not(inner) => !inner.eval(value_of),
+        }
+    }
+
+    /// Returns the variables in the order of their occurrence (a variable is returned once for every occurrence)
+    pub fn vars(&self) -> Vec<&V> {
+        use Expr::*;
+        match self {
+            Var(var) => vec![var],
+            And(left, right) | Or(left, right) => {
+                let mut vars = left.vars();
+                vars.extend(right.vars());
+                vars
+            }
+            Not(inner) => inner.vars(),
+        }
+    }
+
+    /// Writes the operand of `&` or `!` (wraps the operators with lower precedence in parentheses)
+    fn fmt_operand(&self, f: &mut Formatter<'_>, wrap_and: bool) -> fmt::Result
+    where
+        V: Display,
+    {
+        use Expr::*;
+        match self {
+            Or(_, _) => write!(f, "({self})"),
+            And(_, _) if wrap_and => write!(f, "({self})"),
+            _ => write!(f, "{self}"),
+        }
+    }
+}
+
+impl<V: Display> Display for Expr<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use Expr::*;
+        match self {
+            Var(var) => write!(f, "{var}"),
+            And(left, right) => {
+                left.fmt_operand(f, false)?;
+                f.write_str(" & ")?;
+                right.fmt_operand(f, false)
+            }
+            Or(left, right) => write!(f, "{left} | {right}"),
+            Not(inner) => {
+                f.write_str("!")?;
+                inner.fmt_operand(f, true)
+            }
+        }
+    }
+}
+
+impl<V> FromStr for Expr<V>
+where
+    V: FromStr,
+    V::Err: StdError + 'static,
+{
+    type Err = ExprParseError<V::Err>;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        use ExprParseError::*;
+        let mut chars = input.char_indices().peekable();
+        let expr = parse_or(&mut chars)?;
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            Some((offset, char)) => Err(UnexpectedChar {
+                char,
+                offset,
+            }),
+            None => Ok(expr),
+        }
+    }
+}
+
+type Chars<'a> = Peekable<CharIndices<'a>>;
+
+fn parse_or<V: FromStr>(chars: &mut Chars) -> Result<Expr<V>, ExprParseError<V::Err>> {
+    let mut expr = parse_and(chars)?;
+    while next_if_char(chars, '|') {
+        let right = parse_and(chars)?;
+        expr = Expr::Or(Box::new(expr), Box::new(right));
+    }
+    Ok(expr)
+}
+
+fn parse_and<V: FromStr>(chars: &mut Chars) -> Result<Expr<V>, ExprParseError<V::Err>> {
+    let mut expr = parse_unary(chars)?;
+    while next_if_char(chars, '&') {
+        let right = parse_unary(chars)?;
+        expr = Expr::And(Box::new(expr), Box::new(right));
+    }
+    Ok(expr)
+}
+
+fn parse_unary<V: FromStr>(chars: &mut Chars) -> Result<Expr<V>, ExprParseError<V::Err>> {
+    use ExprParseError::*;
+    if next_if_char(chars, '!') {
+        let inner = parse_unary(chars)?;
+        return Ok(Expr::Not(Box::new(inner)));
+    }
+    if next_if_char(chars, '(') {
+        let expr = parse_or(chars)?;
+        skip_whitespace(chars);
+        let (offset, char) = handle_opt!(chars.next(), UnexpectedEnd);
+        return match char {
+            ')' => Ok(expr),
+            char => Err(UnexpectedChar {
+                char,
+                offset,
+            }),
+        };
+    }
+    let (offset, char) = handle_opt!(chars.peek().copied(), UnexpectedEnd);
+    let var = parse_var_str(chars)?;
+    if var.is_empty() {
+        return Err(UnexpectedChar {
+            char,
+            offset,
+        });
+    }
+    match var.parse::<V>() {
+        Ok(value) => Ok(Expr::Var(value)),
+        Err(source) => Err(ParseVarFailed {
+            source,
+            var,
+        }),
+    }
+}
+
+/// Returns the text of the variable (including the double quotes)
+fn parse_var_str<E>(chars: &mut Chars) -> Result<String, ExprParseError<E>> {
+    use ExprParseError::*;
+    let mut var = String::new();
+    while let Some((_, char)) = chars.next_if(|(_, char)| is_var_char(*char)) {
+        var.push(char);
+        if char == '"' {
+            loop {
+                let (_, char) = handle_opt!(chars.next(), UnexpectedEnd);
+                var.push(char);
+                if char == '"' {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(var)
+}
+
+/// Skips the whitespace, then consumes the next char if it is equal to `expected`
+fn next_if_char(chars: &mut Chars, expected: char) -> bool {
+    skip_whitespace(chars);
+    chars.next_if(|(_, char)| *char == expected).is_some()
+}
+
+fn skip_whitespace(chars: &mut Chars) {
+    while chars.next_if(|(_, char)| char.is_whitespace()).is_some() {}
+}
+
+fn is_var_char(char: char) -> bool {
+    !char.is_whitespace() && !matches!(char, '!' | '&' | '|' | '(' | ')')
+}
+
+#[derive(Error, Debug)]
+pub enum ExprParseError<E> {
+    #[error("unexpected character '{char}' at offset {offset}")]
+    UnexpectedChar { char: char, offset: usize },
+    #[error("unexpected end of expression")]
+    UnexpectedEnd,
+    #[error("failed to parse variable '{var}'")]
+    ParseVarFailed { source: E, var: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PropertyName;
+    use Expr::*;
+    use core::convert::Infallible;
+
+    fn var(name: &str) -> Box<Expr<PropertyName>> {
+        Box::new(Var(name.to_string()))
+    }
+
+    #[test]
+    fn must_parse_with_precedence() -> Result<(), ExprParseError<Infallible>> {
+        let expr = "a | !b & (c | d)".parse::<Expr<PropertyName>>()?;
+        let expected = Or(var("a"), Box::new(And(Box::new(Not(var("b"))), Box::new(Or(var("c"), var("d"))))));
+        assert_eq!(expr, expected);
+        assert_eq!(expr.to_string(), "a | !b & (c | d)");
+        Ok(())
+    }
+
+    #[test]
+    fn must_eval() -> Result<(), ExprParseError<Infallible>> {
+        let expr = "active_xor_closed & !tokens_len_is_two".parse::<Expr<PropertyName>>()?;
+        let mut value_of = |var: &PropertyName| var == "active_xor_closed";
+        assert!(expr.eval(&mut value_of));
+        Ok(())
+    }
+
+    #[test]
+    fn must_parse_quoted_var() -> Result<(), ExprParseError<Infallible>> {
+        let expr = r#"question:"rain & snow" & !closed"#.parse::<Expr<PropertyName>>()?;
+        let expected = And(var(r#"question:"rain & snow""#), Box::new(Not(var("closed"))));
+        assert_eq!(expr, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn must_reject_invalid_input() {
+        assert!(matches!("a &".parse::<Expr<PropertyName>>(), Err(ExprParseError::UnexpectedEnd)));
+        assert!(matches!("(a | b".parse::<Expr<PropertyName>>(), Err(ExprParseError::UnexpectedEnd)));
+        assert!(matches!(
+            "a b".parse::<Expr<PropertyName>>(),
+            Err(ExprParseError::UnexpectedChar {
+                char: 'b',
+                offset: 2
+            })
+        ));
+        assert!(matches!(r#"question:"rain"#.parse::<Expr<PropertyName>>(), Err(ExprParseError::UnexpectedEnd)));
+    }
+}
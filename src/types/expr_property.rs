@@ -0,0 +1,94 @@
+use crate::{Expr, Property, PropertyContext, PropertyContextReadError, PropertyFactory, PropertyName, property_name_matches};
+use errgonomic::handle_opt;
+use thiserror::Error;
+
+/// A property that is derived from the registered properties by an [`Expr`] (e.g. `active_xor_closed & !tokens_len_is_two`)
+///
+/// Every variable is evaluated for every value (even if the result of the expression is already known), so the stateful properties see the same values as in a regular check.
+pub struct ExprProperty<T> {
+    expr: Expr<PropertyName>,
+    vars: Vec<(PropertyName, Box<dyn Property<T>>)>,
+}
+
+impl<T> ExprProperty<T> {
+    /// Returns an error with the first variable that doesn't match any property of `factories`
+    pub fn try_new(expr: &Expr<PropertyName>, factories: &[PropertyFactory<T>]) -> Result<Self, ExprPropertyTryNewError> {
+        use ExprPropertyTryNewError::*;
+        let mut vars: Vec<(PropertyName, Box<dyn Property<T>>)> = Vec::new();
+        for var in expr.vars() {
+            if vars.iter().any(|(name, _property)| name == var) {
+                continue;
+            }
+            let property = handle_opt!(
+                factories
+                    .iter()
+                    .map(|factory| factory())
+                    .find(|property| property_name_matches(&property.name(), var)),
+                VarNotFound,
+                var: var.clone()
+            );
+            vars.push((var.clone(), property));
+        }
+        Ok(Self {
+            expr: expr.clone(),
+            vars,
+        })
+    }
+
+    /// Returns the names of the registered properties that are referenced by the variables
+    pub fn property_names(&self) -> impl Iterator<Item = PropertyName> + '_ {
+        self.vars.iter().map(|(_var, property)| property.name())
+    }
+}
+
+impl<T> Property<T> for ExprProperty<T> {
+    fn name(&self) -> PropertyName {
+        self.expr.to_string()
+    }
+
+    /// An expression property can't be created by a [`PropertyFactory`], so it can't be evaluated by the parallel workers
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn holds(&mut self, value: &T, context: &PropertyContext) -> Result<bool, PropertyContextReadError> {
+        // every variable is evaluated before the first error is returned
+        let outcomes = self
+            .vars
+            .iter_mut()
+            .map(|(_name, property)| property.holds(value, context))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let vars = &self.vars;
+        let holds = self.expr.eval(&mut |var| {
+            vars.iter()
+                .position(|(name, _property)| name == var)
+                .and_then(|index| outcomes.get(index))
+                .copied()
+                .unwrap_or_default()
+        });
+        Ok(holds)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ExprPropertyTryNewError {
+    #[error("variable '{var}' doesn't match any property")]
+    VarNotFound { var: PropertyName },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExprParseError, MARKET_RESPONSE_PROPERTIES};
+    use core::convert::Infallible;
+
+    #[test]
+    fn must_return_unmatched_var() -> Result<(), ExprParseError<Infallible>> {
+        let expr = "active_xor_closed & !unknown_property".parse::<Expr<PropertyName>>()?;
+        let result = ExprProperty::try_new(&expr, &MARKET_RESPONSE_PROPERTIES);
+        assert!(matches!(result, Err(ExprPropertyTryNewError::VarNotFound { var }) if var == "unknown_property"));
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{ConvertGammaMarketRawToGammaMarketError, GammaMarket, GammaMarketIsInvertedPricingError, TimeSpreadArbitrageOpportunity, are_questions_date_cascade, gamma_event_raw_is_fresh};
+use crate::{CacheEntity, ConvertGammaMarketRawToGammaMarketError, GAMMA_EVENTS_KEYSPACE, GammaMarket, GammaMarketIsInvertedPricingError, TimeSpreadArbitrageOpportunity, are_questions_date_cascade, gamma_event_raw_is_fresh};
 use core::num::ParseIntError;
 use derive_more::{From, Into};
 use errgonomic::{ErrVec, handle_bool, handle_iter, partition_result};
@@ -32,14 +32,14 @@ pub fn is_date_cascade(markets: &[GammaMarket]) -> Option<bool> {
 }
 
 impl GammaEvent {
-    pub fn api_url(&self) -> String {
-        format!("https://gamma-api.polymarket.com/events/slug/{}", self.slug)
+    pub fn api_url(&self, gamma_host: &str) -> String {
+        format!("{}/events/slug/{}", gamma_host.trim_end_matches('/'), self.slug)
     }
 
     /// This function may return multiple opportunities because multiple adjacent markets may exhibit inverted pricing.
     ///
     /// Returns all adjacent market pairs where earlier-date YES is priced above later-date YES.
-    pub fn get_time_spread_arbitrage_opportunities(&self) -> Result<Vec<TimeSpreadArbitrageOpportunity<'_>>, GammaEventGetTimeSpreadArbitrageOpportunitiesError> {
+    pub fn get_time_spread_arbitrage_opportunities(&self, gamma_host: &str) -> Result<Vec<TimeSpreadArbitrageOpportunity<'_>>, GammaEventGetTimeSpreadArbitrageOpportunitiesError> {
         use GammaEventGetTimeSpreadArbitrageOpportunitiesError::*;
         use itertools::Itertools;
         if !self.is_date_cascade.unwrap_or_default() {
@@ -55,7 +55,7 @@ impl GammaEvent {
                         is_inverted.and_then(|is_inverted| {
                             if is_inverted {
                                 Some(TimeSpreadArbitrageOpportunity {
-                                    event_api_url: self.api_url(),
+                                    event_api_url: self.api_url(gamma_host),
                                     prev,
                                     next,
                                 })
@@ -74,6 +74,15 @@ impl GammaEvent {
     }
 }
 
+/// Events are keyed by slug (the slug is also used by [`GammaEvent::api_url`])
+impl CacheEntity for GammaEvent {
+    const KEYSPACE: &'static str = GAMMA_EVENTS_KEYSPACE;
+
+    fn key(&self) -> String {
+        self.slug.clone()
+    }
+}
+
 impl TryFrom<GammaEventRaw> for GammaEvent {
     type Error = ConvertGammaEventRawToGammaEventError;
 
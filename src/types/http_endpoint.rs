@@ -0,0 +1,10 @@
+use strum::Display;
+
+/// The HTTP APIs that have separate rate limits (see [`HttpMiddleware`](crate::HttpMiddleware))
+#[derive(Display, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[strum(serialize_all = "snake_case")]
+pub enum HttpEndpoint {
+    Clob,
+    Gamma,
+    Opinion,
+}
@@ -0,0 +1,288 @@
+use crate::{DEFAULT_CLOB_REQUESTS_PER_SECOND, DEFAULT_GAMMA_REQUESTS_PER_SECOND, DEFAULT_HTTP_BURST, DEFAULT_HTTP_RETRY_BUDGET, DEFAULT_OPINION_REQUESTS_PER_SECOND, HttpEndpoint, RetryDecision, RetryPolicy, Retryable, TokenBucket, random_u64};
+use core::error::Error as StdError;
+use core::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use thiserror::Error;
+use tokio::time::sleep;
+
+/// Rate limits, retries and backoff for the HTTP requests
+///
+/// The clones share the rate limits and the retry budget, so a single middleware should be used for all requests of a command.
+#[derive(Clone, Debug)]
+pub struct HttpMiddleware {
+    pub policy: RetryPolicy,
+    /// The number of retries that are left for all requests (a long download shouldn't retry forever if the API is down)
+    retry_budget: Arc<AtomicU32>,
+    clob_bucket: Arc<TokenBucket>,
+    gamma_bucket: Arc<TokenBucket>,
+    opinion_bucket: Arc<TokenBucket>,
+}
+
+impl HttpMiddleware {
+    pub fn new(policy: RetryPolicy, retry_budget: u32, clob_bucket: TokenBucket, gamma_bucket: TokenBucket, opinion_bucket: TokenBucket) -> Self {
+        Self {
+            policy,
+            retry_budget: Arc::new(AtomicU32::new(retry_budget)),
+            clob_bucket: Arc::new(clob_bucket),
+            gamma_bucket: Arc::new(gamma_bucket),
+            opinion_bucket: Arc::new(opinion_bucket),
+        }
+    }
+
+    pub fn bucket(&self, endpoint: HttpEndpoint) -> &TokenBucket {
+        use HttpEndpoint::*;
+        match endpoint {
+            Clob => &self.clob_bucket,
+            Gamma => &self.gamma_bucket,
+            Opinion => &self.opinion_bucket,
+        }
+    }
+
+    pub fn retry_budget(&self) -> u32 {
+        self.retry_budget.load(Ordering::Relaxed)
+    }
+
+    /// Sends the request (by calling `request`) after the rate limiter of the `endpoint` allows it, and retries the transient errors with exponential backoff
+    ///
+    /// The `Retry-After` delay takes precedence over the backoff (see [`RetryDecision::RetryAfter`]), but it is capped by [`RetryPolicy::max_backoff`], so that a server can't stall the client indefinitely.
+    pub async fn call<T, E, F, Fut>(&self, endpoint: HttpEndpoint, mut request: F) -> Result<T, HttpMiddlewareCallError<E>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Retryable + StdError + Send + Sync + 'static,
+    {
+        use HttpMiddlewareCallError::*;
+        let mut attempt = 0u32;
+        loop {
+            self.bucket(endpoint).acquire().await;
+            let source = match request().await {
+                Ok(output) => return Ok(output),
+                Err(source) => source,
+            };
+            let attempts = attempt.saturating_add(1);
+            let delay = match source.retry_decision() {
+                RetryDecision::Fail => {
+                    return Err(RequestFailed {
+                        source,
+                        endpoint,
+                        attempts,
+                    });
+                }
+                RetryDecision::Retry => self.policy.backoff(attempt, random_u64()),
+                RetryDecision::RetryAfter(delay) => delay.min(self.policy.max_backoff),
+            };
+            if attempt >= self.policy.max_retries {
+                return Err(RetriesExhausted {
+                    source,
+                    endpoint,
+                    attempts,
+                });
+            }
+            if !self.take_retry() {
+                return Err(RetryBudgetExhausted {
+                    source,
+                    endpoint,
+                    attempts,
+                });
+            }
+            sleep(delay).await;
+            attempt = attempts;
+        }
+    }
+
+    fn take_retry(&self) -> bool {
+        self.retry_budget
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |budget| budget.checked_sub(1))
+            .is_ok()
+    }
+}
+
+impl Default for HttpMiddleware {
+    fn default() -> Self {
+        Self::new(RetryPolicy::default(), DEFAULT_HTTP_RETRY_BUDGET, TokenBucket::per_second(DEFAULT_CLOB_REQUESTS_PER_SECOND, DEFAULT_HTTP_BURST), TokenBucket::per_second(DEFAULT_GAMMA_REQUESTS_PER_SECOND, DEFAULT_HTTP_BURST), TokenBucket::per_second(DEFAULT_OPINION_REQUESTS_PER_SECOND, DEFAULT_HTTP_BURST))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum HttpMiddlewareCallError<E>
+where
+    E: StdError + Send + Sync + 'static,
+{
+    #[error("request to {endpoint} failed with a permanent error after {attempts} attempts")]
+    RequestFailed { source: E, endpoint: HttpEndpoint, attempts: u32 },
+    #[error("request to {endpoint} failed after {attempts} attempts")]
+    RetriesExhausted { source: E, endpoint: HttpEndpoint, attempts: u32 },
+    #[error("request to {endpoint} failed after {attempts} attempts, and the retry budget is exhausted")]
+    RetryBudgetExhausted { source: E, endpoint: HttpEndpoint, attempts: u32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClobClient, ClobClientMarketsError, MOCK_CLOB_MARKETS_FIRST_PAGE, MockHttpResponse, MockHttpServer, MockHttpServerStartError, SendCheckedError, send_checked};
+    use core::time::Duration;
+    use errgonomic::handle;
+    use polymarket_client_sdk::clob::{Client as PolymarketClobClient, Config as PolymarketClobConfig};
+    use polymarket_client_sdk::error::Error as PolymarketError;
+    use polymarket_client_sdk::gamma::Client as GammaClient;
+    use polymarket_client_sdk::gamma::types::request::EventsRequest;
+    use reqwest::{Client, StatusCode};
+    use tokio::time::error::Elapsed;
+    use tokio::time::timeout;
+
+    fn middleware(retry_budget: u32) -> HttpMiddleware {
+        let policy = RetryPolicy::new(3, Duration::from_millis(1), Duration::from_millis(10));
+        let bucket = || TokenBucket::new(Duration::ZERO, 1);
+        HttpMiddleware::new(policy, retry_budget, bucket(), bucket(), bucket())
+    }
+
+    async fn get(middleware: &HttpMiddleware, server: &MockHttpServer) -> Result<StatusCode, HttpMiddlewareCallError<SendCheckedError>> {
+        let client = Client::new();
+        let response = middleware
+            .call(HttpEndpoint::Opinion, || send_checked(client.get(server.url.as_str())))
+            .await?;
+        Ok(response.status())
+    }
+
+    #[tokio::test]
+    async fn must_retry_transient_errors() -> Result<(), MustRetryError> {
+        use MustRetryError::*;
+        let responses = vec![
+            MockHttpResponse::new(429, "").with_header("Retry-After", "0"),
+            MockHttpResponse::new(503, ""),
+            MockHttpResponse::new(200, "ok"),
+        ];
+        let server = handle!(MockHttpServer::start(responses).await, StartFailed);
+        let middleware = middleware(10);
+        let status = handle!(get(&middleware, &server).await, GetFailed);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(server.request_count(), 3);
+        assert_eq!(middleware.retry_budget(), 8);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_not_retry_permanent_errors() -> Result<(), MustRetryError> {
+        use MustRetryError::*;
+        let server = handle!(MockHttpServer::start(vec![MockHttpResponse::new(404, "")]).await, StartFailed);
+        let result = get(&middleware(10), &server).await;
+        assert!(matches!(
+            result,
+            Err(HttpMiddlewareCallError::RequestFailed {
+                attempts: 1,
+                ..
+            })
+        ));
+        assert_eq!(server.request_count(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_stop_after_max_retries() -> Result<(), MustRetryError> {
+        use MustRetryError::*;
+        let server = handle!(MockHttpServer::start(vec![MockHttpResponse::new(500, "")]).await, StartFailed);
+        let result = get(&middleware(10), &server).await;
+        assert!(matches!(
+            result,
+            Err(HttpMiddlewareCallError::RetriesExhausted {
+                attempts: 4,
+                ..
+            })
+        ));
+        assert_eq!(server.request_count(), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_stop_when_retry_budget_is_exhausted() -> Result<(), MustRetryError> {
+        use MustRetryError::*;
+        let server = handle!(MockHttpServer::start(vec![MockHttpResponse::new(502, "")]).await, StartFailed);
+        let result = get(&middleware(1), &server).await;
+        assert!(matches!(
+            result,
+            Err(HttpMiddlewareCallError::RetryBudgetExhausted {
+                attempts: 2,
+                ..
+            })
+        ));
+        assert_eq!(server.request_count(), 2);
+        Ok(())
+    }
+
+    /// The transient responses of the mock server before it succeeds
+    fn transient_responses(body: &str) -> Vec<MockHttpResponse> {
+        vec![
+            MockHttpResponse::new(429, "").with_header("Retry-After", "0"),
+            MockHttpResponse::new(503, ""),
+            MockHttpResponse::new(200, body),
+        ]
+    }
+
+    #[tokio::test]
+    async fn must_retry_clob_status_errors() -> Result<(), MustRetryError> {
+        use MustRetryError::*;
+        let server = handle!(MockHttpServer::start(transient_responses(MOCK_CLOB_MARKETS_FIRST_PAGE)).await, StartFailed);
+        let inner = handle!(PolymarketClobClient::new(&server.url, PolymarketClobConfig::default()), ClientNewFailed);
+        let client = ClobClient {
+            inner,
+            middleware: middleware(10),
+        };
+        let page = handle!(client.markets(None).await, MarketsFailed);
+        assert!(!page.data.is_empty());
+        assert_eq!(server.request_count(), 3);
+        assert_eq!(client.middleware.retry_budget(), 8);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_retry_gamma_status_errors() -> Result<(), MustRetryError> {
+        use MustRetryError::*;
+        let server = handle!(MockHttpServer::start(transient_responses("[]")).await, StartFailed);
+        let client = handle!(GammaClient::new(&server.url), ClientNewFailed);
+        let middleware = middleware(10);
+        let request = EventsRequest::builder().build();
+        let events = handle!(
+            middleware
+                .call(HttpEndpoint::Gamma, || client.events(&request))
+                .await,
+            EventsFailed
+        );
+        assert!(events.is_empty());
+        assert_eq!(server.request_count(), 3);
+        assert_eq!(middleware.retry_budget(), 8);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_cap_retry_after() -> Result<(), MustRetryError> {
+        use MustRetryError::*;
+        let responses = vec![
+            MockHttpResponse::new(429, "").with_header("Retry-After", "3600"),
+            MockHttpResponse::new(200, "ok"),
+        ];
+        let server = handle!(MockHttpServer::start(responses).await, StartFailed);
+        // the delay is capped by the max backoff of the policy (10 ms)
+        let result = handle!(timeout(Duration::from_secs(5), get(&middleware(10), &server)).await, TimeoutFailed);
+        let status = handle!(result, GetFailed);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(server.request_count(), 2);
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum MustRetryError {
+        #[error("failed to start mock server")]
+        StartFailed { source: MockHttpServerStartError },
+        #[error("failed to get mock response")]
+        GetFailed { source: HttpMiddlewareCallError<SendCheckedError> },
+        #[error("failed to create client")]
+        ClientNewFailed { source: PolymarketError },
+        #[error("failed to fetch markets")]
+        MarketsFailed { source: ClobClientMarketsError },
+        #[error("failed to fetch events")]
+        EventsFailed { source: HttpMiddlewareCallError<PolymarketError> },
+        #[error("request has not completed in time")]
+        TimeoutFailed { source: Elapsed },
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{MarketResponseLiteral, TableRow};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+/// A candidate implication `antecedent => consequent` with the number of markets that satisfy the antecedent (`support`) and the number of markets that violate the implication
+#[derive(Serialize, Eq, PartialEq, Clone, Debug)]
+pub struct Implication {
+    pub antecedent: MarketResponseLiteral,
+    pub consequent: MarketResponseLiteral,
+    pub support: usize,
+    pub violations: usize,
+    /// The share of the supporting markets that satisfy the consequent
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub confidence: Option<Decimal>,
+    /// The slugs of the markets that violate the implication
+    pub counterexamples: Vec<String>,
+}
+
+impl Implication {
+    pub fn new(antecedent: MarketResponseLiteral, consequent: MarketResponseLiteral) -> Self {
+        Self {
+            antecedent,
+            consequent,
+            support: 0,
+            violations: 0,
+            confidence: None,
+            counterexamples: Vec::new(),
+        }
+    }
+}
+
+impl TableRow for Implication {
+    fn columns(&self) -> Vec<&'static str> {
+        vec![
+            "antecedent",
+            "consequent",
+            "support",
+            "violations",
+            "confidence",
+            "counterexamples",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let confidence = self
+            .confidence
+            .map(|confidence| confidence.round_dp(4).to_string())
+            .unwrap_or_default();
+        vec![
+            self.antecedent.to_string(),
+            self.consequent.to_string(),
+            self.support.to_string(),
+            self.violations.to_string(),
+            confidence,
+            self.counterexamples.join(", "),
+        ]
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{ClobMarketResponsePrecise, Implication, MarketResponseFeature, MarketResponseLiteral};
+use itertools::Itertools;
+use rust_decimal::Decimal;
+use strum::IntoEnumIterator;
+
+/// Tests every implication between the literals of two different [`MarketResponseFeature`]s
+///
+/// Both directions are tested (e.g. `closed => archived` and `archived => closed`), because an implication and its converse have different support and confidence.
+#[derive(Clone, Debug)]
+pub struct ImplicationMiner {
+    pub example_limit: usize,
+    pub market_count: usize,
+    /// The number of markets for which the feature is true (indexed by the feature)
+    pub feature_counts: Vec<usize>,
+    pub implications: Vec<Implication>,
+}
+
+impl ImplicationMiner {
+    pub fn new(example_limit: usize) -> Self {
+        let literals = MarketResponseFeature::iter()
+            .flat_map(|feature| [true, false].map(|value| MarketResponseLiteral::new(feature, value)))
+            .collect::<Vec<_>>();
+        let implications = literals
+            .iter()
+            .cartesian_product(literals.iter())
+            .filter(|(antecedent, consequent)| antecedent.feature != consequent.feature)
+            .map(|(antecedent, consequent)| Implication::new(*antecedent, *consequent))
+            .collect();
+        Self {
+            example_limit,
+            market_count: 0,
+            feature_counts: vec![0; MarketResponseFeature::iter().len()],
+            implications,
+        }
+    }
+
+    pub fn observe(&mut self, market: &ClobMarketResponsePrecise) {
+        let values = MarketResponseFeature::iter()
+            .map(|feature| feature.value(market))
+            .collect::<Vec<_>>();
+        self.market_count = self.market_count.saturating_add(1);
+        self.feature_counts
+            .iter_mut()
+            .zip(&values)
+            .filter(|(_count, value)| **value)
+            .for_each(|(count, _value)| *count = count.saturating_add(1));
+        for implication in &mut self.implications {
+            if !implication.antecedent.holds(&values) {
+                continue;
+            }
+            implication.support = implication.support.saturating_add(1);
+            if !implication.consequent.holds(&values) {
+                implication.violations = implication.violations.saturating_add(1);
+                if implication.counterexamples.len() < self.example_limit {
+                    implication.counterexamples.push(market.market_slug.clone())
+                }
+            }
+        }
+    }
+
+    /// Returns the implications that have at least `min_support` supporting markets and at least `min_confidence`, sorted by confidence and support (descending)
+    ///
+    /// The implications whose consequent holds for every market are skipped, because they are implied by any antecedent.
+    pub fn into_implications(self, min_support: usize, min_confidence: Decimal) -> Vec<Implication> {
+        let Self {
+            market_count,
+            feature_counts,
+            implications,
+            ..
+        } = self;
+        let is_constant = |literal: &MarketResponseLiteral| {
+            let count = feature_counts
+                .get(literal.feature as usize)
+                .copied()
+                .unwrap_or_default();
+            if literal.value { count == market_count } else { count == 0 }
+        };
+        implications
+            .into_iter()
+            .filter(|implication| implication.support >= min_support.max(1) && !is_constant(&implication.consequent))
+            .map(|mut implication| {
+                let holds = implication.support.saturating_sub(implication.violations);
+                implication.confidence = Decimal::from(holds).checked_div(Decimal::from(implication.support));
+                implication
+            })
+            .filter(|implication| implication.confidence >= Some(min_confidence))
+            .sorted_by(|left, right| {
+                right
+                    .confidence
+                    .cmp(&left.confidence)
+                    .then(right.support.cmp(&left.support))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureMarketResponseError, fixture_market_response};
+    use MarketResponseFeature::*;
+    use errgonomic::handle;
+    use thiserror::Error;
+
+    #[test]
+    fn must_mine_implications() -> Result<(), MustMineImplicationsError> {
+        use MustMineImplicationsError::*;
+        let market = handle!(fixture_market_response(), FixtureMarketResponseFailed);
+        let mut counterexample = market.clone();
+        counterexample.market_slug = "counterexample".to_string();
+        counterexample.closed = !market.closed;
+        counterexample.archived = !market.archived;
+        let mut miner = ImplicationMiner::new(3);
+        (0..9).for_each(|_| miner.observe(&market));
+        miner.observe(&counterexample);
+        let implications = miner.into_implications(2, Decimal::new(9, 1));
+        let find = |antecedent: MarketResponseLiteral, consequent: MarketResponseLiteral| {
+            implications
+                .iter()
+                .find(|implication| implication.antecedent == antecedent && implication.consequent == consequent)
+        };
+        let closed_implies_archived = find(MarketResponseLiteral::new(Closed, market.closed), MarketResponseLiteral::new(Archived, market.archived));
+        assert_eq!(closed_implies_archived.map(|implication| (implication.support, implication.violations)), Some((9, 0)));
+        // the converse is tested too, although the antecedent feature follows the consequent feature
+        let archived_implies_closed = find(MarketResponseLiteral::new(Archived, market.archived), MarketResponseLiteral::new(Closed, market.closed));
+        assert_eq!(archived_implies_closed.map(|implication| (implication.support, implication.violations)), Some((9, 0)));
+        let active_implies_closed = find(MarketResponseLiteral::new(Active, market.active), MarketResponseLiteral::new(Closed, market.closed));
+        assert_eq!(active_implies_closed.map(|implication| implication.counterexamples.clone()), Some(vec!["counterexample".to_string()]));
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum MustMineImplicationsError {
+        #[error("failed to load market fixture")]
+        FixtureMarketResponseFailed { source: FixtureMarketResponseError },
+    }
+}
@@ -0,0 +1,14 @@
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+/// The outcome of rewriting the keys of a single keyspace (see [`CacheMigrateCommand`](crate::CacheMigrateCommand))
+#[derive(new, Serialize, Deserialize, Eq, PartialEq, Default, Clone, Debug)]
+pub struct KeyspaceMigrationReport {
+    pub keyspace: String,
+    /// The number of entries in the keyspace before the migration
+    pub scanned: usize,
+    /// The number of entries that were moved to the key derived by [`CacheEntity::key`](crate::CacheEntity::key)
+    pub rekeyed: usize,
+    /// The number of entries that were removed because an entry with the derived key already exists
+    pub removed: usize,
+}
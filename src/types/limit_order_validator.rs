@@ -0,0 +1,119 @@
+use crate::{Amount, ClobMarket, OrderBook, Price, Side, TokenId};
+use derive_new::new;
+use errgonomic::{handle_bool, handle_opt};
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+/// Checks a limit order against the constraints of its market before it is posted
+///
+/// The `book` is only needed to validate `post_only` orders.
+#[derive(new, Clone, Debug)]
+pub struct LimitOrderValidator {
+    pub market: ClobMarket,
+    pub book: Option<OrderBook>,
+}
+
+impl LimitOrderValidator {
+    pub fn validate(&self, token_id: TokenId, side: Side, price: Price, size: Amount, post_only: bool) -> Result<(), LimitOrderValidatorValidateError> {
+        use LimitOrderValidatorValidateError::*;
+        let Self {
+            market,
+            book,
+        } = self;
+        let slug = &market.slug;
+        handle_bool!(market.left_token_id != token_id && market.right_token_id != token_id, TokenNotInMarket, token_id, slug: slug.clone());
+        handle_bool!(market.closed, MarketClosed, slug: slug.clone());
+        handle_bool!(!market.accepting_orders, MarketNotAcceptingOrders, slug: slug.clone());
+        handle_bool!(price <= Decimal::ZERO || price >= Decimal::ONE, PriceOutOfRange, price);
+        let tick_size = market.minimum_tick_size;
+        let remainder = handle_opt!(price.checked_rem(tick_size), CheckedRemFailed, price, tick_size);
+        handle_bool!(!remainder.is_zero(), PriceNotTickMultiple, price, tick_size);
+        let minimum_order_size = market.minimum_order_size;
+        handle_bool!(size < minimum_order_size, SizeBelowMinimum, size, minimum_order_size);
+        if post_only {
+            let book = handle_opt!(book.as_ref(), OrderBookNotFound, token_id);
+            // a post-only order must rest on the book, so it must not reach the best level of the opposite side
+            let opposite_side = match side {
+                Side::Buy => Side::Sell,
+                Side::Sell => Side::Buy,
+            };
+            if let Some(best) = book.best(opposite_side) {
+                let is_crossing = match side {
+                    Side::Buy => price >= best.price,
+                    Side::Sell => price <= best.price,
+                };
+                handle_bool!(is_crossing, PostOnlyCrossesBook, side, price, best_price: best.price);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Error, Clone, Debug)]
+pub enum LimitOrderValidatorValidateError {
+    #[error("token '{token_id}' does not belong to market '{slug}'")]
+    TokenNotInMarket { token_id: TokenId, slug: String },
+    #[error("market '{slug}' is closed")]
+    MarketClosed { slug: String },
+    #[error("market '{slug}' is not accepting orders")]
+    MarketNotAcceptingOrders { slug: String },
+    #[error("price '{price}' must be greater than 0 and less than 1")]
+    PriceOutOfRange { price: Price },
+    #[error("failed to compute the remainder of price '{price}' divided by tick size '{tick_size}'")]
+    CheckedRemFailed { price: Price, tick_size: Price },
+    #[error("price '{price}' is not a multiple of tick size '{tick_size}'")]
+    PriceNotTickMultiple { price: Price, tick_size: Price },
+    #[error("size '{size}' is below the minimum order size '{minimum_order_size}'")]
+    SizeBelowMinimum { size: Amount, minimum_order_size: Amount },
+    #[error("order book for token '{token_id}' is required to validate a post-only order")]
+    OrderBookNotFound { token_id: TokenId },
+    #[error("post-only '{side:?}' order at price '{price}' would cross the best opposite price '{best_price}'")]
+    PostOnlyCrossesBook { side: Side, price: Price, best_price: Price },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClobMarketFallible, FixtureMarketResponseError, FixtureOrderBookError, fixture_market_response, fixture_order_book};
+    use errgonomic::handle;
+
+    #[test]
+    fn must_validate_limit_orders() -> Result<(), LimitOrderValidatorTestError> {
+        use LimitOrderValidatorTestError::*;
+        let market_precise = handle!(fixture_market_response(), FixtureMarketResponseFailed);
+        let market = handle!(ClobMarket::try_from(market_precise), MarketTryFromFailed);
+        let book = OrderBook::from(handle!(fixture_order_book(), FixtureOrderBookFailed));
+        let token_id = book.token_id();
+        let best_ask = handle_opt!(book.best_ask(), BestAskNotFound);
+        let validator = LimitOrderValidator::new(market, Some(book));
+        let size = Decimal::TEN;
+        assert!(
+            validator
+                .validate(token_id, Side::Buy, Decimal::new(1, 2), size, true)
+                .is_ok()
+        );
+        assert!(matches!(validator.validate(token_id, Side::Buy, Decimal::ONE, size, false), Err(LimitOrderValidatorValidateError::PriceOutOfRange { .. })));
+        assert!(matches!(validator.validate(token_id, Side::Buy, Decimal::new(1005, 4), size, false), Err(LimitOrderValidatorValidateError::PriceNotTickMultiple { .. })));
+        assert!(matches!(validator.validate(token_id, Side::Buy, Decimal::new(1, 2), Decimal::ONE, false), Err(LimitOrderValidatorValidateError::SizeBelowMinimum { .. })));
+        assert!(matches!(validator.validate(token_id, Side::Buy, best_ask.price, size, true), Err(LimitOrderValidatorValidateError::PostOnlyCrossesBook { .. })));
+        assert!(
+            validator
+                .validate(token_id, Side::Buy, best_ask.price, size, false)
+                .is_ok()
+        );
+        assert!(matches!(validator.validate(TokenId::from(7u64), Side::Buy, Decimal::new(1, 2), size, false), Err(LimitOrderValidatorValidateError::TokenNotInMarket { .. })));
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum LimitOrderValidatorTestError {
+        #[error("failed to load market fixture")]
+        FixtureMarketResponseFailed { source: FixtureMarketResponseError },
+        #[error("failed to convert market")]
+        MarketTryFromFailed { source: Box<ClobMarketFallible> },
+        #[error("failed to load orderbook fixture")]
+        FixtureOrderBookFailed { source: FixtureOrderBookError },
+        #[error("best ask not found")]
+        BestAskNotFound,
+    }
+}
@@ -0,0 +1,19 @@
+use crate::{ConditionId, TimestampVisitor, TokenId, UintAsString};
+use polymarket_client_sdk::clob::types::response::OrderSummary;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+/// A full snapshot of the order book (emitted on subscription and when a trade affects the book)
+#[derive(Deserialize, Clone, Debug)]
+pub struct MarketChannelBookMessage {
+    #[serde(with = "UintAsString")]
+    pub asset_id: TokenId,
+    pub market: ConditionId,
+    #[serde(with = "TimestampVisitor")]
+    pub timestamp: OffsetDateTime,
+    pub hash: Option<String>,
+    #[serde(alias = "buys")]
+    pub bids: Vec<OrderSummary>,
+    #[serde(alias = "sells")]
+    pub asks: Vec<OrderSummary>,
+}
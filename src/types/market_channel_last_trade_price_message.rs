@@ -0,0 +1,18 @@
+use crate::{Amount, ConditionId, Price, Side, TimestampVisitor, TokenId, UintAsString};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+/// A trade (emitted when a maker and a taker order are matched)
+#[derive(Deserialize, Clone, Debug)]
+pub struct MarketChannelLastTradePriceMessage {
+    #[serde(with = "UintAsString")]
+    pub asset_id: TokenId,
+    pub market: ConditionId,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub price: Price,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub size: Amount,
+    pub side: Side,
+    #[serde(with = "TimestampVisitor")]
+    pub timestamp: OffsetDateTime,
+}
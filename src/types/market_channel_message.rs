@@ -0,0 +1,28 @@
+use crate::{MarketChannelBookMessage, MarketChannelLastTradePriceMessage, MarketChannelPriceChangeMessage, MarketChannelTickSizeChangeMessage};
+use serde::Deserialize;
+
+/// A message received from the CLOB market channel
+///
+/// See `.agents/docs/docs.polymarket.com/developers/CLOB/websocket/market-channel.md`
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+pub enum MarketChannelMessage {
+    Book(MarketChannelBookMessage),
+    PriceChange(MarketChannelPriceChangeMessage),
+    TickSizeChange(MarketChannelTickSizeChangeMessage),
+    LastTradePrice(MarketChannelLastTradePriceMessage),
+    /// Messages behind the `custom_feature_enabled` flag and any messages that are added to the channel later
+    #[serde(other)]
+    Other,
+}
+
+impl MarketChannelMessage {
+    /// Parses a text frame, which contains either a single message or an array of messages (the initial `book` messages are sent as an array)
+    pub fn from_text(text: &str) -> Result<Vec<Self>, serde_json::Error> {
+        if text.trim_start().starts_with('[') {
+            serde_json::from_str(text)
+        } else {
+            serde_json::from_str(text).map(|message| vec![message])
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{Amount, Level, Price, Side, TokenId, UintAsString};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+/// A new aggregate size of a single price level
+///
+/// The `best_bid` and `best_ask` reflect the server-side book after this change, so they can be used to detect the gaps in the local book.
+#[derive(Deserialize, Clone, Debug)]
+pub struct MarketChannelPriceChange {
+    #[serde(with = "UintAsString")]
+    pub asset_id: TokenId,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub price: Price,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub size: Amount,
+    pub side: Side,
+    pub hash: Option<String>,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub best_bid: Price,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub best_ask: Price,
+}
+
+impl MarketChannelPriceChange {
+    pub fn level(&self) -> Level {
+        Level::from((self.price, self.size))
+    }
+
+    /// Returns true if the best prices of the local book match the best prices reported by the server
+    ///
+    /// The server reports `0` as the best bid of an empty bid side and either `0` or `1` as the best ask of an empty ask side.
+    pub fn is_consistent_with(&self, best_bid_opt: Option<Price>, best_ask_opt: Option<Price>) -> bool {
+        let is_best_bid_consistent = match best_bid_opt {
+            Some(best_bid) => best_bid == self.best_bid,
+            None => self.best_bid.is_zero(),
+        };
+        let is_best_ask_consistent = match best_ask_opt {
+            Some(best_ask) => best_ask == self.best_ask,
+            None => self.best_ask.is_zero() || self.best_ask == Decimal::ONE,
+        };
+        is_best_bid_consistent && is_best_ask_consistent
+    }
+}
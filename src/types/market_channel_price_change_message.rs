@@ -0,0 +1,12 @@
+use crate::{ConditionId, MarketChannelPriceChange, TimestampVisitor};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+/// A batch of price level updates (emitted when an order is placed or cancelled)
+#[derive(Deserialize, Clone, Debug)]
+pub struct MarketChannelPriceChangeMessage {
+    pub market: ConditionId,
+    pub price_changes: Vec<MarketChannelPriceChange>,
+    #[serde(with = "TimestampVisitor")]
+    pub timestamp: OffsetDateTime,
+}
@@ -0,0 +1,410 @@
+use crate::{BookSide, BookSideMap, CLOB_WEBSOCKET_PING_INTERVAL, ConvertVecOrderSummaryToBookSideError, MarketChannelBookMessage, MarketChannelLastTradePriceMessage, MarketChannelMessage, MarketChannelPriceChangeMessage, MarketChannelTickSizeChangeMessage, MarketChannelUpdate, OrderBook, OrderBookSnapshotSource, TokenId};
+use errgonomic::{ErrVec, handle, handle_iter};
+use futures::future::join_all;
+use futures::{SinkExt, StreamExt};
+use rustc_hash::FxHashMap;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::collections::hash_map::Entry;
+use thiserror::Error;
+use tokio::net::TcpStream;
+use tokio::time::{Instant, Interval, MissedTickBehavior, interval_at};
+use tokio_tungstenite::tungstenite::{Error as TungsteniteError, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+const PING: &str = "PING";
+const PONG: &str = "PONG";
+
+/// Maintains the local order books of the subscribed tokens by applying the messages from the CLOB market channel
+///
+/// The books are fetched from the `source` on connect, then updated by the `book` and `price_change` messages.
+/// If a `price_change` can't be applied or leaves the local book with best prices that differ from the server-side best prices, the book is fetched from the `source` again.
+/// If a `book` message has the same hash as the local book but different levels, the local book has diverged from the server-side book (e.g. because of a missed message), so the subscriber resubscribes to receive fresh `book` messages for all tokens.
+pub struct MarketChannelSubscriber<S> {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    source: S,
+    token_ids: Vec<TokenId>,
+    books: FxHashMap<TokenId, OrderBook>,
+    updates: VecDeque<MarketChannelUpdate>,
+    ping_interval: Interval,
+}
+
+impl<S: OrderBookSnapshotSource> MarketChannelSubscriber<S> {
+    pub async fn connect(url: &str, source: S, token_ids: Vec<TokenId>) -> Result<Self, MarketChannelSubscriberConnectError<S::Error>> {
+        use MarketChannelSubscriberConnectError::*;
+        let (mut socket, _response) = handle!(connect_async(url).await, ConnectFailed, url: url.to_string());
+        handle!(socket.send(subscribe_request(&token_ids)).await, SendSubscribeRequestFailed);
+        let snapshots = join_all(
+            token_ids
+                .iter()
+                .map(|token_id| source.order_book_snapshot(*token_id)),
+        )
+        .await;
+        let snapshots = handle_iter!(snapshots.into_iter(), OrderBookSnapshotsFailed);
+        let books = snapshots
+            .into_iter()
+            .map(|snapshot| (snapshot.token_id, OrderBook::from(snapshot)))
+            .collect();
+        let now = Instant::now();
+        let ping_start = now.checked_add(CLOB_WEBSOCKET_PING_INTERVAL).unwrap_or(now);
+        let mut ping_interval = interval_at(ping_start, CLOB_WEBSOCKET_PING_INTERVAL);
+        ping_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Ok(Self {
+            socket,
+            source,
+            token_ids,
+            books,
+            updates: VecDeque::new(),
+            ping_interval,
+        })
+    }
+
+    pub fn book(&self, token_id: &TokenId) -> Option<&OrderBook> {
+        self.books.get(token_id)
+    }
+
+    pub fn books(&self) -> &FxHashMap<TokenId, OrderBook> {
+        &self.books
+    }
+
+    /// Returns the next update of the local books (`None` if the server has closed the connection)
+    pub async fn next_update(&mut self) -> Result<Option<MarketChannelUpdate>, MarketChannelSubscriberNextUpdateError<S::Error>> {
+        use MarketChannelSubscriberNextUpdateError::*;
+        loop {
+            if let Some(update) = self.updates.pop_front() {
+                return Ok(Some(update));
+            }
+            let message_opt = tokio::select! {
+                _ = self.ping_interval.tick() => None,
+                message_opt = self.socket.next() => Some(message_opt),
+            };
+            let message = match message_opt {
+                None => {
+                    handle!(self.socket.send(Message::text(PING)).await, SendPingFailed);
+                    continue;
+                }
+                Some(None) => return Ok(None),
+                Some(Some(result)) => handle!(result, ReceiveFailed),
+            };
+            match message {
+                Message::Text(text) if text.as_str() == PONG => (),
+                Message::Text(text) => {
+                    let messages = handle!(MarketChannelMessage::from_text(text.as_str()), ParseFailed, text: text.as_str().to_string());
+                    for message in messages {
+                        handle!(self.apply(message).await, ApplyFailed);
+                    }
+                }
+                Message::Close(_) => return Ok(None),
+                // tungstenite replies to the protocol-level pings automatically
+                Message::Binary(_) | Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => (),
+            }
+        }
+    }
+
+    async fn apply(&mut self, message: MarketChannelMessage) -> Result<(), MarketChannelSubscriberApplyError<S::Error>> {
+        match message {
+            MarketChannelMessage::Book(message) => self.apply_book(message).await,
+            MarketChannelMessage::PriceChange(message) => self.apply_price_change(message).await,
+            MarketChannelMessage::TickSizeChange(message) => self.apply_tick_size_change(message).await,
+            MarketChannelMessage::LastTradePrice(message) => self.apply_last_trade_price(message).await,
+            MarketChannelMessage::Other => Ok(()),
+        }
+    }
+
+    async fn apply_book(&mut self, message: MarketChannelBookMessage) -> Result<(), MarketChannelSubscriberApplyError<S::Error>> {
+        use MarketChannelSubscriberApplyError::*;
+        let MarketChannelBookMessage {
+            asset_id: token_id,
+            timestamp,
+            hash,
+            bids,
+            asks,
+            ..
+        } = message;
+        let bids = BookSide::from(handle!(BookSideMap::try_from(bids), BidsTryFromFailed, token_id));
+        let asks = BookSide::from(handle!(BookSideMap::try_from(asks), AsksTryFromFailed, token_id));
+        let book = handle!(self.book_mut(token_id).await, ResnapshotFailed);
+        let is_diverged = hash.is_some() && book.hash() == hash.as_deref() && (book.bids() != &bids || book.asks() != &asks);
+        // the REST snapshot may be newer than the message
+        if book.updated_at() <= timestamp {
+            book.replace_levels(bids, asks, hash, timestamp);
+            self.updates.push_back(MarketChannelUpdate::Snapshot {
+                token_id,
+            });
+        }
+        if is_diverged {
+            handle!(self.socket.send(subscribe_request(&self.token_ids)).await, ResubscribeFailed, token_id);
+            self.updates.push_back(MarketChannelUpdate::Resubscribe {
+                token_id,
+            });
+        }
+        Ok(())
+    }
+
+    async fn apply_price_change(&mut self, message: MarketChannelPriceChangeMessage) -> Result<(), MarketChannelSubscriberApplyError<S::Error>> {
+        use MarketChannelSubscriberApplyError::*;
+        let MarketChannelPriceChangeMessage {
+            price_changes,
+            timestamp,
+            ..
+        } = message;
+        for price_change in price_changes {
+            let token_id = price_change.asset_id;
+            let is_applied = match self.books.get_mut(&token_id) {
+                Some(book) => {
+                    let result = book.apply([(price_change.side, price_change.level())], price_change.hash.clone(), timestamp);
+                    let best_bid_opt = book.best_bid().map(|level| level.price);
+                    let best_ask_opt = book.best_ask().map(|level| level.price);
+                    result.is_ok() && price_change.is_consistent_with(best_bid_opt, best_ask_opt)
+                }
+                None => false,
+            };
+            let update = if is_applied {
+                MarketChannelUpdate::Delta {
+                    token_id,
+                }
+            } else {
+                handle!(self.resnapshot(token_id).await, ResnapshotFailed);
+                MarketChannelUpdate::Resnapshot {
+                    token_id,
+                }
+            };
+            self.updates.push_back(update);
+        }
+        Ok(())
+    }
+
+    async fn apply_tick_size_change(&mut self, message: MarketChannelTickSizeChangeMessage) -> Result<(), MarketChannelSubscriberApplyError<S::Error>> {
+        use MarketChannelSubscriberApplyError::*;
+        let MarketChannelTickSizeChangeMessage {
+            asset_id: token_id,
+            new_tick_size,
+            ..
+        } = message;
+        let book = handle!(self.book_mut(token_id).await, ResnapshotFailed);
+        book.set_min_tick_size(new_tick_size);
+        self.updates.push_back(MarketChannelUpdate::TickSizeChange {
+            token_id,
+            min_tick_size: new_tick_size,
+        });
+        Ok(())
+    }
+
+    async fn apply_last_trade_price(&mut self, message: MarketChannelLastTradePriceMessage) -> Result<(), MarketChannelSubscriberApplyError<S::Error>> {
+        use MarketChannelSubscriberApplyError::*;
+        let MarketChannelLastTradePriceMessage {
+            asset_id: token_id,
+            price,
+            size,
+            side,
+            ..
+        } = message;
+        let book = handle!(self.book_mut(token_id).await, ResnapshotFailed);
+        book.set_last_trade_price(price);
+        self.updates.push_back(MarketChannelUpdate::LastTradePrice {
+            token_id,
+            price,
+            size,
+            side,
+        });
+        Ok(())
+    }
+
+    /// Returns the local book (fetches the book from the `source` if the book is missing)
+    async fn book_mut(&mut self, token_id: TokenId) -> Result<&mut OrderBook, S::Error> {
+        match self.books.entry(token_id) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let snapshot = self.source.order_book_snapshot(token_id).await?;
+                Ok(entry.insert(OrderBook::from(snapshot)))
+            }
+        }
+    }
+
+    async fn resnapshot(&mut self, token_id: TokenId) -> Result<(), S::Error> {
+        let snapshot = self.source.order_book_snapshot(token_id).await?;
+        self.books.insert(token_id, OrderBook::from(snapshot));
+        Ok(())
+    }
+}
+
+fn subscribe_request(token_ids: &[TokenId]) -> Message {
+    let assets_ids = token_ids
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let request = json!({
+        "assets_ids": assets_ids,
+        "type": "market",
+    });
+    Message::text(request.to_string())
+}
+
+#[derive(Error, Debug)]
+pub enum MarketChannelSubscriberConnectError<E> {
+    #[error("failed to connect to '{url}'")]
+    ConnectFailed { source: TungsteniteError, url: String },
+    #[error("failed to send subscribe request")]
+    SendSubscribeRequestFailed { source: TungsteniteError },
+    #[error("failed to fetch {len} order book snapshots", len = source.len())]
+    OrderBookSnapshotsFailed { source: ErrVec<E> },
+}
+
+#[derive(Error, Debug)]
+pub enum MarketChannelSubscriberNextUpdateError<E> {
+    #[error("failed to send ping")]
+    SendPingFailed { source: TungsteniteError },
+    #[error("failed to receive message")]
+    ReceiveFailed { source: TungsteniteError },
+    #[error("failed to parse message '{text}'")]
+    ParseFailed { source: serde_json::Error, text: String },
+    #[error("failed to apply message")]
+    ApplyFailed { source: MarketChannelSubscriberApplyError<E> },
+}
+
+#[derive(Error, Debug)]
+pub enum MarketChannelSubscriberApplyError<E> {
+    #[error("failed to convert bids of token '{token_id}'")]
+    BidsTryFromFailed { source: ConvertVecOrderSummaryToBookSideError, token_id: TokenId },
+    #[error("failed to convert asks of token '{token_id}'")]
+    AsksTryFromFailed { source: ConvertVecOrderSummaryToBookSideError, token_id: TokenId },
+    #[error("failed to resnapshot order book")]
+    ResnapshotFailed { source: E },
+    #[error("failed to resubscribe after the book of token '{token_id}' has diverged")]
+    ResubscribeFailed { source: TungsteniteError, token_id: TokenId },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureOrderBookError, OrderBookSummaryResponsePrecise, fixture_order_book};
+    use core::convert::Infallible;
+    use errgonomic::handle_opt;
+    use rust_decimal::Decimal;
+    use std::io;
+    use tokio::net::TcpListener;
+    use tokio::task::JoinError;
+    use tokio_tungstenite::accept_async;
+
+    struct FixtureSource(OrderBookSummaryResponsePrecise);
+
+    impl OrderBookSnapshotSource for FixtureSource {
+        type Error = Infallible;
+
+        async fn order_book_snapshot(&self, _token_id: TokenId) -> Result<OrderBookSummaryResponsePrecise, Self::Error> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn must_apply_messages_from_mock_server() -> Result<(), MarketChannelSubscriberTestError> {
+        use MarketChannelSubscriberTestError::*;
+        let snapshot = handle!(fixture_order_book(), FixtureOrderBookFailed);
+        let token_id = snapshot.token_id;
+        let market = snapshot.condition_id;
+        let snapshot_hash = snapshot.hash.clone();
+        let listener = handle!(TcpListener::bind("127.0.0.1:0").await, BindFailed);
+        let address = handle!(listener.local_addr(), LocalAddrFailed);
+        let messages = [
+            json!([{
+                "event_type": "book",
+                "asset_id": token_id.to_string(),
+                "market": market.to_string(),
+                "bids": [{ "price": ".48", "size": "30" }, { "price": ".49", "size": "20" }],
+                "asks": [{ "price": ".52", "size": "25" }, { "price": ".53", "size": "60" }],
+                "timestamp": "4102444800000",
+                "hash": "book",
+            }]),
+            json!({
+                "event_type": "price_change",
+                "market": market.to_string(),
+                "price_changes": [{ "asset_id": token_id.to_string(), "price": "0.5", "size": "10", "side": "BUY", "hash": "delta", "best_bid": "0.5", "best_ask": "0.52" }],
+                "timestamp": "4102444800001",
+            }),
+            json!({
+                "event_type": "price_change",
+                "market": market.to_string(),
+                "price_changes": [{ "asset_id": token_id.to_string(), "price": "0.51", "size": "10", "side": "SELL", "hash": "gap", "best_bid": "0.5", "best_ask": "0.505" }],
+                "timestamp": "4102444800002",
+            }),
+            // same hash as the REST snapshot, but different levels
+            json!({
+                "event_type": "book",
+                "asset_id": token_id.to_string(),
+                "market": market.to_string(),
+                "bids": [{ "price": ".48", "size": "30" }],
+                "asks": [{ "price": ".52", "size": "25" }],
+                "timestamp": "4102444800003",
+                "hash": snapshot_hash,
+            }),
+        ];
+        let server = tokio::spawn(async move {
+            let (stream, _address) = listener.accept().await?;
+            let mut socket = accept_async(stream).await.map_err(io::Error::other)?;
+            let _subscribe_request = socket.next().await;
+            for message in messages {
+                socket
+                    .send(Message::text(message.to_string()))
+                    .await
+                    .map_err(io::Error::other)?;
+            }
+            let resubscribe_request = socket.next().await;
+            socket.close(None).await.map_err(io::Error::other)?;
+            Ok::<_, io::Error>(resubscribe_request.is_some())
+        });
+        let url = format!("ws://{address}");
+        let mut subscriber = handle!(MarketChannelSubscriber::connect(&url, FixtureSource(snapshot.clone()), vec![token_id]).await, ConnectFailed);
+        let mut updates = Vec::new();
+        while let Some(update) = handle!(subscriber.next_update().await, NextUpdateFailed) {
+            updates.push(update);
+        }
+        let server_result = handle!(server.await, JoinFailed);
+        let is_resubscribed = handle!(server_result, ServerFailed);
+        assert!(is_resubscribed);
+        assert_eq!(
+            updates,
+            vec![
+                MarketChannelUpdate::Snapshot {
+                    token_id
+                },
+                MarketChannelUpdate::Delta {
+                    token_id
+                },
+                MarketChannelUpdate::Resnapshot {
+                    token_id
+                },
+                MarketChannelUpdate::Snapshot {
+                    token_id
+                },
+                MarketChannelUpdate::Resubscribe {
+                    token_id
+                },
+            ]
+        );
+        // the last book message is authoritative, so the book must have its levels
+        let book = handle_opt!(subscriber.book(&token_id), BookNotFound);
+        assert_eq!(book.best_bid().map(|level| level.price), Some(Decimal::new(48, 2)));
+        assert_eq!(book.best_ask().map(|level| level.price), Some(Decimal::new(52, 2)));
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum MarketChannelSubscriberTestError {
+        #[error("failed to load orderbook fixture")]
+        FixtureOrderBookFailed { source: FixtureOrderBookError },
+        #[error("failed to bind mock server")]
+        BindFailed { source: io::Error },
+        #[error("failed to get mock server address")]
+        LocalAddrFailed { source: io::Error },
+        #[error("failed to connect to mock server")]
+        ConnectFailed { source: MarketChannelSubscriberConnectError<Infallible> },
+        #[error("failed to receive update")]
+        NextUpdateFailed { source: MarketChannelSubscriberNextUpdateError<Infallible> },
+        #[error("failed to join mock server task")]
+        JoinFailed { source: JoinError },
+        #[error("mock server failed")]
+        ServerFailed { source: io::Error },
+        #[error("book not found")]
+        BookNotFound,
+    }
+}
@@ -0,0 +1,17 @@
+use crate::{ConditionId, Price, TimestampVisitor, TokenId, UintAsString};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+/// A change of the minimum tick size (emitted when the price of the book approaches the limits)
+#[derive(Deserialize, Clone, Debug)]
+pub struct MarketChannelTickSizeChangeMessage {
+    #[serde(with = "UintAsString")]
+    pub asset_id: TokenId,
+    pub market: ConditionId,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub old_tick_size: Price,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub new_tick_size: Price,
+    #[serde(with = "TimestampVisitor")]
+    pub timestamp: OffsetDateTime,
+}
@@ -0,0 +1,70 @@
+use crate::{Amount, Price, Side, TokenId, UintAsString};
+use serde::Serialize;
+
+/// A change of the local order book that was applied by [`MarketChannelSubscriber`](crate::MarketChannelSubscriber)
+#[derive(Serialize, Eq, PartialEq, Clone, Copy, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MarketChannelUpdate {
+    /// The levels were replaced by a `book` message
+    Snapshot {
+        #[serde(with = "UintAsString")]
+        token_id: TokenId,
+    },
+    /// A single level was updated by a `price_change` message
+    Delta {
+        #[serde(with = "UintAsString")]
+        token_id: TokenId,
+    },
+    /// The book was fetched via REST because the local book diverged from the server-side book
+    Resnapshot {
+        #[serde(with = "UintAsString")]
+        token_id: TokenId,
+    },
+    /// A `book` message had the same hash as the local book but different levels, so the subscriber has resubscribed to all tokens
+    Resubscribe {
+        #[serde(with = "UintAsString")]
+        token_id: TokenId,
+    },
+    TickSizeChange {
+        #[serde(with = "UintAsString")]
+        token_id: TokenId,
+        #[serde(with = "rust_decimal::serde::str")]
+        min_tick_size: Price,
+    },
+    LastTradePrice {
+        #[serde(with = "UintAsString")]
+        token_id: TokenId,
+        #[serde(with = "rust_decimal::serde::str")]
+        price: Price,
+        #[serde(with = "rust_decimal::serde::str")]
+        size: Amount,
+        side: Side,
+    },
+}
+
+impl MarketChannelUpdate {
+    pub fn token_id(&self) -> TokenId {
+        match self {
+            MarketChannelUpdate::Snapshot {
+                token_id,
+            }
+            | MarketChannelUpdate::Delta {
+                token_id,
+            }
+            | MarketChannelUpdate::Resnapshot {
+                token_id,
+            }
+            | MarketChannelUpdate::Resubscribe {
+                token_id,
+            }
+            | MarketChannelUpdate::TickSizeChange {
+                token_id,
+                ..
+            }
+            | MarketChannelUpdate::LastTradePrice {
+                token_id,
+                ..
+            } => *token_id,
+        }
+    }
+}
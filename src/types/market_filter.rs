@@ -0,0 +1,166 @@
+use crate::{Amount, ClobMarketResponsePrecise, Comparison, MarketResponseFeature};
+use core::str::FromStr;
+use errgonomic::handle;
+use regex::Regex;
+use rust_decimal::Decimal;
+use thiserror::Error;
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime};
+
+/// A predicate over [`ClobMarketResponsePrecise`] (a variable of [`Expr<MarketFilter>`](crate::Expr))
+///
+/// Syntax:
+///
+/// * `active`, `neg_risk`, `has_condition_id` (any [`MarketResponseFeature`])
+/// * `tag:Politics` (case-insensitive)
+/// * `question:"donald trump"` (case-insensitive substring)
+/// * `question~"^Will .* win"` (regex)
+/// * `end_date>=2025-01-01`, `end_date<2025-01-01T12:00:00Z` (a market without an end date doesn't match)
+/// * `minimum_tick_size<=0.01`
+/// * `rewards_daily_rate>0` (the max rate of the market, or zero if the market has no rewards)
+#[derive(Clone, Debug)]
+pub enum MarketFilter {
+    Feature(MarketResponseFeature),
+    Tag(String),
+    QuestionContains(String),
+    QuestionMatches(Regex),
+    EndDate(Comparison, OffsetDateTime),
+    MinimumTickSize(Comparison, Amount),
+    RewardsDailyRate(Comparison, Amount),
+}
+
+impl MarketFilter {
+    pub fn matches(&self, market: &ClobMarketResponsePrecise) -> bool {
+        use MarketFilter::*;
+        match self {
+            Feature(feature) => feature.value(market),
+            Tag(tag) => market
+                .tags
+                .iter()
+                .any(|market_tag| market_tag.eq_ignore_ascii_case(tag)),
+            QuestionContains(needle) => market.question.to_lowercase().contains(needle),
+            QuestionMatches(regex) => regex.is_match(&market.question),
+            EndDate(comparison, date) => market
+                .end_date_iso
+                .is_some_and(|end_date| comparison.holds(&end_date, date)),
+            MinimumTickSize(comparison, amount) => comparison.holds(&market.minimum_tick_size, amount),
+            RewardsDailyRate(comparison, amount) => {
+                let rate = market
+                    .rewards
+                    .rates
+                    .iter()
+                    .map(|rate| rate.rewards_daily_rate)
+                    .max()
+                    .unwrap_or(Decimal::ZERO);
+                comparison.holds(&rate, amount)
+            }
+        }
+    }
+
+    fn parse_date(value: &str) -> Result<OffsetDateTime, time::error::Parse> {
+        match Date::parse(value, format_description!("[year]-[month]-[day]")) {
+            Ok(date) => Ok(date.midnight().assume_utc()),
+            Err(_) => OffsetDateTime::parse(value, &Rfc3339),
+        }
+    }
+}
+
+impl FromStr for MarketFilter {
+    type Err = MarketFilterParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        use MarketFilter::*;
+        use MarketFilterParseError::*;
+        let Some(operator_start) = input.find([':', '~', '<', '>', '=']) else {
+            let feature = handle!(MarketResponseFeature::from_str(input), UnknownFeature, name: input.to_string());
+            return Ok(Feature(feature));
+        };
+        let (field, rest) = input.split_at(operator_start);
+        let operator_len = if rest.starts_with("<=") || rest.starts_with(">=") { 2 } else { 1 };
+        let (operator, value) = rest.split_at(operator_len);
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+        match (field, operator) {
+            ("tag", ":" | "=") => Ok(Tag(value.to_string())),
+            ("question", ":") => Ok(QuestionContains(value.to_lowercase())),
+            ("question", "~") => Ok(QuestionMatches(handle!(Regex::new(value), InvalidRegex, value: value.to_string()))),
+            ("end_date", operator) => {
+                let comparison = handle!(Comparison::from_str(operator), InvalidComparison, field: field.to_string(), operator: operator.to_string());
+                let date = handle!(Self::parse_date(value), InvalidDate, value: value.to_string());
+                Ok(EndDate(comparison, date))
+            }
+            ("minimum_tick_size" | "rewards_daily_rate", operator) => {
+                let comparison = handle!(Comparison::from_str(operator), InvalidComparison, field: field.to_string(), operator: operator.to_string());
+                let amount = handle!(Decimal::from_str(value), InvalidDecimal, value: value.to_string());
+                if field == "minimum_tick_size" {
+                    Ok(MinimumTickSize(comparison, amount))
+                } else {
+                    Ok(RewardsDailyRate(comparison, amount))
+                }
+            }
+            ("tag" | "question", operator) => Err(UnsupportedOperator {
+                field: field.to_string(),
+                operator: operator.to_string(),
+            }),
+            (field, _) => Err(UnknownField {
+                field: field.to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum MarketFilterParseError {
+    #[error("unknown market feature '{name}'")]
+    UnknownFeature { source: strum::ParseError, name: String },
+    #[error("unknown market field '{field}'")]
+    UnknownField { field: String },
+    #[error("unsupported operator '{operator}' for field '{field}'")]
+    UnsupportedOperator { field: String, operator: String },
+    #[error("invalid comparison operator '{operator}' for field '{field}'")]
+    InvalidComparison { source: strum::ParseError, field: String, operator: String },
+    #[error("invalid regex '{value}'")]
+    InvalidRegex { source: regex::Error, value: String },
+    #[error("invalid date '{value}' (expected YYYY-MM-DD or RFC 3339)")]
+    InvalidDate { source: time::error::Parse, value: String },
+    #[error("invalid decimal '{value}'")]
+    InvalidDecimal { source: rust_decimal::Error, value: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Expr, ExprParseError, FixtureMarketResponseError, fixture_market_response};
+
+    #[test]
+    fn must_match_fixture() -> Result<(), MustMatchFixtureError> {
+        use MustMatchFixtureError::*;
+        let market = handle!(fixture_market_response(), FixtureMarketResponseFailed);
+        let matches = |input: &str| -> Result<bool, ExprParseError<MarketFilterParseError>> {
+            let expr = input.parse::<Expr<MarketFilter>>()?;
+            Ok(expr.eval(&mut |filter: &MarketFilter| filter.matches(&market)))
+        };
+        assert!(handle!(matches(r#"question:"donald trump" & question~"^Will .* win""#), ParseFailed));
+        assert!(handle!(matches("minimum_tick_size<=0.01 | minimum_tick_size>0.01"), ParseFailed));
+        assert!(!handle!(matches("end_date<1970-01-02"), ParseFailed));
+        assert!(matches!(
+            matches("volume>10"),
+            Err(ExprParseError::ParseVarFailed {
+                source: MarketFilterParseError::UnknownField { .. },
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum MustMatchFixtureError {
+        #[error("failed to load market fixture")]
+        FixtureMarketResponseFailed { source: FixtureMarketResponseError },
+        #[error("failed to parse filter")]
+        ParseFailed { source: ExprParseError<MarketFilterParseError> },
+    }
+}
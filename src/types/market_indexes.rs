@@ -0,0 +1,313 @@
+use crate::{CACHE_REINDEX_CHUNK_SIZE, CLOB_MARKET_SLUGS_BY_CONDITION_ID_KEYSPACE, CLOB_MARKET_SLUGS_BY_END_DATE_KEYSPACE, CLOB_MARKET_SLUGS_BY_EVENT_ID_KEYSPACE, CLOB_MARKET_SLUGS_BY_QUESTION_ID_KEYSPACE, CLOB_MARKET_SLUGS_BY_TAG_KEYSPACE, ClobMarketResponsePrecise, ConditionId, EventId, OpenKeyspaceError, QuestionId, Repository, RepositoryReadEntryError, open_keyspace, sortable_timestamp_bytes};
+use core::ops::Bound;
+use errgonomic::{ErrVec, handle, handle_iter};
+use fjall::{Error as FjallError, Guard, Readable, SingleWriterTxDatabase, SingleWriterTxKeyspace, SingleWriterWriteTx, Slice};
+use itertools::Itertools;
+use std::string::FromUtf8Error;
+use thiserror::Error;
+use time::OffsetDateTime;
+
+/// The secondary indexes of [`CLOB_MARKET_RESPONSES_KEYSPACE`](crate::CLOB_MARKET_RESPONSES_KEYSPACE)
+///
+/// Every index key is the indexed value followed by the market slug, and every index value is empty, so a prefix lookup returns the slugs of the matching markets.
+/// The indexes are updated in the same transaction as the market responses (see [`Self::update`]).
+/// The indexes of a database that was written without them (or by a version with a different index layout) are rebuilt by `cache reindex` (see [`Self::rebuild`]).
+pub struct MarketIndexes {
+    pub condition_id_keyspace: SingleWriterTxKeyspace,
+    pub question_id_keyspace: SingleWriterTxKeyspace,
+    /// Indexed by [`NegRisk::event_id`](crate::NegRisk::event_id) (`neg_risk_request_id`)
+    pub event_id_keyspace: SingleWriterTxKeyspace,
+    pub tag_keyspace: SingleWriterTxKeyspace,
+    pub end_date_keyspace: SingleWriterTxKeyspace,
+}
+
+impl MarketIndexes {
+    /// Separates the tag from the slug (tags don't contain the null byte)
+    const TAG_SEPARATOR: u8 = 0;
+
+    pub fn open(db: &SingleWriterTxDatabase) -> Result<Self, MarketIndexesOpenError> {
+        use MarketIndexesOpenError::*;
+        let condition_id_keyspace = handle!(open_keyspace(db, CLOB_MARKET_SLUGS_BY_CONDITION_ID_KEYSPACE), OpenKeyspaceFailed);
+        let question_id_keyspace = handle!(open_keyspace(db, CLOB_MARKET_SLUGS_BY_QUESTION_ID_KEYSPACE), OpenKeyspaceFailed);
+        let event_id_keyspace = handle!(open_keyspace(db, CLOB_MARKET_SLUGS_BY_EVENT_ID_KEYSPACE), OpenKeyspaceFailed);
+        let tag_keyspace = handle!(open_keyspace(db, CLOB_MARKET_SLUGS_BY_TAG_KEYSPACE), OpenKeyspaceFailed);
+        let end_date_keyspace = handle!(open_keyspace(db, CLOB_MARKET_SLUGS_BY_END_DATE_KEYSPACE), OpenKeyspaceFailed);
+        Ok(Self {
+            condition_id_keyspace,
+            question_id_keyspace,
+            event_id_keyspace,
+            tag_keyspace,
+            end_date_keyspace,
+        })
+    }
+
+    pub fn keyspaces(&self) -> [&SingleWriterTxKeyspace; 5] {
+        [
+            &self.condition_id_keyspace,
+            &self.question_id_keyspace,
+            &self.event_id_keyspace,
+            &self.tag_keyspace,
+            &self.end_date_keyspace,
+        ]
+    }
+
+    /// Removes the index entries of the `previous` version of the market (if it was stored), then inserts the index entries of the `market`
+    pub fn update(&self, tx: &mut SingleWriterWriteTx, previous: Option<&ClobMarketResponsePrecise>, market: &ClobMarketResponsePrecise) {
+        if let Some(previous) = previous {
+            self.entries(previous)
+                .into_iter()
+                .for_each(|(keyspace, key)| tx.remove(keyspace, key));
+        }
+        self.entries(market)
+            .into_iter()
+            .for_each(|(keyspace, key)| tx.insert(keyspace, key, Vec::<u8>::new()));
+    }
+
+    /// Removes every index entry, then inserts the index entries of every stored market response
+    ///
+    /// The entries are read from a snapshot and written in transactions of [`CACHE_REINDEX_CHUNK_SIZE`] entries, so the memory use doesn't grow with the number of markets.
+    /// The rebuild is not atomic: if it fails, the indexes stay incomplete until the next rebuild.
+    ///
+    /// Returns the number of indexed market responses.
+    pub fn rebuild(&self, db: &SingleWriterTxDatabase, repository: &Repository<ClobMarketResponsePrecise>) -> Result<usize, MarketIndexesRebuildError> {
+        use MarketIndexesRebuildError::*;
+        let snapshot = db.read_tx();
+        let index_entries = self.keyspaces().into_iter().flat_map(|keyspace| {
+            snapshot
+                .iter(keyspace)
+                .map(move |guard| guard.into_inner().map(|(key, _value)| (keyspace, key)))
+        });
+        for chunk in &index_entries.chunks(CACHE_REINDEX_CHUNK_SIZE) {
+            let mut tx = db.write_tx();
+            for entry in chunk {
+                let (keyspace, key) = handle!(entry, ReadIndexEntryFailed);
+                tx.remove(keyspace, key);
+            }
+            handle!(tx.commit(), CommitTransactionFailed);
+        }
+        let mut count: usize = 0;
+        for chunk in &repository.iter(&snapshot).chunks(CACHE_REINDEX_CHUNK_SIZE) {
+            let mut tx = db.write_tx();
+            for entry in chunk {
+                let (_key, market_response) = handle!(entry, ReadMarketResponseFailed);
+                self.update(&mut tx, None, &market_response);
+                count = count.saturating_add(1);
+            }
+            handle!(tx.commit(), CommitTransactionFailed);
+        }
+        Ok(count)
+    }
+
+    fn entries(&self, market: &ClobMarketResponsePrecise) -> Vec<(&SingleWriterTxKeyspace, Vec<u8>)> {
+        let slug = market.market_slug.as_bytes();
+        let mut entries = Vec::new();
+        if let Some(condition_id) = market.condition_id {
+            entries.push((&self.condition_id_keyspace, [condition_id.as_slice(), slug].concat()));
+        }
+        if let Some(question_id) = market.question_id {
+            entries.push((&self.question_id_keyspace, [question_id.as_slice(), slug].concat()));
+        }
+        if let Some(event_id) = market.neg_risk_request_id {
+            entries.push((&self.event_id_keyspace, [event_id.as_slice(), slug].concat()));
+        }
+        market.tags.iter().for_each(|tag| {
+            entries.push((&self.tag_keyspace, [Self::tag_prefix(tag).as_slice(), slug].concat()));
+        });
+        if let Some(end_date) = market.end_date_iso {
+            entries.push((&self.end_date_keyspace, [sortable_timestamp_bytes(end_date).as_slice(), slug].concat()));
+        }
+        entries
+    }
+
+    fn tag_prefix(tag: &str) -> Vec<u8> {
+        [tag.as_bytes(), &[Self::TAG_SEPARATOR]].concat()
+    }
+
+    pub fn slugs_by_condition_id(&self, snapshot: &impl Readable, condition_id: ConditionId) -> Result<Vec<String>, MarketIndexesReadError> {
+        Self::slugs_by_prefix(snapshot, &self.condition_id_keyspace, condition_id.as_slice())
+    }
+
+    pub fn slugs_by_question_id(&self, snapshot: &impl Readable, question_id: QuestionId) -> Result<Vec<String>, MarketIndexesReadError> {
+        Self::slugs_by_prefix(snapshot, &self.question_id_keyspace, question_id.as_slice())
+    }
+
+    pub fn slugs_by_event_id(&self, snapshot: &impl Readable, event_id: EventId) -> Result<Vec<String>, MarketIndexesReadError> {
+        Self::slugs_by_prefix(snapshot, &self.event_id_keyspace, event_id.as_slice())
+    }
+
+    /// The tag is matched exactly (case-sensitive)
+    pub fn slugs_by_tag(&self, snapshot: &impl Readable, tag: &str) -> Result<Vec<String>, MarketIndexesReadError> {
+        Self::slugs_by_prefix(snapshot, &self.tag_keyspace, &Self::tag_prefix(tag))
+    }
+
+    /// Returns the slugs of the markets with `start <= end_date < end` (ordered by the end date)
+    pub fn slugs_by_end_date(&self, snapshot: &impl Readable, start: OffsetDateTime, end: OffsetDateTime) -> Result<Vec<String>, MarketIndexesReadError> {
+        use MarketIndexesReadError::*;
+        let start_bytes = sortable_timestamp_bytes(start);
+        // every key with the `end` prefix is greater than the prefix itself, so it is excluded
+        let end_bytes = sortable_timestamp_bytes(end);
+        let prefix_len = start_bytes.len();
+        let range = (Bound::Included(start_bytes.to_vec()), Bound::Excluded(end_bytes.to_vec()));
+        let slugs = handle_iter!(
+            snapshot
+                .range(&self.end_date_keyspace, range)
+                .map(|guard| Self::slug_from_guard(guard, prefix_len)),
+            SlugFromGuardFailed
+        );
+        Ok(slugs)
+    }
+
+    fn slugs_by_prefix(snapshot: &impl Readable, keyspace: &SingleWriterTxKeyspace, prefix: &[u8]) -> Result<Vec<String>, MarketIndexesReadError> {
+        use MarketIndexesReadError::*;
+        let slugs = handle_iter!(
+            snapshot
+                .prefix(keyspace, prefix)
+                .map(|guard| Self::slug_from_guard(guard, prefix.len())),
+            SlugFromGuardFailed
+        );
+        Ok(slugs)
+    }
+
+    fn slug_from_guard(guard: Guard, prefix_len: usize) -> Result<String, MarketIndexesSlugFromGuardError> {
+        use MarketIndexesSlugFromGuardError::*;
+        let (key, _value) = handle!(guard.into_inner(), IntoInnerFailed);
+        let slug_bytes = key.get(prefix_len..).unwrap_or_default().to_vec();
+        let slug = handle!(String::from_utf8(slug_bytes), FromUtf8Failed, key);
+        Ok(slug)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum MarketIndexesOpenError {
+    #[error("failed to open index keyspace")]
+    OpenKeyspaceFailed { source: OpenKeyspaceError },
+}
+
+#[derive(Error, Debug)]
+pub enum MarketIndexesRebuildError {
+    #[error("failed to read market response")]
+    ReadMarketResponseFailed { source: RepositoryReadEntryError },
+    #[error("failed to read index entry")]
+    ReadIndexEntryFailed { source: FjallError },
+    #[error("failed to commit database transaction")]
+    CommitTransactionFailed { source: FjallError },
+}
+
+#[derive(Error, Debug)]
+pub enum MarketIndexesReadError {
+    #[error("failed to read {len} index entries", len = source.len())]
+    SlugFromGuardFailed { source: ErrVec<MarketIndexesSlugFromGuardError> },
+}
+
+#[derive(Error, Debug)]
+pub enum MarketIndexesSlugFromGuardError {
+    #[error("failed to read index entry")]
+    IntoInnerFailed { source: FjallError },
+    #[error("index key doesn't end with a valid slug")]
+    FromUtf8Failed { source: FromUtf8Error, key: Slice },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureMarketResponseError, RepositoryInsertError, RepositoryOpenError, fixture_market_response};
+    use std::io;
+    use time::macros::datetime;
+
+    #[test]
+    fn must_replace_stale_index_entries() -> Result<(), MustReplaceStaleIndexEntriesError> {
+        use MustReplaceStaleIndexEntriesError::*;
+        let previous = handle!(fixture_market_response(), FixtureMarketResponseFailed);
+        let mut market = previous.clone();
+        market.tags = vec!["Elections".to_string()];
+        market.end_date_iso = Some(datetime!(2030-01-01 00:00 UTC));
+        let dir = handle!(tempfile::tempdir(), TempdirFailed);
+        let db = handle!(SingleWriterTxDatabase::builder(dir.path()).open(), OpenDatabaseFailed);
+        let indexes = handle!(MarketIndexes::open(&db), OpenIndexesFailed);
+        let mut tx = db.write_tx();
+        indexes.update(&mut tx, None, &previous);
+        handle!(tx.commit(), CommitFailed);
+        let mut tx = db.write_tx();
+        indexes.update(&mut tx, Some(&previous), &market);
+        handle!(tx.commit(), CommitFailed);
+        let snapshot = db.read_tx();
+        let slugs = vec![market.market_slug.clone()];
+        if let Some(condition_id) = market.condition_id {
+            assert_eq!(handle!(indexes.slugs_by_condition_id(&snapshot, condition_id), ReadFailed), slugs);
+        }
+        assert_eq!(handle!(indexes.slugs_by_tag(&snapshot, "Elections"), ReadFailed), slugs);
+        assert_eq!(handle!(indexes.slugs_by_end_date(&snapshot, datetime!(2029-12-31 00:00 UTC), datetime!(2030-01-02 00:00 UTC)), ReadFailed), slugs);
+        assert_eq!(handle!(indexes.slugs_by_end_date(&snapshot, datetime!(2000-01-01 00:00 UTC), datetime!(2029-01-01 00:00 UTC)), ReadFailed), Vec::<String>::new());
+        for tag in &previous.tags {
+            assert_eq!(handle!(indexes.slugs_by_tag(&snapshot, tag), ReadFailed), Vec::<String>::new());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn must_rebuild_indexes() -> Result<(), MustRebuildIndexesError> {
+        use MustRebuildIndexesError::*;
+        let mut market = handle!(fixture_market_response(), FixtureMarketResponseFailed);
+        market.tags = vec!["Elections".to_string()];
+        let dir = handle!(tempfile::tempdir(), TempdirFailed);
+        let db = handle!(SingleWriterTxDatabase::builder(dir.path()).open(), OpenDatabaseFailed);
+        let indexes = handle!(MarketIndexes::open(&db), OpenIndexesFailed);
+        let repository = handle!(Repository::<ClobMarketResponsePrecise>::open(&db), OpenRepositoryFailed);
+        // the market is stored without its index entries, while the tag index has an entry of a removed market
+        let mut tx = db.write_tx();
+        handle!(repository.insert(&mut tx, &market), InsertFailed);
+        tx.insert(
+            &indexes.tag_keyspace,
+            [
+                MarketIndexes::tag_prefix("Elections").as_slice(),
+                b"removed-market",
+            ]
+            .concat(),
+            Vec::<u8>::new(),
+        );
+        handle!(tx.commit(), CommitFailed);
+        let count = handle!(indexes.rebuild(&db, &repository), RebuildFailed);
+        assert_eq!(count, 1);
+        let snapshot = db.read_tx();
+        assert_eq!(handle!(indexes.slugs_by_tag(&snapshot, "Elections"), ReadFailed), vec![market.market_slug.clone()]);
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum MustRebuildIndexesError {
+        #[error("failed to load market fixture")]
+        FixtureMarketResponseFailed { source: FixtureMarketResponseError },
+        #[error("failed to create temporary directory")]
+        TempdirFailed { source: io::Error },
+        #[error("failed to open database")]
+        OpenDatabaseFailed { source: FjallError },
+        #[error("failed to open indexes")]
+        OpenIndexesFailed { source: MarketIndexesOpenError },
+        #[error("failed to open repository")]
+        OpenRepositoryFailed { source: RepositoryOpenError },
+        #[error("failed to insert market response")]
+        InsertFailed { source: RepositoryInsertError },
+        #[error("failed to commit transaction")]
+        CommitFailed { source: FjallError },
+        #[error("failed to rebuild indexes")]
+        RebuildFailed { source: MarketIndexesRebuildError },
+        #[error("failed to read index")]
+        ReadFailed { source: MarketIndexesReadError },
+    }
+
+    #[derive(Error, Debug)]
+    enum MustReplaceStaleIndexEntriesError {
+        #[error("failed to load market fixture")]
+        FixtureMarketResponseFailed { source: FixtureMarketResponseError },
+        #[error("failed to create temporary directory")]
+        TempdirFailed { source: io::Error },
+        #[error("failed to open database")]
+        OpenDatabaseFailed { source: FjallError },
+        #[error("failed to open indexes")]
+        OpenIndexesFailed { source: MarketIndexesOpenError },
+        #[error("failed to commit transaction")]
+        CommitFailed { source: FjallError },
+        #[error("failed to read index")]
+        ReadFailed { source: MarketIndexesReadError },
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{Amount, OrderBook, Price, Side};
+use errgonomic::{handle_bool, handle_opt};
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+/// The expected fill of a market order that walks the opposite side of the book
+///
+/// The `amount` is denominated in USDC for [`Side::Buy`] and in shares for [`Side::Sell`] (same as the `amount` of the CLOB market order).
+#[derive(serde::Serialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub struct MarketOrderFill {
+    pub side: Side,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub amount: Amount,
+    /// The number of shares that would be bought or sold
+    #[serde(with = "rust_decimal::serde::str")]
+    pub shares: Amount,
+    /// The amount of USDC that would be spent or received
+    #[serde(with = "rust_decimal::serde::str")]
+    pub notional: Amount,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub average_price: Price,
+    /// The price of the last level that would be touched (the limit price of the market order)
+    #[serde(with = "rust_decimal::serde::str")]
+    pub worst_price: Price,
+    /// Whether the book has enough liquidity to fill the whole `amount`
+    pub is_complete: bool,
+}
+
+impl MarketOrderFill {
+    pub fn simulate(book: &OrderBook, side: Side, amount: Amount) -> Result<Self, MarketOrderFillSimulateError> {
+        use MarketOrderFillSimulateError::*;
+        handle_bool!(amount <= Decimal::ZERO, AmountNotPositive, amount);
+        // a buy order takes the asks, a sell order takes the bids
+        let opposite_side = match side {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        };
+        let mut remaining = amount;
+        let mut shares = Decimal::ZERO;
+        let mut notional = Decimal::ZERO;
+        let mut worst_price_opt = None;
+        for level in book.levels(opposite_side) {
+            if remaining.is_zero() {
+                break;
+            }
+            let level_notional = handle_opt!(level.price.checked_mul(level.size), CheckedMulFailed, price: level.price, size: level.size);
+            let (level_shares, level_notional) = match side {
+                Side::Buy if remaining < level_notional => (handle_opt!(remaining.checked_div(level.price), CheckedDivFailed, amount: remaining, price: level.price), remaining),
+                Side::Sell if remaining < level.size => (remaining, handle_opt!(remaining.checked_mul(level.price), CheckedMulFailed, price: level.price, size: remaining)),
+                Side::Buy | Side::Sell => (level.size, level_notional),
+            };
+            let level_amount = match side {
+                Side::Buy => level_notional,
+                Side::Sell => level_shares,
+            };
+            remaining = handle_opt!(remaining.checked_sub(level_amount), CheckedSubFailed, remaining, level_amount);
+            shares = handle_opt!(shares.checked_add(level_shares), CheckedAddFailed, total: shares, value: level_shares);
+            notional = handle_opt!(notional.checked_add(level_notional), CheckedAddFailed, total: notional, value: level_notional);
+            worst_price_opt = Some(level.price);
+        }
+        let worst_price = handle_opt!(worst_price_opt, BookSideEmpty, side: opposite_side);
+        let average_price = handle_opt!(notional.checked_div(shares), CheckedDivFailed, amount: notional, price: shares);
+        Ok(Self {
+            side,
+            amount,
+            shares,
+            notional,
+            average_price,
+            worst_price,
+            is_complete: remaining.is_zero(),
+        })
+    }
+}
+
+#[derive(Error, Copy, Clone, Debug)]
+pub enum MarketOrderFillSimulateError {
+    #[error("amount '{amount}' must be positive")]
+    AmountNotPositive { amount: Amount },
+    #[error("the '{side:?}' side of the book is empty")]
+    BookSideEmpty { side: Side },
+    #[error("failed to multiply price '{price}' by size '{size}'")]
+    CheckedMulFailed { price: Price, size: Amount },
+    #[error("failed to divide '{amount}' by '{price}'")]
+    CheckedDivFailed { amount: Amount, price: Price },
+    #[error("failed to subtract '{level_amount}' from '{remaining}'")]
+    CheckedSubFailed { remaining: Amount, level_amount: Amount },
+    #[error("failed to add '{value}' to '{total}'")]
+    CheckedAddFailed { total: Amount, value: Amount },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureOrderBookError, fixture_order_book};
+    use errgonomic::handle;
+
+    #[test]
+    fn must_simulate_buy_and_sell() -> Result<(), MarketOrderFillTestError> {
+        use MarketOrderFillTestError::*;
+        let book = OrderBook::from(handle!(fixture_order_book(), FixtureOrderBookFailed));
+        let best_ask = handle_opt!(book.best_ask(), BestAskNotFound);
+        let best_bid = handle_opt!(book.best_bid(), BestBidNotFound);
+        let buy_amount = handle_opt!(best_ask.price.checked_mul(best_ask.size), CheckedMulFailed);
+        let buy = handle!(MarketOrderFill::simulate(&book, Side::Buy, buy_amount), SimulateFailed);
+        assert_eq!(buy.worst_price, best_ask.price);
+        assert_eq!(buy.shares, best_ask.size);
+        assert!(buy.is_complete);
+        let sell = handle!(MarketOrderFill::simulate(&book, Side::Sell, Decimal::MAX), SimulateFailed);
+        assert!(!sell.is_complete);
+        assert!(sell.worst_price <= best_bid.price);
+        assert!(sell.average_price <= best_bid.price);
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum MarketOrderFillTestError {
+        #[error("failed to load orderbook fixture")]
+        FixtureOrderBookFailed { source: FixtureOrderBookError },
+        #[error("best ask not found")]
+        BestAskNotFound,
+        #[error("best bid not found")]
+        BestBidNotFound,
+        #[error("failed to compute buy amount")]
+        CheckedMulFailed,
+        #[error("failed to simulate market order")]
+        SimulateFailed { source: MarketOrderFillSimulateError },
+    }
+}
@@ -0,0 +1,46 @@
+use crate::ClobMarketResponsePrecise;
+use serde::Serialize;
+use strum::{Display, EnumIter, EnumString};
+
+/// A boolean feature of [`ClobMarketResponsePrecise`] (used by [`ImplicationMiner`](crate::ImplicationMiner))
+#[derive(Display, EnumIter, EnumString, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, Debug)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MarketResponseFeature {
+    Active,
+    Closed,
+    Archived,
+    AcceptingOrders,
+    EnableOrderBook,
+    NegRisk,
+    #[strum(serialize = "is_50_50_outcome")]
+    #[serde(rename = "is_50_50_outcome")]
+    Is5050Outcome,
+    NotificationsEnabled,
+    HasConditionId,
+    HasQuestionId,
+    HasNegRiskMarketId,
+    HasNegRiskRequestId,
+    HasAcceptingOrderTimestamp,
+}
+
+impl MarketResponseFeature {
+    pub fn value(&self, market: &ClobMarketResponsePrecise) -> bool {
+        use MarketResponseFeature::*;
+        match self {
+            Active => market.active,
+            Closed => market.closed,
+            Archived => market.archived,
+            AcceptingOrders => market.accepting_orders,
+            EnableOrderBook => market.enable_order_book,
+            NegRisk => market.neg_risk,
+            Is5050Outcome => market.is_50_50_outcome,
+            NotificationsEnabled => market.notifications_enabled,
+            HasConditionId => market.condition_id.is_some(),
+            HasQuestionId => market.question_id.is_some(),
+            HasNegRiskMarketId => market.neg_risk_market_id.is_some(),
+            HasNegRiskRequestId => market.neg_risk_request_id.is_some(),
+            HasAcceptingOrderTimestamp => market.accepting_order_timestamp.is_some(),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use crate::MarketResponseFeature;
+use derive_new::new;
+use serde::{Serialize, Serializer};
+use std::fmt::{self, Display, Formatter};
+
+/// A feature or its negation
+#[derive(new, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, Debug)]
+pub struct MarketResponseLiteral {
+    pub feature: MarketResponseFeature,
+    pub value: bool,
+}
+
+impl MarketResponseLiteral {
+    pub fn holds(&self, values: &[bool]) -> bool {
+        values.get(self.feature as usize).copied() == Some(self.value)
+    }
+}
+
+impl Display for MarketResponseLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.value { write!(f, "{}", self.feature) } else { write!(f, "!{}", self.feature) }
+    }
+}
+
+impl Serialize for MarketResponseLiteral {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
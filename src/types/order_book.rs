@@ -0,0 +1,329 @@
+use crate::{Amount, BookSide, ConditionId, Level, OrderBookSummaryResponsePrecise, Price, Side, TokenId};
+use errgonomic::handle_bool;
+use rust_decimal::Decimal;
+use thiserror::Error;
+use time::OffsetDateTime;
+
+/// A local order book that applies incremental price level updates
+///
+/// Both sides are [`BookSide`]s sorted by ascending price, so the best bid is the last level of `bids` and the best ask is the first level of `asks`.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct OrderBook {
+    condition_id: ConditionId,
+    token_id: TokenId,
+    updated_at: OffsetDateTime,
+    hash: Option<String>,
+    last_trade_price: Option<Price>,
+    min_order_size: Amount,
+    min_tick_size: Price,
+    neg_risk: bool,
+    bids: BookSide,
+    asks: BookSide,
+}
+
+impl OrderBook {
+    pub fn condition_id(&self) -> ConditionId {
+        self.condition_id
+    }
+
+    pub fn token_id(&self) -> TokenId {
+        self.token_id
+    }
+
+    pub fn updated_at(&self) -> OffsetDateTime {
+        self.updated_at
+    }
+
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+
+    pub fn last_trade_price(&self) -> Option<Price> {
+        self.last_trade_price
+    }
+
+    pub fn min_order_size(&self) -> Amount {
+        self.min_order_size
+    }
+
+    pub fn min_tick_size(&self) -> Price {
+        self.min_tick_size
+    }
+
+    pub fn neg_risk(&self) -> bool {
+        self.neg_risk
+    }
+
+    pub fn bids(&self) -> &BookSide {
+        &self.bids
+    }
+
+    pub fn asks(&self) -> &BookSide {
+        &self.asks
+    }
+
+    /// Returns the bids for [`Side::Buy`] and the asks for [`Side::Sell`]
+    pub fn side(&self, side: Side) -> &BookSide {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    fn side_mut(&mut self, side: Side) -> &mut BookSide {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    /// Sets the size of the price level (removes the level if the size is zero)
+    pub fn set_level(&mut self, side: Side, level: Level) -> Result<(), OrderBookSetLevelError> {
+        use OrderBookSetLevelError::*;
+        let Level {
+            price,
+            size,
+        } = level;
+        handle_bool!(size.is_sign_negative(), NegativeSize, side, price, size);
+        self.side_mut(side).set(price, size);
+        Ok(())
+    }
+
+    /// Applies the price level updates and records the `hash` and `updated_at` of the resulting book
+    ///
+    /// The updates are applied in order, so the book may be partially updated if an error is returned.
+    pub fn apply(&mut self, updates: impl IntoIterator<Item = (Side, Level)>, hash: Option<String>, updated_at: OffsetDateTime) -> Result<(), OrderBookSetLevelError> {
+        updates
+            .into_iter()
+            .try_for_each(|(side, level)| self.set_level(side, level))?;
+        self.hash = hash;
+        self.updated_at = updated_at;
+        Ok(())
+    }
+
+    /// Replaces all price levels with a full snapshot of both sides
+    pub fn replace_levels(&mut self, bids: BookSide, asks: BookSide, hash: Option<String>, updated_at: OffsetDateTime) {
+        self.bids = bids;
+        self.asks = asks;
+        self.hash = hash;
+        self.updated_at = updated_at;
+    }
+
+    pub fn set_last_trade_price(&mut self, last_trade_price: Price) {
+        self.last_trade_price = Some(last_trade_price);
+    }
+
+    pub fn set_min_tick_size(&mut self, min_tick_size: Price) {
+        self.min_tick_size = min_tick_size;
+    }
+
+    pub fn best_bid(&self) -> Option<Level> {
+        self.bids.max()
+    }
+
+    pub fn best_ask(&self) -> Option<Level> {
+        self.asks.min()
+    }
+
+    /// Returns the best level of the side
+    pub fn best(&self, side: Side) -> Option<Level> {
+        match side {
+            Side::Buy => self.best_bid(),
+            Side::Sell => self.best_ask(),
+        }
+    }
+
+    /// Returns the midpoint between the best bid and the best ask (`None` if either side is empty)
+    pub fn mid_price(&self) -> Option<Price> {
+        let best_bid = self.best_bid()?;
+        let best_ask = self.best_ask()?;
+        best_bid
+            .price
+            .checked_add(best_ask.price)?
+            .checked_div(Decimal::TWO)
+    }
+
+    /// Returns the difference between the best ask and the best bid (`None` if either side is empty)
+    pub fn spread(&self) -> Option<Price> {
+        let best_bid = self.best_bid()?;
+        let best_ask = self.best_ask()?;
+        best_ask.price.checked_sub(best_bid.price)
+    }
+
+    pub fn is_crossed(&self) -> bool {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(best_bid), Some(best_ask)) => best_bid.price >= best_ask.price,
+            _ => false,
+        }
+    }
+
+    /// Returns the size at the price level (zero if there is no such level)
+    pub fn depth_at(&self, side: Side, price: Price) -> Amount {
+        self.side(side).get(&price).copied().unwrap_or(Amount::ZERO)
+    }
+
+    /// Returns the total size of the levels that are at least as good as the `price` (bids at or above the price, asks at or below the price)
+    ///
+    /// Returns `None` on overflow.
+    pub fn cumulative_depth(&self, side: Side, price: Price) -> Option<Amount> {
+        let levels = match side {
+            Side::Buy => self.bids.range(price..),
+            Side::Sell => self.asks.range(..=price),
+        };
+        levels
+            .map(|(_, size)| size)
+            .try_fold(Amount::ZERO, |total, size| total.checked_add(*size))
+    }
+
+    /// Returns the levels of the side from the best to the worst price
+    pub fn levels(&self, side: Side) -> Box<dyn Iterator<Item = Level> + '_> {
+        match side {
+            Side::Buy => Box::new(self.bids.iter().rev().map(Level::from)),
+            Side::Sell => Box::new(self.asks.iter().map(Level::from)),
+        }
+    }
+}
+
+impl From<OrderBookSummaryResponsePrecise> for OrderBook {
+    fn from(orderbook: OrderBookSummaryResponsePrecise) -> Self {
+        let OrderBookSummaryResponsePrecise {
+            condition_id,
+            token_id,
+            updated_at,
+            hash,
+            last_trade_price,
+            min_order_size,
+            min_tick_size,
+            neg_risk,
+            bids,
+            asks,
+        } = orderbook;
+        let bids = BookSide::from(bids);
+        let asks = BookSide::from(asks);
+        Self {
+            condition_id,
+            token_id,
+            updated_at,
+            hash,
+            last_trade_price,
+            min_order_size,
+            min_tick_size,
+            neg_risk,
+            bids,
+            asks,
+        }
+    }
+}
+
+/// Note that the levels are sorted by ascending price, so the order may differ from the original response
+impl From<OrderBook> for OrderBookSummaryResponsePrecise {
+    fn from(orderbook: OrderBook) -> Self {
+        let OrderBook {
+            condition_id,
+            token_id,
+            updated_at,
+            hash,
+            last_trade_price,
+            min_order_size,
+            min_tick_size,
+            neg_risk,
+            bids,
+            asks,
+        } = orderbook;
+        Self {
+            condition_id,
+            token_id,
+            updated_at,
+            hash,
+            last_trade_price,
+            min_order_size,
+            min_tick_size,
+            neg_risk,
+            bids: bids.into(),
+            asks: asks.into(),
+        }
+    }
+}
+
+#[derive(Error, Copy, Clone, Debug)]
+pub enum OrderBookSetLevelError {
+    #[error("size '{size}' of the price level '{price}' on the '{side:?}' side is negative")]
+    NegativeSize { side: Side, price: Price, size: Amount },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureOrderBookError, fixture_order_book};
+    use errgonomic::{handle, handle_opt};
+
+    #[test]
+    fn must_apply_level_updates() -> Result<(), OrderBookTestError> {
+        use OrderBookTestError::*;
+        let mut orderbook = fixture_orderbook()?;
+        let best_bid = handle_opt!(orderbook.best_bid(), BestBidNotFound);
+        let best_ask = handle_opt!(orderbook.best_ask(), BestAskNotFound);
+        let better_bid_price = handle_opt!(best_bid.price.checked_add(orderbook.min_tick_size()), CheckedAddFailed);
+        let better_bid = Level::from((better_bid_price, Decimal::TEN));
+        let removed_ask = Level::from((best_ask.price, Decimal::ZERO));
+        let updated_at = orderbook.updated_at();
+        handle!(orderbook.apply([(Side::Buy, better_bid), (Side::Sell, removed_ask)], Some("hash".to_string()), updated_at), ApplyFailed);
+        assert_eq!(orderbook.best_bid(), Some(better_bid));
+        assert_ne!(orderbook.best_ask().map(|level| level.price), Some(best_ask.price));
+        assert_eq!(orderbook.depth_at(Side::Sell, best_ask.price), Decimal::ZERO);
+        assert_eq!(orderbook.hash(), Some("hash"));
+        assert!(orderbook.bids().keys().is_sorted());
+        assert!(orderbook.asks().keys().is_sorted());
+        Ok(())
+    }
+
+    #[test]
+    fn must_compute_cumulative_depth() -> Result<(), OrderBookTestError> {
+        use OrderBookTestError::*;
+        let orderbook = fixture_orderbook()?;
+        let best_bid = handle_opt!(orderbook.best_bid(), BestBidNotFound);
+        let best_ask = handle_opt!(orderbook.best_ask(), BestAskNotFound);
+        let total_bids = orderbook
+            .bids()
+            .values()
+            .try_fold(Amount::ZERO, |total, size| total.checked_add(*size));
+        let total_asks = orderbook
+            .asks()
+            .values()
+            .try_fold(Amount::ZERO, |total, size| total.checked_add(*size));
+        assert_eq!(orderbook.cumulative_depth(Side::Buy, best_bid.price), Some(best_bid.size));
+        assert_eq!(orderbook.cumulative_depth(Side::Sell, best_ask.price), Some(best_ask.size));
+        assert_eq!(orderbook.cumulative_depth(Side::Buy, Price::ZERO), total_bids);
+        assert_eq!(orderbook.cumulative_depth(Side::Sell, Price::MAX), total_asks);
+        assert_eq!(orderbook.spread(), best_ask.price.checked_sub(best_bid.price));
+        Ok(())
+    }
+
+    #[test]
+    fn must_reject_negative_size() -> Result<(), OrderBookTestError> {
+        let mut orderbook = fixture_orderbook()?;
+        let result = orderbook.set_level(Side::Buy, Level::from((Decimal::new(5, 1), Decimal::NEGATIVE_ONE)));
+        assert!(matches!(result, Err(OrderBookSetLevelError::NegativeSize { .. })));
+        Ok(())
+    }
+
+    fn fixture_orderbook() -> Result<OrderBook, OrderBookTestError> {
+        use OrderBookTestError::*;
+        let orderbook = handle!(fixture_order_book(), FixtureOrderBookFailed);
+        Ok(OrderBook::from(orderbook))
+    }
+
+    #[derive(Error, Debug)]
+    enum OrderBookTestError {
+        #[error("failed to load orderbook fixture")]
+        FixtureOrderBookFailed { source: FixtureOrderBookError },
+        #[error("best bid not found")]
+        BestBidNotFound,
+        #[error("best ask not found")]
+        BestAskNotFound,
+        #[error("failed to add tick size to best bid price")]
+        CheckedAddFailed,
+        #[error("failed to apply level updates")]
+        ApplyFailed { source: OrderBookSetLevelError },
+    }
+}
@@ -0,0 +1,15 @@
+use crate::{ClobClientOrderBookError, OrderBookSummaryResponsePrecise};
+
+/// The result of [`ClobClient::order_books`](crate::ClobClient::order_books): the order books that were fetched and converted, and the failures of the other tokens
+#[derive(Default, Debug)]
+pub struct OrderBookBatch {
+    pub order_books: Vec<OrderBookSummaryResponsePrecise>,
+    pub failures: Vec<ClobClientOrderBookError>,
+}
+
+impl OrderBookBatch {
+    pub fn append(&mut self, mut other: Self) {
+        self.order_books.append(&mut other.order_books);
+        self.failures.append(&mut other.failures);
+    }
+}
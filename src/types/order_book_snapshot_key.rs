@@ -0,0 +1,89 @@
+use crate::{TokenId, sortable_timestamp_bytes, sortable_timestamp_nanos};
+use derive_new::new;
+use errgonomic::{handle, handle_opt};
+use thiserror::Error;
+use time::OffsetDateTime;
+use time::error::ComponentRange;
+
+/// The key of an entry in [`CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE`](crate::CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE)
+///
+/// The bytes are `token_id` (32 bytes, big-endian) followed by `updated_at` in unix nanoseconds (16 bytes, big-endian, sign bit flipped), so the lexicographic order of the bytes matches the `(token_id, updated_at)` order.
+#[derive(new, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct OrderBookSnapshotKey {
+    pub token_id: TokenId,
+    pub updated_at: OffsetDateTime,
+}
+
+impl OrderBookSnapshotKey {
+    pub const TOKEN_ID_LEN: usize = 32;
+    pub const UPDATED_AT_LEN: usize = 16;
+    /// `TOKEN_ID_LEN + UPDATED_AT_LEN`
+    pub const LEN: usize = 48;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        let (token_id_bytes, updated_at_bytes) = bytes.split_at_mut(Self::TOKEN_ID_LEN);
+        token_id_bytes.copy_from_slice(&Self::token_id_prefix(self.token_id));
+        updated_at_bytes.copy_from_slice(&Self::updated_at_bytes(self.updated_at));
+        bytes
+    }
+
+    /// Returns the prefix that is shared by the keys of all snapshots of the token
+    pub fn token_id_prefix(token_id: TokenId) -> [u8; Self::TOKEN_ID_LEN] {
+        token_id.to_be_bytes::<{ Self::TOKEN_ID_LEN }>()
+    }
+
+    fn updated_at_bytes(updated_at: OffsetDateTime) -> [u8; Self::UPDATED_AT_LEN] {
+        sortable_timestamp_bytes(updated_at)
+    }
+}
+
+impl TryFrom<&[u8]> for OrderBookSnapshotKey {
+    type Error = ConvertBytesToOrderBookSnapshotKeyError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        use ConvertBytesToOrderBookSnapshotKeyError::*;
+        let (token_id_bytes, updated_at_bytes) = handle_opt!(bytes.split_at_checked(Self::TOKEN_ID_LEN), InvalidLength, len: bytes.len());
+        let token_id_array = handle!(<[u8; Self::TOKEN_ID_LEN]>::try_from(token_id_bytes), InvalidLength, len: bytes.len());
+        let updated_at_array = handle!(<[u8; Self::UPDATED_AT_LEN]>::try_from(updated_at_bytes), InvalidLength, len: bytes.len());
+        let token_id = TokenId::from_be_bytes(token_id_array);
+        let nanos = sortable_timestamp_nanos(updated_at_array);
+        let updated_at = handle!(OffsetDateTime::from_unix_timestamp_nanos(nanos), FromUnixTimestampNanosFailed, nanos);
+        Ok(Self::new(token_id, updated_at))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ConvertBytesToOrderBookSnapshotKeyError {
+    #[error("invalid order book snapshot key length: expected {expected}, got {len}", expected = OrderBookSnapshotKey::LEN)]
+    InvalidLength { len: usize },
+    #[error("failed to convert '{nanos}' nanoseconds to timestamp")]
+    FromUnixTimestampNanosFailed { source: ComponentRange, nanos: i128 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn must_order_bytes_by_token_id_then_updated_at() {
+        let token_id = TokenId::from(7u64);
+        let keys = [
+            OrderBookSnapshotKey::new(token_id, datetime!(1969-12-31 23:59:59 UTC)),
+            OrderBookSnapshotKey::new(token_id, datetime!(2025-01-01 00:00:00 UTC)),
+            OrderBookSnapshotKey::new(token_id, datetime!(2025-01-01 00:00:01 UTC)),
+            OrderBookSnapshotKey::new(TokenId::from(8u64), datetime!(1970-01-01 00:00:00 UTC)),
+        ];
+        let bytes = keys.map(|key| key.to_bytes());
+        assert!(bytes.is_sorted());
+    }
+
+    #[test]
+    fn must_round_trip() -> Result<(), ConvertBytesToOrderBookSnapshotKeyError> {
+        let key = OrderBookSnapshotKey::new(TokenId::from(u64::MAX), datetime!(2025-06-15 12:34:56.789 UTC));
+        let key_round_trip = OrderBookSnapshotKey::try_from(key.to_bytes().as_slice())?;
+        assert_eq!(key, key_round_trip);
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::RkyvOffsetDateTime;
-use crate::{BidAskCrossError, BookSideMap, ConditionId, ConvertVecOrderSummaryToBookSideError, RkyvDecimal, TimestampVisitor, TokenId, UintAsString, from_chrono_date_time, into_chrono_date_time};
+use crate::{BidAskCrossError, BookSideMap, CLOB_ORDER_BOOK_SUMMARY_RESPONSE_KEYSPACE, CacheEntity, ConditionId, ConvertVecOrderSummaryToBookSideError, RkyvDecimal, TimestampVisitor, TokenId, UintAsString, from_chrono_date_time, into_chrono_date_time};
 use chrono::{DateTime, Utc};
 use derive_more::{From, Into};
 use errgonomic::handle;
@@ -56,6 +56,15 @@ impl OrderBookSummaryResponsePrecise {
     }
 }
 
+/// Only the latest order book of every token is stored in this keyspace (the history is stored in [`CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE`](crate::CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE))
+impl CacheEntity for OrderBookSummaryResponsePrecise {
+    const KEYSPACE: &'static str = CLOB_ORDER_BOOK_SUMMARY_RESPONSE_KEYSPACE;
+
+    fn key(&self) -> String {
+        self.token_id.to_string()
+    }
+}
+
 impl TryFrom<OrderBookSummaryResponse> for OrderBookSummaryResponsePrecise {
     type Error = ConvertOrderBookSummaryResponseToOrderbookError;
 
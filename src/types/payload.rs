@@ -4,7 +4,10 @@ use serde::{Deserialize, Serialize};
 
 #[derive(new, From, Into, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Default, Hash, Clone, Debug)]
 pub struct Payload<T> {
+    /// Some endpoints return only `data` and `next_cursor`
+    #[serde(default)]
     pub limit: u64,
+    #[serde(default)]
     pub count: u64,
     pub next_cursor: String,
     pub data: Vec<T>,
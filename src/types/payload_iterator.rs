@@ -1,71 +1,207 @@
-use crate::{NEXT_CURSOR_STOP, NextCursor, Payload};
+use crate::{NEXT_CURSOR_START, NEXT_CURSOR_STOP, NextCursor, Payload, SendCheckedError, send_checked};
+use core::pin::Pin;
+use core::task::Poll::*;
+use core::task::{Context, Poll, ready};
+use errgonomic::handle;
 use futures::Stream;
+use reqwest::{Client, RequestBuilder};
 use serde::de::DeserializeOwned;
-use std::pin::{Pin, pin};
-use std::task::Poll::*;
-use std::task::{Context, Poll};
+use thiserror::Error;
 use url::Url;
 
+type PayloadFuture<T> = Pin<Box<dyn Future<Output = Result<Payload<T>, PayloadIteratorFetchError>> + Send>>;
+
+/// A stream of pages from a cursor-paginated endpoint that returns `{data, next_cursor}` (e.g. the CLOB `/markets`)
+///
+/// The stream ends when the endpoint returns [`NEXT_CURSOR_STOP`], when the page limit is reached, or after the first error (like [`get_page_stream`](crate::get_page_stream)).
 pub struct PayloadIterator<T> {
+    client: Client,
     url: Url,
-    next_cursor: String,
-    #[allow(dead_code)]
-    future: Option<Box<dyn Future<Output = Payload<T>>>>,
+    query: Vec<(String, String)>,
+    next_cursor: NextCursor,
+    page_limit: Option<usize>,
+    page_count: usize,
+    failed: bool,
+    future: Option<PayloadFuture<T>>,
 }
 
 impl<T> PayloadIterator<T> {
     pub fn new(url: impl Into<Url>) -> Self {
-        Self::new_with_cursor(url, "")
+        Self::new_with_cursor(url, NEXT_CURSOR_START)
     }
 
     pub fn new_with_cursor(url: impl Into<Url>, next_cursor: impl Into<NextCursor>) -> Self {
         Self {
+            client: Client::new(),
             url: url.into(),
+            query: vec![],
             next_cursor: next_cursor.into(),
+            page_limit: None,
+            page_count: 0,
+            failed: false,
             future: None,
         }
     }
+
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Appends a query param to every page request (the `next_cursor` param is appended by the iterator)
+    pub fn with_query_pair(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_page_limit(mut self, page_limit: usize) -> Self {
+        self.page_limit = Some(page_limit);
+        self
+    }
+
+    /// Returns the cursor of the next page (it can be used to resume the iteration later)
+    pub fn next_cursor(&self) -> &str {
+        &self.next_cursor
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.failed
+            || self.next_cursor == NEXT_CURSOR_STOP
+            || self
+                .page_limit
+                .is_some_and(|page_limit| self.page_count >= page_limit)
+    }
+
+    pub fn page_url(&self) -> Url {
+        let mut url = self.url.clone();
+        url.query_pairs_mut()
+            .extend_pairs(&self.query)
+            .append_pair("next_cursor", &self.next_cursor);
+        url
+    }
+}
+
+impl<T> PayloadIterator<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    async fn fetch(request: RequestBuilder, url: Url) -> Result<Payload<T>, PayloadIteratorFetchError> {
+        use PayloadIteratorFetchError::*;
+        let response = handle!(send_checked(request).await, SendFailed, url);
+        let payload = handle!(response.json::<Payload<T>>().await, JsonFailed, url);
+        Ok(payload)
+    }
 }
 
 impl<T> Stream for PayloadIterator<T>
 where
     T: DeserializeOwned + Send + 'static,
 {
-    type Item = Result<Vec<T>, reqwest::Error>;
+    type Item = Result<Vec<T>, PayloadIteratorFetchError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        if self.is_final() {
-            Ready(None)
-        } else {
-            let mut url = self.url.clone();
-            url.query_pairs_mut()
-                .append_pair("next_cursor", &self.next_cursor);
-            let response_result = match pin!(reqwest::get(url)).poll(cx) {
-                Ready(resp) => resp,
-                Pending => return Pending,
-            };
-            let response = match response_result {
-                Ok(response) => response,
-                Err(err) => return Ready(Some(Err(err))),
-            };
-            let payload_result: reqwest::Result<Payload<T>> = match pin!(response.json()).poll(cx) {
-                Ready(result) => result,
-                Pending => return Pending,
-            };
-            let payload = match payload_result {
-                Ok(payload) => payload,
-                Err(err) => return Ready(Some(Err(err))),
-            };
-
-            self.next_cursor = payload.next_cursor;
-
-            Ready(Some(Ok(payload.data)))
+        let this = &mut *self;
+        if this.future.is_none() {
+            if this.is_final() {
+                return Ready(None);
+            }
+            let url = this.page_url();
+            let request = this.client.get(url.clone());
+            this.future = Some(Box::pin(Self::fetch(request, url)));
+        }
+        let Some(future) = this.future.as_mut() else {
+            return Ready(None);
+        };
+        let result = ready!(future.as_mut().poll(cx));
+        this.future = None;
+        match result {
+            Ok(payload) => {
+                this.next_cursor = payload.next_cursor;
+                this.page_count = this.page_count.saturating_add(1);
+                Ready(Some(Ok(payload.data)))
+            }
+            Err(error) => {
+                this.failed = true;
+                Ready(Some(Err(error)))
+            }
         }
     }
 }
 
-impl<T> PayloadIterator<T> {
-    pub fn is_final(&self) -> bool {
-        self.next_cursor == NEXT_CURSOR_STOP
+#[derive(Error, Debug)]
+pub enum PayloadIteratorFetchError {
+    #[error("failed to fetch page from '{url}'")]
+    SendFailed { source: SendCheckedError, url: Url },
+    #[error("failed to parse page from '{url}'")]
+    JsonFailed { source: reqwest::Error, url: Url },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockHttpResponse, MockHttpServer, MockHttpServerStartError};
+    use futures::{StreamExt, TryStreamExt};
+
+    fn page(data: &str, next_cursor: &str) -> MockHttpResponse {
+        MockHttpResponse::new(200, format!(r#"{{"data":{data},"next_cursor":"{next_cursor}"}}"#))
+    }
+
+    fn markets_url(server: &MockHttpServer) -> Result<Url, url::ParseError> {
+        Url::parse(&format!("{}/markets", server.url))
+    }
+
+    #[tokio::test]
+    async fn must_follow_next_cursor_until_stop() -> Result<(), MustPaginateError> {
+        use MustPaginateError::*;
+        let server = handle!(MockHttpServer::start(vec![page("[1,2]", "MQ=="), page("[3]", NEXT_CURSOR_STOP)]).await, StartFailed);
+        let url = handle!(markets_url(&server), ParseUrlFailed);
+        let iterator = PayloadIterator::<u64>::new(url).with_query_pair("active", "true");
+        let pages = handle!(iterator.try_collect::<Vec<_>>().await, FetchFailed);
+        assert_eq!(pages, vec![vec![1, 2], vec![3]]);
+        assert_eq!(
+            server.requests(),
+            vec![
+                "/markets?active=true&next_cursor=",
+                "/markets?active=true&next_cursor=MQ%3D%3D"
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_stop_at_page_limit() -> Result<(), MustPaginateError> {
+        use MustPaginateError::*;
+        let server = handle!(MockHttpServer::start(vec![page("[1]", "MQ==")]).await, StartFailed);
+        let url = handle!(markets_url(&server), ParseUrlFailed);
+        let mut iterator = PayloadIterator::<u64>::new(url).with_page_limit(3);
+        let mut pages = vec![];
+        while let Some(page) = iterator.next().await {
+            pages.push(handle!(page, FetchFailed));
+        }
+        assert_eq!(pages.len(), 3);
+        assert_eq!(server.request_count(), 3);
+        assert_eq!(iterator.next_cursor(), "MQ==");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_stop_after_error() -> Result<(), MustPaginateError> {
+        use MustPaginateError::*;
+        let server = handle!(MockHttpServer::start(vec![MockHttpResponse::new(500, "")]).await, StartFailed);
+        let url = handle!(markets_url(&server), ParseUrlFailed);
+        let results = PayloadIterator::<u64>::new(url).collect::<Vec<_>>().await;
+        assert!(matches!(results.as_slice(), [Err(PayloadIteratorFetchError::SendFailed { .. })]));
+        assert_eq!(server.request_count(), 1);
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum MustPaginateError {
+        #[error("failed to start mock server")]
+        StartFailed { source: MockHttpServerStartError },
+        #[error("failed to parse url")]
+        ParseUrlFailed { source: url::ParseError },
+        #[error("failed to fetch page")]
+        FetchFailed { source: PayloadIteratorFetchError },
     }
 }
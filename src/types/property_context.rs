@@ -0,0 +1,71 @@
+use crate::{ClobMarket, ClobMarketResponsePrecise, GammaEvent, OrderBookSummaryResponsePrecise, Repository, RepositoryGetError, RepositoryOpenError, RepositoryReadEntryError, TokenId};
+use errgonomic::handle;
+use fjall::{SingleWriterTxDatabase, Snapshot};
+use thiserror::Error;
+
+/// The data that is available to every [`Property`](crate::Property) (the snapshot and the repositories to look up the related values)
+pub struct PropertyContext {
+    pub snapshot: Snapshot,
+    pub market_response_repository: Repository<ClobMarketResponsePrecise>,
+    pub market_repository: Repository<ClobMarket>,
+    pub orderbook_repository: Repository<OrderBookSummaryResponsePrecise>,
+    pub event_repository: Repository<GammaEvent>,
+}
+
+impl PropertyContext {
+    pub fn open(db: &SingleWriterTxDatabase) -> Result<Self, PropertyContextOpenError> {
+        use PropertyContextOpenError::*;
+        let market_response_repository = handle!(Repository::open(db), RepositoryOpenFailed);
+        let market_repository = handle!(Repository::open(db), RepositoryOpenFailed);
+        let orderbook_repository = handle!(Repository::open(db), RepositoryOpenFailed);
+        let event_repository = handle!(Repository::open(db), RepositoryOpenFailed);
+        let snapshot = db.read_tx();
+        Ok(Self {
+            snapshot,
+            market_response_repository,
+            market_repository,
+            orderbook_repository,
+            event_repository,
+        })
+    }
+
+    pub fn has_order_book(&self, token_id: TokenId) -> Result<bool, PropertyContextReadError> {
+        use PropertyContextReadError::*;
+        Ok(handle!(
+            self.orderbook_repository
+                .contains_key(&self.snapshot, &token_id.to_string()),
+            GetFailed
+        ))
+    }
+
+    pub fn order_book(&self, token_id: TokenId) -> Result<Option<OrderBookSummaryResponsePrecise>, PropertyContextReadError> {
+        use PropertyContextReadError::*;
+        Ok(handle!(
+            self.orderbook_repository
+                .get(&self.snapshot, &token_id.to_string()),
+            GetFailed
+        ))
+    }
+
+    pub fn clob_markets(&self) -> impl Iterator<Item = Result<ClobMarket, PropertyContextReadError>> + '_ {
+        self.market_repository.iter(&self.snapshot).map(|entry| {
+            use PropertyContextReadError::*;
+            let (_key, market) = handle!(entry, ReadEntryFailed);
+            Ok(market)
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PropertyContextOpenError {
+    #[error("failed to open repository")]
+    RepositoryOpenFailed { source: RepositoryOpenError },
+}
+
+#[derive(Error, Debug)]
+pub enum PropertyContextReadError {
+    #[error("failed to get entry")]
+    GetFailed { source: RepositoryGetError },
+    #[error("failed to read entry")]
+    ReadEntryFailed { source: RepositoryReadEntryError },
+}
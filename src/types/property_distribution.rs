@@ -3,19 +3,49 @@ use derive_more::{From, Into};
 use derive_new::new;
 
 #[derive(new, From, Into, Ord, PartialOrd, Eq, PartialEq, Default, Hash, Clone, Debug)]
-pub struct PropertyDistribution<const LIMIT: usize, T> {
+pub struct PropertyDistribution<T> {
+    /// The name of the keyspace that contains the values
+    pub keyspace: String,
     pub name: String,
-    pub success: PropertyStats<LIMIT, T>,
-    pub failure: PropertyStats<LIMIT, T>,
+    /// See [`Property::is_invariant`](crate::Property::is_invariant)
+    pub is_invariant: bool,
+    /// The maximum number of examples per outcome
+    pub example_limit: usize,
+    pub success: PropertyStats<T>,
+    pub failure: PropertyStats<T>,
+    /// The values for which the property couldn't be evaluated (see [`Property::holds`](crate::Property::holds))
+    pub error: PropertyStats<T>,
 }
 
-impl<const LIMIT: usize, T> PropertyDistribution<LIMIT, T> {
+impl<T> PropertyDistribution<T> {
     pub fn as_simple(&self) -> (&str, usize, usize, usize) {
         let successes = self.success.count;
         let failures = self.failure.count;
         (self.name.as_str(), successes, failures, successes.abs_diff(failures))
     }
+
+    /// Returns true if the property is an invariant and it doesn't hold for some value
+    pub fn is_invariant_violated(&self) -> bool {
+        self.is_invariant && self.failure.count != 0
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error.count != 0
+    }
+
+    pub fn witness_success(&mut self, example: T) {
+        self.success.witness(example, self.example_limit)
+    }
+
+    pub fn witness_failure(&mut self, example: T) {
+        self.failure.witness(example, self.example_limit)
+    }
+
+    pub fn witness_error(&mut self, example: T) {
+        self.error.witness(example, self.example_limit)
+    }
 }
+
 mod property_distribution_simple_view;
 
 pub use property_distribution_simple_view::*;
@@ -23,3 +53,7 @@ pub use property_distribution_simple_view::*;
 mod property_distribution_view;
 
 pub use property_distribution_view::*;
+
+mod property_distribution_examples_view;
+
+pub use property_distribution_examples_view::*;
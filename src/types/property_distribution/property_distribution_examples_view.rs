@@ -0,0 +1,43 @@
+use crate::{PropertyDistribution, PropertyDistributionSimpleView, TableRow};
+use derive_more::{From, Into};
+use derive_new::new;
+use serde::Serialize;
+
+/// A [`PropertyDistributionSimpleView`] with the keys of the example values
+#[derive(new, From, Into, Serialize, Ord, PartialOrd, Eq, PartialEq, Default, Hash, Clone, Debug)]
+pub struct PropertyDistributionExamplesView<'a> {
+    #[serde(flatten)]
+    pub simple: PropertyDistributionSimpleView<'a>,
+    pub success_examples: &'a [String],
+    pub failure_examples: &'a [String],
+    pub error_examples: &'a [String],
+}
+
+impl<'a> From<&'a PropertyDistribution<String>> for PropertyDistributionExamplesView<'a> {
+    fn from(distribution: &'a PropertyDistribution<String>) -> Self {
+        Self {
+            simple: PropertyDistributionSimpleView::from(distribution),
+            success_examples: distribution.success.examples.as_slice(),
+            failure_examples: distribution.failure.examples.as_slice(),
+            error_examples: distribution.error.examples.as_slice(),
+        }
+    }
+}
+
+impl<'a> TableRow for PropertyDistributionExamplesView<'a> {
+    fn columns(&self) -> Vec<&'static str> {
+        let mut columns = self.simple.columns();
+        columns.extend(["success_examples", "failure_examples", "error_examples"]);
+        columns
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let mut cells = self.simple.cells();
+        cells.extend([
+            self.success_examples.join(", "),
+            self.failure_examples.join(", "),
+            self.error_examples.join(", "),
+        ]);
+        cells
+    }
+}
@@ -1,32 +1,84 @@
-use crate::PropertyDistribution;
+use crate::{PropertyDistribution, TableRow};
 use derive_more::{From, Into};
 use derive_new::new;
+use rust_decimal::Decimal;
 use serde::Serialize;
 
 #[derive(new, From, Into, Serialize, Ord, PartialOrd, Eq, PartialEq, Default, Hash, Clone, Debug)]
 pub struct PropertyDistributionSimpleView<'a> {
+    pub keyspace: &'a str,
     pub name: &'a str,
+    pub is_invariant: bool,
     pub successes: usize,
     pub failures: usize,
+    pub errors: usize,
     pub abs_diff: usize,
+    /// The share of values for which the property holds (`None` if there are no values)
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub ratio: Option<Decimal>,
 }
 
-impl<'a, const LIMIT: usize, T> From<&'a PropertyDistribution<LIMIT, T>> for PropertyDistributionSimpleView<'a> {
-    fn from(distribution: &'a PropertyDistribution<LIMIT, T>) -> Self {
+impl<'a, T> From<&'a PropertyDistribution<T>> for PropertyDistributionSimpleView<'a> {
+    fn from(distribution: &'a PropertyDistribution<T>) -> Self {
         let PropertyDistribution {
+            keyspace,
             name,
+            is_invariant,
+            example_limit: _,
             success,
             failure,
+            error,
         } = distribution;
+        let keyspace = keyspace.as_str();
         let name = name.as_str();
         let successes = success.count;
         let failures = failure.count;
+        let errors = error.count;
         let abs_diff = successes.abs_diff(failures);
+        let ratio = successes
+            .checked_add(failures)
+            .and_then(|total| Decimal::from(successes).checked_div(Decimal::from(total)));
         Self {
+            keyspace,
             name,
+            is_invariant: *is_invariant,
             successes,
             failures,
+            errors,
             abs_diff,
+            ratio,
         }
     }
 }
+
+impl<'a> TableRow for PropertyDistributionSimpleView<'a> {
+    fn columns(&self) -> Vec<&'static str> {
+        vec![
+            "keyspace",
+            "name",
+            "is_invariant",
+            "successes",
+            "failures",
+            "errors",
+            "abs_diff",
+            "ratio",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let ratio = self
+            .ratio
+            .map(|ratio| ratio.round_dp(4).to_string())
+            .unwrap_or_default();
+        vec![
+            self.keyspace.to_string(),
+            self.name.to_string(),
+            self.is_invariant.to_string(),
+            self.successes.to_string(),
+            self.failures.to_string(),
+            self.errors.to_string(),
+            self.abs_diff.to_string(),
+            ratio,
+        ]
+    }
+}
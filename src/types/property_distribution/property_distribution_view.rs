@@ -1,19 +1,41 @@
-use crate::{PropertyDistribution, PropertyDistributionSimpleView};
+use crate::{PropertyDistribution, PropertyDistributionExamplesView, PropertyDistributionSimpleView, TableRow};
 #[allow(unused_imports)]
 use PropertyDistributionView::*;
 use clap::ValueEnum;
+use serde::Serialize;
 use strum::{Display, EnumDiscriminants};
 
-#[derive(EnumDiscriminants, Display, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug)]
+#[derive(EnumDiscriminants, Display, Serialize, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug)]
 #[strum_discriminants(name(PropertyDistributionViewName), derive(ValueEnum))]
+#[serde(untagged)]
 pub enum PropertyDistributionView<'a> {
     Simple(PropertyDistributionSimpleView<'a>),
+    Examples(PropertyDistributionExamplesView<'a>),
 }
 
 impl<'a> PropertyDistributionView<'a> {}
 
-impl<'a, const LIMIT: usize, T> From<(PropertyDistributionViewName, &'a PropertyDistribution<LIMIT, T>)> for PropertyDistributionSimpleView<'a> {
-    fn from((_name, _distribution): (PropertyDistributionViewName, &'a PropertyDistribution<LIMIT, T>)) -> Self {
-        todo!()
+impl<'a> From<(PropertyDistributionViewName, &'a PropertyDistribution<String>)> for PropertyDistributionView<'a> {
+    fn from((name, distribution): (PropertyDistributionViewName, &'a PropertyDistribution<String>)) -> Self {
+        match name {
+            PropertyDistributionViewName::Simple => Simple(PropertyDistributionSimpleView::from(distribution)),
+            PropertyDistributionViewName::Examples => Examples(PropertyDistributionExamplesView::from(distribution)),
+        }
+    }
+}
+
+impl<'a> TableRow for PropertyDistributionView<'a> {
+    fn columns(&self) -> Vec<&'static str> {
+        match self {
+            Simple(view) => view.columns(),
+            Examples(view) => view.columns(),
+        }
+    }
+
+    fn cells(&self) -> Vec<String> {
+        match self {
+            Simple(view) => view.cells(),
+            Examples(view) => view.cells(),
+        }
     }
 }
@@ -9,3 +9,15 @@ pub fn property_name<T: ?Sized>() -> PropertyName {
         .expect("type name should not be empty")
         .to_owned()
 }
+
+/// Returns true if `input` refers to the property `name` (the comparison ignores the case and the underscores, so both `TokensLenIsTwo` and `tokens_len_is_two` match)
+pub fn property_name_matches(name: &str, input: &str) -> bool {
+    let normalize = |string: &str| {
+        string
+            .chars()
+            .filter(|char| *char != '_')
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    normalize(name) == normalize(input)
+}
@@ -3,25 +3,17 @@ use derive_new::new;
 use serde::{Deserialize, Serialize};
 
 #[derive(new, From, Into, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Default, Hash, Clone, Debug)]
-pub struct PropertyStats<const LIMIT: usize, T> {
+pub struct PropertyStats<T> {
     pub count: usize,
     pub examples: Vec<T>,
 }
 
-impl<const LIMIT: usize, T> PropertyStats<LIMIT, T> {
-    pub fn witness(&mut self, example: T) {
+impl<T> PropertyStats<T> {
+    /// Counts the example, but keeps at most `limit` examples
+    pub fn witness(&mut self, example: T, limit: usize) {
         self.count = self.count.saturating_add(1);
-        if self.examples.len() < LIMIT {
+        if self.examples.len() < limit {
             self.examples.push(example)
         }
     }
 }
-
-// impl<const LIMIT: usize, T> Default for ViolationStats<LIMIT, T> {
-//     fn default() -> Self {
-//         Self {
-//             count: Default::default(),
-//             examples: Default::default(),
-//         }
-//     }
-// }
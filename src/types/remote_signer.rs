@@ -0,0 +1,259 @@
+use crate::{REMOTE_SIGNER_CONNECT_TIMEOUT, REMOTE_SIGNER_REQUEST_TIMEOUT};
+use alloy::dyn_abi::{Error as DynAbiError, TypedData};
+use alloy::primitives::{Address, B256, Bytes, ChainId, Signature, SignatureError};
+use alloy::signers::{Error as SignerError, Result as SignerResult, Signer};
+use alloy::sol_types::{Eip712Domain, SolStruct};
+use async_trait::async_trait;
+use errgonomic::{handle, handle_bool};
+use serde::Serialize;
+use thiserror::Error;
+use url::Url;
+
+/// A signer that delegates signing to an HTTP service, so that the private key never leaves the service
+///
+/// The service must implement three endpoints:
+/// * `GET {url}/address` returns `{"address": "0x..."}`
+/// * `POST {url}/sign-typed-data` accepts the EIP-712 typed data (the `eth_signTypedData_v4` format) and returns `{"signature": "0x..."}` (65 bytes: `r`, `s`, `v`)
+/// * `POST {url}/sign-hash` accepts `{"hash": "0x..."}` and returns `{"signature": "0x..."}`
+///
+/// The orders that are signed by this crate are sent as typed data, so the service can inspect them before signing.
+/// The SDK signs the digests itself (the L1 authentication headers and the orders of the `place-*` commands), so these requests go to `sign-hash`.
+#[derive(Clone, Debug)]
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    url: Url,
+    address: Address,
+    chain_id: Option<ChainId>,
+}
+
+impl RemoteSigner {
+    /// Fetches the address of the signer from the service
+    pub async fn connect(url: Url) -> Result<Self, RemoteSignerConnectError> {
+        use RemoteSignerConnectError::*;
+        let client = handle!(
+            reqwest::Client::builder()
+                .connect_timeout(REMOTE_SIGNER_CONNECT_TIMEOUT)
+                .timeout(REMOTE_SIGNER_REQUEST_TIMEOUT)
+                .build(),
+            ClientBuildFailed
+        );
+        let address_url = Self::endpoint_url(&url, "address");
+        let response = handle!(client.get(address_url.as_str()).send().await, SendFailed, address_url);
+        let response = handle!(response.error_for_status(), ErrorForStatusFailed, address_url);
+        let RemoteSignerAddressResponse {
+            address,
+        } = handle!(response.json::<RemoteSignerAddressResponse>().await, JsonFailed, address_url);
+        Ok(Self {
+            client,
+            url,
+            address,
+            chain_id: None,
+        })
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    async fn sign_typed_data_remotely(&self, typed_data: &TypedData) -> Result<Signature, RemoteSignerSignTypedDataError> {
+        use RemoteSignerSignTypedDataError::*;
+        let hash = handle!(typed_data.eip712_signing_hash(), SigningHashFailed);
+        let signature = handle!(
+            self.request_signature("sign-typed-data", typed_data, &hash)
+                .await,
+            RequestSignatureFailed
+        );
+        Ok(signature)
+    }
+
+    /// Posts the `body` to the `endpoint`, and verifies that the signature of the `hash` has been made by the address of the service (a misconfigured service would otherwise produce orders that are rejected by the exchange)
+    async fn request_signature<B: Serialize + ?Sized>(&self, endpoint: &str, body: &B, hash: &B256) -> Result<Signature, RemoteSignerRequestSignatureError> {
+        use RemoteSignerRequestSignatureError::*;
+        let url = Self::endpoint_url(&self.url, endpoint);
+        let response = handle!(self.client.post(url.as_str()).json(body).send().await, SendFailed, url);
+        let response = handle!(response.error_for_status(), ErrorForStatusFailed, url);
+        let RemoteSignerSignatureResponse {
+            signature,
+        } = handle!(response.json::<RemoteSignerSignatureResponse>().await, JsonFailed, url);
+        let signature = handle!(Signature::try_from(signature.as_ref()), SignatureTryFromFailed, signature);
+        let address = handle!(signature.recover_address_from_prehash(hash), RecoverAddressFailed, signature);
+        handle_bool!(address != self.address, SignerAddressMismatch, expected: self.address, actual: address);
+        Ok(signature)
+    }
+
+    fn endpoint_url(url: &Url, endpoint: &str) -> String {
+        format!("{}/{endpoint}", url.as_str().trim_end_matches('/'))
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    async fn sign_hash(&self, hash: &B256) -> SignerResult<Signature> {
+        let request = RemoteSignerSignHashRequest {
+            hash: *hash,
+        };
+        self.request_signature("sign-hash", &request, hash)
+            .await
+            .map_err(SignerError::other)
+    }
+
+    async fn sign_typed_data<T: SolStruct + Send + Sync>(&self, payload: &T, domain: &Eip712Domain) -> SignerResult<Signature>
+    where
+        Self: Sized,
+    {
+        let typed_data = TypedData::from_struct(payload, Some(domain.clone()));
+        self.sign_dynamic_typed_data(&typed_data).await
+    }
+
+    async fn sign_dynamic_typed_data(&self, payload: &TypedData) -> SignerResult<Signature> {
+        self.sign_typed_data_remotely(payload)
+            .await
+            .map_err(SignerError::other)
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+struct RemoteSignerAddressResponse {
+    address: Address,
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct RemoteSignerSignHashRequest {
+    hash: B256,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+struct RemoteSignerSignatureResponse {
+    signature: Bytes,
+}
+
+#[derive(Error, Debug)]
+pub enum RemoteSignerConnectError {
+    #[error("failed to build HTTP client")]
+    ClientBuildFailed { source: reqwest::Error },
+    #[error("failed to send request to '{url}'")]
+    SendFailed { source: reqwest::Error, url: String },
+    #[error("remote signer returned an error status for '{url}'")]
+    ErrorForStatusFailed { source: reqwest::Error, url: String },
+    #[error("failed to parse the response from '{url}'")]
+    JsonFailed { source: reqwest::Error, url: String },
+}
+
+#[derive(Error, Debug)]
+pub enum RemoteSignerSignTypedDataError {
+    #[error("failed to compute the signing hash of the typed data")]
+    SigningHashFailed { source: DynAbiError },
+    #[error("failed to request the signature of the typed data")]
+    RequestSignatureFailed { source: RemoteSignerRequestSignatureError },
+}
+
+#[derive(Error, Debug)]
+pub enum RemoteSignerRequestSignatureError {
+    #[error("failed to send request to '{url}'")]
+    SendFailed { source: reqwest::Error, url: String },
+    #[error("remote signer returned an error status for '{url}'")]
+    ErrorForStatusFailed { source: reqwest::Error, url: String },
+    #[error("failed to parse the response from '{url}'")]
+    JsonFailed { source: reqwest::Error, url: String },
+    #[error("failed to parse signature '{signature}'")]
+    SignatureTryFromFailed { source: SignatureError, signature: Bytes },
+    #[error("failed to recover the address from signature '{signature}'")]
+    RecoverAddressFailed { source: SignatureError, signature: Signature },
+    #[error("remote signer has signed with '{actual}' instead of '{expected}'")]
+    SignerAddressMismatch { expected: Address, actual: Address },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClobOfflineLimitOrder, ClobOfflineLimitOrderSignError, MockHttpServerStartError, OrderType, Side, start_mock_remote_signer};
+    use alloy::primitives::U256;
+    use alloy::signers::local::{LocalSignerError, PrivateKeySigner};
+    use errgonomic::handle_opt;
+    use polymarket_client_sdk::POLYGON;
+    use rust_decimal::Decimal;
+    use url::ParseError;
+
+    #[tokio::test]
+    async fn must_sign_typed_order_with_remote_signer() -> Result<(), RemoteSignerTestError> {
+        use RemoteSignerTestError::*;
+        let local_signer = handle!("0x0123456789012345678901234567890123456789012345678901234567890123".parse::<PrivateKeySigner>(), PrivateKeyParseFailed);
+        let server = handle!(start_mock_remote_signer(local_signer.clone()).await, StartServerFailed);
+        let url = handle!(Url::parse(&server.url), UrlParseFailed);
+        let remote_signer = handle!(RemoteSigner::connect(url).await, ConnectFailed);
+        assert_eq!(remote_signer.address(), local_signer.address());
+        let order = ClobOfflineLimitOrder {
+            token_id: U256::from(7),
+            side: Side::Sell,
+            price: Decimal::new(5, 1),
+            size: Decimal::TEN,
+            tick_size: Decimal::new(1, 2),
+            neg_risk: false,
+            chain_id: POLYGON,
+            maker: local_signer.address(),
+            taker: Address::ZERO,
+            signature_type: 0,
+            nonce: 0,
+            expiration: 0,
+            fee_rate_bps: 0,
+            order_type: OrderType::Gtc,
+            post_only: false,
+        };
+        let signed_order = handle!(order.sign(&remote_signer).await, SignFailed);
+        let domain = handle_opt!(signed_order.domain.clone(), DomainNotFound);
+        let hash = signed_order
+            .order()
+            .eip712_signing_hash(&domain.eip712_domain());
+        let address = handle!(signed_order.signature.recover_address_from_prehash(&hash), RecoverAddressFailed);
+        assert_eq!(address, local_signer.address());
+        assert_eq!(server.requests(), vec!["/address".to_string(), "/sign-typed-data".to_string()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn must_sign_hash_with_remote_signer() -> Result<(), RemoteSignerTestError> {
+        use RemoteSignerTestError::*;
+        let local_signer = handle!("0x0123456789012345678901234567890123456789012345678901234567890123".parse::<PrivateKeySigner>(), PrivateKeyParseFailed);
+        let server = handle!(start_mock_remote_signer(local_signer.clone()).await, StartServerFailed);
+        let url = handle!(Url::parse(&server.url), UrlParseFailed);
+        let remote_signer = handle!(RemoteSigner::connect(url).await, ConnectFailed);
+        let hash = B256::repeat_byte(7);
+        let signature = handle!(remote_signer.sign_hash(&hash).await, SignHashFailed);
+        let address = handle!(signature.recover_address_from_prehash(&hash), RecoverAddressFailed);
+        assert_eq!(address, local_signer.address());
+        assert_eq!(server.requests(), vec!["/address".to_string(), "/sign-hash".to_string()]);
+        Ok(())
+    }
+
+    #[derive(Error, Debug)]
+    enum RemoteSignerTestError {
+        #[error("failed to parse private key")]
+        PrivateKeyParseFailed { source: LocalSignerError },
+        #[error("failed to start mock remote signer")]
+        StartServerFailed { source: MockHttpServerStartError },
+        #[error("failed to parse mock server URL")]
+        UrlParseFailed { source: ParseError },
+        #[error("failed to connect to remote signer")]
+        ConnectFailed { source: RemoteSignerConnectError },
+        #[error("failed to sign order")]
+        SignFailed { source: ClobOfflineLimitOrderSignError },
+        #[error("failed to sign hash")]
+        SignHashFailed { source: SignerError },
+        #[error("domain not found")]
+        DomainNotFound,
+        #[error("failed to recover address")]
+        RecoverAddressFailed { source: SignatureError },
+    }
+}
@@ -0,0 +1,150 @@
+use crate::TableRow;
+use clap::ValueEnum;
+use errgonomic::handle;
+use serde::Serialize;
+use std::io;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(ValueEnum, Copy, Clone, Debug, Default)]
+#[clap(rename_all = "kebab")]
+pub enum ReportFormat {
+    /// A pretty-printed JSON array
+    Json,
+    /// One JSON object per line
+    #[default]
+    Jsonl,
+    /// A plain text table with aligned columns
+    Table,
+    /// A Markdown table
+    Markdown,
+}
+
+impl ReportFormat {
+    pub fn write<T: Serialize + TableRow>(&self, writer: &mut impl Write, rows: &[T]) -> Result<(), ReportFormatWriteError> {
+        use ReportFormat::*;
+        use ReportFormatWriteError::*;
+        match self {
+            Json => {
+                handle!(serde_json::to_writer_pretty(&mut *writer, rows), SerializeFailed);
+                handle!(writer.write_all(b"\n"), WriteAllFailed);
+            }
+            Jsonl => {
+                for row in rows {
+                    handle!(serde_json::to_writer(&mut *writer, row), SerializeFailed);
+                    handle!(writer.write_all(b"\n"), WriteAllFailed);
+                }
+            }
+            Table => {
+                let lines = Self::table_lines(rows, " ", None);
+                handle!(Self::write_lines(writer, lines), WriteAllFailed);
+            }
+            Markdown => {
+                let lines = Self::table_lines(rows, " | ", Some(("| ", " |")));
+                handle!(Self::write_lines(writer, lines), WriteAllFailed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the header line, the separator line and the row lines (the columns are taken from the first row)
+    fn table_lines<T: TableRow>(rows: &[T], separator: &str, edges: Option<(&str, &str)>) -> Vec<String> {
+        let Some(first) = rows.first() else {
+            return Vec::new();
+        };
+        let columns = first
+            .columns()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let cells = rows.iter().map(TableRow::cells).collect::<Vec<_>>();
+        let widths = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                cells
+                    .iter()
+                    .filter_map(|row| row.get(index))
+                    .chain([column])
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        let dashes = widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>();
+        let (left, right) = edges.unwrap_or_default();
+        let line = |row: &[String]| {
+            let padded = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>();
+            format!("{left}{}{right}", padded.join(separator))
+                .trim_end()
+                .to_string()
+        };
+        [columns, dashes]
+            .iter()
+            .chain(cells.iter())
+            .map(|row| line(row.as_slice()))
+            .collect()
+    }
+
+    fn write_lines(writer: &mut impl Write, lines: Vec<String>) -> Result<(), io::Error> {
+        lines.into_iter().try_for_each(|line| {
+            writer.write_all(line.as_bytes())?;
+            writer.write_all(b"\n")
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ReportFormatWriteError {
+    #[error("failed to serialize report row")]
+    SerializeFailed { source: serde_json::Error },
+    #[error("failed to write report")]
+    WriteAllFailed { source: io::Error },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        name: &'static str,
+        count: usize,
+    }
+
+    impl TableRow for Row {
+        fn columns(&self) -> Vec<&'static str> {
+            vec!["name", "count"]
+        }
+
+        fn cells(&self) -> Vec<String> {
+            vec![self.name.to_string(), self.count.to_string()]
+        }
+    }
+
+    #[test]
+    fn must_write_markdown_table() -> Result<(), ReportFormatWriteError> {
+        let rows = [
+            Row {
+                name: "a",
+                count: 10,
+            },
+            Row {
+                name: "long",
+                count: 2,
+            },
+        ];
+        let mut output = Vec::new();
+        ReportFormat::Markdown.write(&mut output, &rows)?;
+        let expected = "| name | count |\n| ---- | ----- |\n| a    | 10    |\n| long | 2     |\n";
+        assert_eq!(String::from_utf8_lossy(&output), expected);
+        Ok(())
+    }
+}
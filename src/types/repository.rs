@@ -0,0 +1,144 @@
+use crate::{CacheEntity, OpenKeyspaceError, open_keyspace};
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
+use errgonomic::handle;
+use fjall::{Error as FjallError, Guard, Readable, SingleWriterTxDatabase, SingleWriterTxKeyspace, SingleWriterWriteTx, Slice, Snapshot};
+use rkyv::api::high::{HighSerializer, HighValidator};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::de::Pool;
+use rkyv::rancor::{Error as RkyvError, Strategy};
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use rkyv::{Deserialize as RkyvDeserialize, Serialize as RkyvSerialize, from_bytes, to_bytes};
+use thiserror::Error;
+
+/// The typed access to the keyspace of a [`CacheEntity`]
+///
+/// Every command reads and writes the entities through a repository, so the keys are always derived by [`CacheEntity::key`].
+pub struct Repository<T> {
+    pub keyspace: SingleWriterTxKeyspace,
+    pub keyspace_name: &'static str,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Repository<T>
+where
+    T: CacheEntity + for<'a> RkyvSerialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, RkyvError>> + RkyvDeserialize<T, Strategy<Pool, RkyvError>>,
+{
+    pub fn open(db: &SingleWriterTxDatabase) -> Result<Self, RepositoryOpenError> {
+        Self::open_named(db, T::KEYSPACE)
+    }
+
+    /// Opens a keyspace that stores the entities under other keys than [`CacheEntity::key`] (e.g. the order book history in [`CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE`](crate::CLOB_ORDER_BOOK_SNAPSHOTS_KEYSPACE))
+    pub fn open_named(db: &SingleWriterTxDatabase, keyspace_name: &'static str) -> Result<Self, RepositoryOpenError> {
+        use RepositoryOpenError::*;
+        let keyspace = handle!(open_keyspace(db, keyspace_name), OpenKeyspaceFailed);
+        Ok(Self {
+            keyspace,
+            keyspace_name,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn insert(&self, tx: &mut SingleWriterWriteTx, value: &T) -> Result<(), RepositoryInsertError> {
+        use RepositoryInsertError::*;
+        let key = value.key();
+        let bytes = handle!(to_bytes::<RkyvError>(value), SerializeFailed, keyspace: self.keyspace_name, key);
+        tx.insert(&self.keyspace, key, bytes.into_vec());
+        Ok(())
+    }
+
+    /// Inserts the value under an explicit key (for the keyspaces opened by [`Self::open_named`])
+    pub fn insert_with_key(&self, tx: &mut SingleWriterWriteTx, key: impl Into<Slice>, value: &T) -> Result<(), RepositoryInsertError> {
+        use RepositoryInsertError::*;
+        let key = key.into();
+        let bytes = handle!(to_bytes::<RkyvError>(value), SerializeFailed, keyspace: self.keyspace_name, key: String::from_utf8_lossy(&key).into_owned());
+        tx.insert(&self.keyspace, key, bytes.into_vec());
+        Ok(())
+    }
+
+    pub fn remove(&self, tx: &mut SingleWriterWriteTx, key: &str) {
+        tx.remove(&self.keyspace, key);
+    }
+
+    pub fn contains_key(&self, snapshot: &impl Readable, key: &str) -> Result<bool, RepositoryGetError> {
+        use RepositoryGetError::*;
+        Ok(handle!(snapshot.contains_key(&self.keyspace, key), GetFailed, keyspace: self.keyspace_name, key: key.to_string()))
+    }
+
+    pub fn get(&self, snapshot: &impl Readable, key: &str) -> Result<Option<T>, RepositoryGetError> {
+        use RepositoryGetError::*;
+        let value_opt = handle!(snapshot.get(&self.keyspace, key), GetFailed, keyspace: self.keyspace_name, key: key.to_string());
+        match value_opt {
+            Some(value) => Ok(Some(handle!(from_bytes::<T, RkyvError>(value.as_ref()), DeserializeFailed, keyspace: self.keyspace_name, key: key.to_string()))),
+            None => Ok(None),
+        }
+    }
+
+    /// Counts the stored entries without deserializing them (the entries are still read, so that a corrupted entry is reported)
+    pub fn count(&self, snapshot: &Snapshot) -> Result<usize, RepositoryCountError> {
+        use RepositoryCountError::*;
+        let mut count: usize = 0;
+        for guard in snapshot.iter(&self.keyspace) {
+            handle!(guard.into_inner(), IntoInnerFailed, keyspace: self.keyspace_name, count);
+            count = count.saturating_add(1);
+        }
+        Ok(count)
+    }
+
+    /// Returns the entries in key order (the stored key is returned as well, because it may differ from [`CacheEntity::key`] in a database that has not been migrated)
+    pub fn iter<'a>(&'a self, snapshot: &'a Snapshot) -> impl Iterator<Item = Result<(Slice, T), RepositoryReadEntryError>> + 'a {
+        snapshot
+            .iter(&self.keyspace)
+            .map(|guard| self.entry_from_guard(guard))
+    }
+
+    /// Returns the entries with keys within the range in key order
+    pub fn range<'a, K: AsRef<[u8]>, R: RangeBounds<K>>(&'a self, snapshot: &'a Snapshot, range: R) -> impl DoubleEndedIterator<Item = Result<(Slice, T), RepositoryReadEntryError>> + 'a {
+        snapshot
+            .range(&self.keyspace, range)
+            .map(|guard| self.entry_from_guard(guard))
+    }
+
+    fn entry_from_guard(&self, guard: Guard) -> Result<(Slice, T), RepositoryReadEntryError> {
+        use RepositoryReadEntryError::*;
+        let (key, value) = handle!(guard.into_inner(), IntoInnerFailed, keyspace: self.keyspace_name);
+        let value = handle!(from_bytes::<T, RkyvError>(value.as_ref()), DeserializeFailed, keyspace: self.keyspace_name, key);
+        Ok((key, value))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum RepositoryOpenError {
+    #[error("failed to open keyspace")]
+    OpenKeyspaceFailed { source: OpenKeyspaceError },
+}
+
+#[derive(Error, Debug)]
+pub enum RepositoryInsertError {
+    #[error("failed to serialize value for key '{key}' in keyspace '{keyspace}'")]
+    SerializeFailed { source: RkyvError, keyspace: &'static str, key: String },
+}
+
+#[derive(Error, Debug)]
+pub enum RepositoryGetError {
+    #[error("failed to get value for key '{key}' in keyspace '{keyspace}'")]
+    GetFailed { source: FjallError, keyspace: &'static str, key: String },
+    #[error("failed to deserialize value for key '{key}' in keyspace '{keyspace}'")]
+    DeserializeFailed { source: RkyvError, keyspace: &'static str, key: String },
+}
+
+#[derive(Error, Debug)]
+pub enum RepositoryCountError {
+    #[error("failed to read entry in keyspace '{keyspace}' after {count} entries")]
+    IntoInnerFailed { source: FjallError, keyspace: &'static str, count: usize },
+}
+
+#[derive(Error, Debug)]
+pub enum RepositoryReadEntryError {
+    #[error("failed to read entry in keyspace '{keyspace}'")]
+    IntoInnerFailed { source: FjallError, keyspace: &'static str },
+    #[error("failed to deserialize entry in keyspace '{keyspace}'")]
+    DeserializeFailed { source: RkyvError, keyspace: &'static str, key: Slice },
+}
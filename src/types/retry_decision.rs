@@ -0,0 +1,12 @@
+use core::time::Duration;
+
+/// Whether a failed request should be retried (see [`Retryable`](crate::Retryable))
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum RetryDecision {
+    /// The error is permanent (e.g. `404 Not Found`)
+    Fail,
+    /// The error is transient, and the delay is computed by [`RetryPolicy::backoff`](crate::RetryPolicy::backoff)
+    Retry,
+    /// The error is transient, and the server has requested a delay (the `Retry-After` header)
+    RetryAfter(Duration),
+}
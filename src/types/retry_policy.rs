@@ -0,0 +1,53 @@
+use crate::{DEFAULT_HTTP_INITIAL_BACKOFF_MILLIS, DEFAULT_HTTP_MAX_BACKOFF_MILLIS, DEFAULT_HTTP_MAX_RETRIES};
+use core::time::Duration;
+use derive_new::new;
+
+#[derive(new, Eq, PartialEq, Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// The number of retries of a single request (the request is sent at most `max_retries + 1` times)
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Returns a random delay between zero and `min(max_backoff, initial_backoff * 2^attempt)` ("full jitter")
+    ///
+    /// The `random` value is the source of the jitter (see [`random_u64`](crate::random_u64)).
+    pub fn backoff(&self, attempt: u32, random: u64) -> Duration {
+        let multiplier = 2u32.saturating_pow(attempt);
+        let ceiling = self
+            .initial_backoff
+            .saturating_mul(multiplier)
+            .min(self.max_backoff);
+        let ceiling_nanos = u64::try_from(ceiling.as_nanos()).unwrap_or(u64::MAX);
+        let nanos = random
+            .checked_rem(ceiling_nanos.saturating_add(1))
+            .unwrap_or_default();
+        Duration::from_nanos(nanos)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_HTTP_MAX_RETRIES,
+            initial_backoff: Duration::from_millis(DEFAULT_HTTP_INITIAL_BACKOFF_MILLIS),
+            max_backoff: Duration::from_millis(DEFAULT_HTTP_MAX_BACKOFF_MILLIS),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_cap_backoff() {
+        let policy = RetryPolicy::new(10, Duration::from_millis(100), Duration::from_secs(1));
+        assert!(policy.backoff(0, u64::MAX) <= Duration::from_millis(100));
+        assert!(policy.backoff(3, u64::MAX) <= Duration::from_millis(800));
+        assert!(policy.backoff(30, u64::MAX) <= Duration::from_secs(1));
+        assert_eq!(policy.backoff(30, 0), Duration::ZERO);
+    }
+}
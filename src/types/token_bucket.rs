@@ -0,0 +1,71 @@
+use core::time::Duration;
+use std::sync::{Mutex, PoisonError};
+use tokio::time::{Instant, sleep};
+
+/// A token bucket rate limiter that allows `burst` requests at once and refills one token every `interval`
+///
+/// The bucket is implemented as GCRA: instead of the number of tokens, it stores the time when the bucket becomes full again.
+#[derive(Debug)]
+pub struct TokenBucket {
+    pub interval: Duration,
+    pub burst: u32,
+    full_at: Mutex<Instant>,
+}
+
+impl TokenBucket {
+    pub fn new(interval: Duration, burst: u32) -> Self {
+        Self {
+            interval,
+            burst: burst.max(1),
+            full_at: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Returns a bucket that refills `requests_per_second` tokens every second
+    pub fn per_second(requests_per_second: u32, burst: u32) -> Self {
+        let interval = Duration::from_secs(1)
+            .checked_div(requests_per_second.max(1))
+            .unwrap_or_default();
+        Self::new(interval, burst)
+    }
+
+    /// Waits until a token is available, then takes it
+    pub async fn acquire(&self) {
+        while let Err(delay) = self.try_acquire_at(Instant::now()) {
+            sleep(delay).await;
+        }
+    }
+
+    /// Takes a token if it is available at `now`, otherwise returns the delay until the next token
+    pub fn try_acquire_at(&self, now: Instant) -> Result<(), Duration> {
+        let mut full_at = self.full_at.lock().unwrap_or_else(PoisonError::into_inner);
+        let start = (*full_at).max(now);
+        let tolerance = self.interval.saturating_mul(self.burst.saturating_sub(1));
+        let delay = start
+            .saturating_duration_since(now)
+            .saturating_sub(tolerance);
+        if delay.is_zero() {
+            *full_at = start.checked_add(self.interval).unwrap_or(start);
+            Ok(())
+        } else {
+            Err(delay)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_allow_burst_then_wait() {
+        let bucket = TokenBucket::new(Duration::from_secs(1), 2);
+        let now = Instant::now();
+        assert_eq!(bucket.try_acquire_at(now), Ok(()));
+        assert_eq!(bucket.try_acquire_at(now), Ok(()));
+        assert_eq!(bucket.try_acquire_at(now), Err(Duration::from_secs(1)));
+        let later = now.checked_add(Duration::from_secs(1)).unwrap_or(now);
+        assert_eq!(bucket.try_acquire_at(later), Ok(()));
+        assert_eq!(bucket.try_acquire_at(later), Err(Duration::from_secs(1)));
+    }
+}